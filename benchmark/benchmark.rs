@@ -48,6 +48,13 @@ mod Uncompressed {
         options : capnp::message::ReaderOptions) -> capnp::serialize::OwnedSpaceMessageReader {
         capnp::serialize::new_reader(inputStream, options).unwrap()
     }
+
+    pub fn new_scratch_reader<'a, R: capnp::io::BufferedInputStream>(
+        inputStream : &mut R,
+        scratch_space : &'a mut std::vec::Vec<capnp::common::Word>,
+        options : capnp::message::ReaderOptions) -> capnp::serialize::ScratchSpaceMessageReader<'a> {
+        capnp::serialize::new_scratch_space_reader(inputStream, scratch_space, options).unwrap()
+    }
 }
 
 mod Packed {
@@ -73,29 +80,54 @@ mod Packed {
         capnp::serialize_packed::new_reader(inputStream, options).unwrap()
     }
 
+    pub fn new_scratch_reader<'a, R: capnp::io::BufferedInputStream>(
+        inputStream : &mut R,
+        scratch_space : &'a mut std::vec::Vec<capnp::common::Word>,
+        options : capnp::message::ReaderOptions) -> capnp::serialize::ScratchSpaceMessageReader<'a> {
+        capnp::serialize_packed::new_scratch_space_reader(inputStream, scratch_space, options).unwrap()
+    }
+
 }
 
 static SCRATCH_SIZE : uint = 128 * 1024;
 
-pub struct NoScratch;
+pub struct NoScratch {
+    read_buffer : std::vec::Vec<capnp::common::Word>
+}
 
 impl NoScratch {
+    pub fn new() -> NoScratch {
+        NoScratch { read_buffer : std::vec::Vec::new() }
+    }
+
     fn new_builder(&mut self, _idx : uint) -> capnp::message::MallocMessageBuilder {
         capnp::message::MallocMessageBuilder::new_default()
     }
+
+    // Hands out a fresh buffer every time, so each message gets its own allocation.
+    fn read_buffer<'a>(&'a mut self) -> &'a mut std::vec::Vec<capnp::common::Word> {
+        self.read_buffer = std::vec::Vec::new();
+        &mut self.read_buffer
+    }
 }
 
 pub struct UseScratch {
-    scratch_space : std::vec::Vec<capnp::common::Word>
+    scratch_space : std::vec::Vec<capnp::common::Word>,
+    read_buffer : std::vec::Vec<capnp::common::Word>
 }
 
 impl UseScratch {
     pub fn new() -> UseScratch {
         UseScratch {
-            scratch_space : capnp::common::allocate_zeroed_words(SCRATCH_SIZE * 6)
+            scratch_space : capnp::common::allocate_zeroed_words(SCRATCH_SIZE * 6),
+            read_buffer : capnp::common::allocate_zeroed_words(SCRATCH_SIZE)
         }
     }
 
+    fn read_buffer<'a>(&'a mut self) -> &'a mut std::vec::Vec<capnp::common::Word> {
+        &mut self.read_buffer
+    }
+
     fn new_builder<'a>(&mut self, idx : uint) -> capnp::message::ScratchSpaceMallocMessageBuilder<'a> {
         assert!(idx < 6);
         unsafe {
@@ -153,7 +185,7 @@ macro_rules! pass_by_bytes(
                     &mut capnp::io::ArrayInputStream::new(requestBytes.as_slice()),
                     capnp::message::DefaultReaderOptions);
 
-                let requestReader : $testcase::RequestReader = messageReader.get_root();
+                let requestReader : $testcase::RequestReader = messageReader.get_root().unwrap();
                 $testcase::handle_request(requestReader, response);
 
                 {
@@ -165,7 +197,7 @@ macro_rules! pass_by_bytes(
                     &mut capnp::io::ArrayInputStream::new(responseBytes.as_slice()),
                     capnp::message::DefaultReaderOptions);
 
-                let responseReader : $testcase::ResponseReader = messageReader.get_root();
+                let responseReader : $testcase::ResponseReader = messageReader.get_root().unwrap();
                 if !$testcase::check_response(responseReader, expected) {
                     fail!("Incorrect response.");
                 }
@@ -181,10 +213,11 @@ macro_rules! server(
                 let mut messageRes = $reuse.new_builder(0);
 
                 let response = messageRes.init_root::<$testcase::ResponseBuilder>();
-                let messageReader = $compression::new_buffered_reader(
+                let messageReader = $compression::new_scratch_reader(
                     &mut inBuffered,
+                    $reuse.read_buffer(),
                     capnp::message::DefaultReaderOptions);
-                let requestReader : $testcase::RequestReader = messageReader.get_root();
+                let requestReader : $testcase::RequestReader = messageReader.get_root().unwrap();
                 $testcase::handle_request(requestReader, response);
 
                 $compression::write_buffered(&mut outBuffered, &messageRes);
@@ -207,10 +240,11 @@ macro_rules! sync_client(
                 let expected = $testcase::setup_request(&mut rng, request);
                 $compression::write(&mut outStream, &messageReq);
 
-                let messageReader = $compression::new_buffered_reader(
+                let messageReader = $compression::new_scratch_reader(
                     &mut inBuffered,
+                    $reuse.read_buffer(),
                     capnp::message::DefaultReaderOptions);
-                let responseReader : $testcase::ResponseReader = messageReader.get_root();
+                let responseReader : $testcase::ResponseReader = messageReader.get_root().unwrap();
                 assert!($testcase::check_response(responseReader, expected));
 
             }
@@ -277,7 +311,7 @@ macro_rules! do_testcase2(
     ( $testcase:expr, $mode:expr, $reuse:expr, $compression:ident, $iters:expr) => ({
             match $reuse.as_slice() {
                 "no-reuse" => {
-                    let mut scratch = NoScratch;
+                    let mut scratch = NoScratch::new();
                     do_testcase1!($testcase, $mode, scratch, $compression, $iters)
                 }
                 "reuse" => {
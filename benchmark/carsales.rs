@@ -27,7 +27,7 @@ macro_rules! car_value_impl(
                     result += self.get_doors() as u64 * 350;
 
                     // TODO Lists should have iterators.
-                    let wheels = self.get_wheels();
+                    let wheels = self.get_wheels().unwrap();
                     for i in range(0, wheels.size()) {
                         let wheel = wheels[i];
                         result += wheel.get_diameter() as u64 * wheel.get_diameter() as u64;
@@ -36,7 +36,7 @@ macro_rules! car_value_impl(
 
                     result += self.get_length() as u64 * self.get_width() as u64 * self.get_height() as u64 / 50;
 
-                    let engine = self.get_engine();
+                    let engine = self.get_engine().unwrap();
                     result += engine.get_horsepower() as u64 * 40;
                     if engine.get_uses_electric() {
                         if engine.get_uses_gas() {
@@ -121,7 +121,7 @@ pub fn setup_request(rng : &mut FastRand, request : ParkingLot::Builder) -> u64
 
 pub fn handle_request(request : ParkingLot::Reader, response : TotalValue::Builder) {
     let mut result = 0;
-    let cars = request.get_cars();
+    let cars = request.get_cars().unwrap();
     for i in range(0, cars.size()) {
         result += cars[i].car_value();
     }
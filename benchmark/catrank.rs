@@ -73,14 +73,14 @@ pub fn handle_request(request : SearchResultList::Reader,
                      response : SearchResultList::Builder) {
     let mut scoredResults : Vec<ScoredResult> = Vec::new();
 
-    let results = request.get_results();
+    let results = request.get_results().unwrap();
     for i in range(0, results.size()) {
         let result = results[i];
         let mut score = result.get_score();
-        if result.get_snippet().contains(" cat ") {
+        if result.get_snippet().unwrap().contains(" cat ") {
             score *= 10000.0;
         }
-        if result.get_snippet().contains(" dog ") {
+        if result.get_snippet().unwrap().contains(" dog ") {
             score /= 10000.0;
         }
         scoredResults.push(ScoredResult {score : score, result : result});
@@ -93,14 +93,14 @@ pub fn handle_request(request : SearchResultList::Reader,
         let item = list[i];
         let result = scoredResults.get(i);
         item.set_score(result.score);
-        item.set_url(result.result.get_url());
-        item.set_snippet(result.result.get_snippet());
+        item.set_url(result.result.get_url().unwrap());
+        item.set_snippet(result.result.get_snippet().unwrap());
     }
 }
 
 pub fn check_response(response : SearchResultList::Reader, expectedGoodCount : int) -> bool {
     let mut goodCount : int = 0;
-    let results = response.get_results();
+    let results = response.get_results().unwrap();
     for i in range(0, results.size()) {
         let result = results[i];
         if result.get_score() > 1001.0 {
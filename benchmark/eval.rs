@@ -49,12 +49,12 @@ fn make_expression(rng : &mut FastRand, exp : Expression::Builder, depth : u32)
 fn evaluate_expression(exp : Expression::Reader) -> i32 {
     let left = match exp.get_left().which() {
         Some(Expression::Left::Value(v)) => v,
-        Some(Expression::Left::Expression(e)) => evaluate_expression(e),
+        Some(Expression::Left::Expression(e)) => evaluate_expression(e.unwrap()),
         None => fail!("impossible")
     };
     let right = match exp.get_right().which() {
         Some(Expression::Right::Value(v)) => v,
-        Some(Expression::Right::Expression(e)) => evaluate_expression(e),
+        Some(Expression::Right::Expression(e)) => evaluate_expression(e.unwrap()),
         None => fail!("impossible")
     };
 
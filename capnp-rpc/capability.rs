@@ -83,9 +83,9 @@ pub trait InitRequest<'a, T> {
 impl <'a, Params : FromStructBuilder<'a> + HasStructSize, Results, Pipeline> InitRequest<'a, Params>
 for Request<Params, Results, Pipeline> {
     fn init(&'a mut self) -> Params {
-        let message : Message::Builder = self.hook.message().get_root();
+        let message : Message::Builder = self.hook.message().get_root().unwrap();
         match message.which() {
-            Some(Message::Call(call)) => {
+            Some(Message::Call(Ok(call))) => {
                 let params = call.init_params();
                 params.get_content().init_as_struct()
             }
@@ -106,15 +106,18 @@ for ResultFuture<Results, Pipeline> {
         match self.answer_result {
             Err(_) => Err("answer channel closed".to_string()),
             Ok(ref mut response_hook) => {
-                let root : Message::Reader = response_hook.get().get_as_struct();
+                let root : Message::Reader = match response_hook.get().and_then(|r| r.get_as_struct()) {
+                    Ok(root) => root,
+                    Err(e) => return Err(e.to_string()),
+                };
                 match root.which() {
-                    Some(Message::Return(ret)) => {
+                    Some(Message::Return(Ok(ret))) => {
                         match ret.which() {
-                            Some(Return::Results(res)) => {
-                                Ok(res.get_content().get_as_struct())
+                            Some(Return::Results(Ok(res))) => {
+                                res.get_content().get_as_struct().map_err(|e| e.to_string())
                             }
-                            Some(Return::Exception(e)) => {
-                                Err(e.get_reason().to_string())
+                            Some(Return::Exception(Ok(e))) => {
+                                Err(e.get_reason().unwrap_or("").to_string())
                             }
                             _ => fail!(),
                         }
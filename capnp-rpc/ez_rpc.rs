@@ -50,12 +50,12 @@ impl EzRpcClient {
         self.rpc_chan.send(Outgoing(outgoing));
 
         let mut response_hook = answer_port.recv();
-        let message : Message::Reader = response_hook.get().get_as_struct();
+        let message : Message::Reader = response_hook.get().and_then(|r| r.get_as_struct()).unwrap();
         let client = match message.which() {
-            Some(Message::Return(ret)) => {
+            Some(Message::Return(Ok(ret))) => {
                 match ret.which() {
-                    Some(Return::Results(payload)) => {
-                        payload.get_content().get_as_capability::<T>()
+                    Some(Return::Results(Ok(payload))) => {
+                        payload.get_content().get_as_capability::<T>().unwrap()
                     }
                     _ => { fail!() }
                 }
@@ -112,8 +112,12 @@ impl Restorer {
 
 impl SturdyRefRestorer for Restorer {
     fn restore(&self, obj_id : AnyPointer::Reader) -> Option<Box<ClientHook:Send>> {
+        let name = match obj_id.get_as_text() {
+            Ok(name) => name,
+            Err(_e) => return None,
+        };
         let (tx, rx) = std::comm::channel();
-        self.sender.send(ExportEventRestore(obj_id.get_as_text().to_string(), tx));
+        self.sender.send(ExportEventRestore(name.to_string(), tx));
         return rx.recv();
     }
 }
@@ -10,6 +10,7 @@ use capnp::capability::{CallContextHook, ClientHook, PipelineHook, PipelineOp, R
                         RequestHook, Request, ResponseHook};
 use capnp::common;
 use capnp::{ReaderOptions, MessageReader, BuilderOptions, MessageBuilder, MallocMessageBuilder};
+use capnp::Result;
 use capnp::serialize;
 use capnp::OwnedSpaceMessageReader;
 
@@ -97,14 +98,17 @@ impl AnswerRef {
 
     fn do_call(answer_message : &mut Box<MallocMessageBuilder>, interface_id : u64, method_id : u16,
                ops : Vec<PipelineOp::Type>, context : Box<CallContextHook:Send>) {
-        let root : Message::Builder = answer_message.get_root();
+        let root : Message::Builder = answer_message.get_root().unwrap();
         match root.which() {
-            Some(Message::Return(ret)) => {
+            Some(Message::Return(Ok(ret))) => {
                 match ret.which() {
-                    Some(Return::Results(payload)) => {
-                        let hook = payload.get_content().as_reader().
-                            get_pipelined_cap(ops.as_slice());
-                        hook.call(interface_id, method_id, context);
+                    Some(Return::Results(Ok(payload))) => {
+                        match payload.get_content().as_reader().get_pipelined_cap(ops.as_slice()) {
+                            Ok(hook) => hook.call(interface_id, method_id, context),
+                            Err(_e) => {
+                                // TODO
+                            }
+                        }
                     }
                     Some(Return::Exception(_exc)) => {
                         // TODO
@@ -252,7 +256,10 @@ fn client_hooks_of_payload(payload : Payload::Reader,
                            rpc_chan : &std::comm::Sender<RpcEvent>,
                            answers : &ImportTable<Answer>) -> Vec<Option<Box<ClientHook:Send>>> {
     let mut result = Vec::new();
-    let cap_table = payload.get_cap_table();
+    let cap_table = match payload.get_cap_table() {
+        Ok(cap_table) => cap_table,
+        Err(_e) => return result,
+    };
     for ii in range(0, cap_table.size()) {
         match cap_table[ii].which() {
             Some(CapDescriptor::None(())) => {
@@ -272,7 +279,7 @@ fn client_hooks_of_payload(payload : Payload::Reader,
             Some(CapDescriptor::ReceiverHosted(_id)) => {
                 fail!()
             }
-            Some(CapDescriptor::ReceiverAnswer(promised_answer)) => {
+            Some(CapDescriptor::ReceiverAnswer(Ok(promised_answer))) => {
                 result.push(Some(
                         (box PromisedAnswerClient {
                                 rpc_chan : rpc_chan.clone(),
@@ -284,6 +291,7 @@ fn client_hooks_of_payload(payload : Payload::Reader,
             Some(CapDescriptor::ThirdPartyHosted(_)) => {
                 fail!()
             }
+            Some(CapDescriptor::ReceiverAnswer(Err(e))) => { fail!("malformed cap descriptor: {}", e)}
             None => { fail!("unknown cap descriptor")}
         }
     }
@@ -295,30 +303,31 @@ fn populate_cap_table(message : &mut OwnedSpaceMessageReader,
                       answers : &ImportTable<Answer>) {
     let mut the_cap_table : Vec<Option<Box<ClientHook:Send>>> = Vec::new();
     {
-        let root = message.get_root::<Message::Reader>();
-
-        match root.which() {
-            Some(Message::Return(ret)) => {
-                match ret.which() {
-                    Some(Return::Results(payload)) => {
-                        the_cap_table = client_hooks_of_payload(payload, rpc_chan, answers);
+        match message.get_root::<Message::Reader>() {
+            Ok(root) => match root.which() {
+                Some(Message::Return(Ok(ret))) => {
+                    match ret.which() {
+                        Some(Return::Results(Ok(payload))) => {
+                            the_cap_table = client_hooks_of_payload(payload, rpc_chan, answers);
+                        }
+                        Some(Return::Exception(_e)) => {
+                        }
+                        _ => {}
                     }
-                    Some(Return::Exception(_e)) => {
+
+                }
+                Some(Message::Call(Ok(call))) => {
+                    match call.get_params() {
+                        Ok(params) => {
+                            the_cap_table = client_hooks_of_payload(params, rpc_chan, answers);
+                        }
+                        Err(_e) => {}
                     }
-                    _ => {}
                 }
-
-            }
-            Some(Message::Call(call)) => {
-               the_cap_table = client_hooks_of_payload(call.get_params(), rpc_chan, answers);
-            }
-            Some(Message::Unimplemented(_)) => {
-            }
-            Some(Message::Abort(_exc)) => {
-            }
-            None => {
-            }
-            _ => {
+                _ => {
+                }
+            },
+            Err(_e) => {
             }
         }
     }
@@ -327,7 +336,10 @@ fn populate_cap_table(message : &mut OwnedSpaceMessageReader,
 
 fn get_pipeline_ops(promised_answer : PromisedAnswer::Reader) -> Vec<PipelineOp::Type> {
     let mut result = Vec::new();
-    let transform = promised_answer.get_transform();
+    let transform = match promised_answer.get_transform() {
+        Ok(transform) => transform,
+        Err(_e) => return result,
+    };
     for ii in range(0, transform.size()) {
         match transform[ii].which() {
             Some(PromisedAnswer::Op::Noop(())) => result.push(PipelineOp::Noop),
@@ -402,36 +414,42 @@ impl RpcConnectionState {
                         }
 
                         populate_cap_table(message, &rpc_chan, &answers);
-                        let root = message.get_root::<Message::Reader>();
+                        let root = match message.get_root::<Message::Reader>() {
+                            Ok(root) => root,
+                            Err(e) => {
+                                println!("malformed message: {}", e);
+                                continue;
+                            }
+                        };
                         let receiver = match root.which() {
                             Some(Message::Unimplemented(_)) => {
                                 println!("unimplemented");
                                 Nobody
                             }
-                            Some(Message::Abort(exc)) => {
-                                println!("abort: {}", exc.get_reason());
+                            Some(Message::Abort(Ok(exc))) => {
+                                println!("abort: {}", exc.get_reason().unwrap_or(""));
                                 Nobody
                             }
-                            Some(Message::Call(call)) => {
-                                match call.get_target().which() {
+                            Some(Message::Call(Ok(call))) => {
+                                match call.get_target().ok().and_then(|target| target.which()) {
                                     Some(MessageTarget::ImportedCap(import_id)) => {
                                         ExportReceiver(import_id)
                                     }
-                                    Some(MessageTarget::PromisedAnswer(promised_answer)) => {
+                                    Some(MessageTarget::PromisedAnswer(Ok(promised_answer))) => {
                                         PromisedAnswerReceiver(
                                             promised_answer.get_question_id(),
                                             get_pipeline_ops(promised_answer))
                                     }
-                                    None => {
+                                    _ => {
                                         fail!("call targets something else");
                                     }
                                 }
                             }
 
-                            Some(Message::Return(ret)) => {
+                            Some(Message::Return(Ok(ret))) => {
                                 QuestionReceiver(ret.get_answer_id())
                             }
-                            Some(Message::Finish(finish)) => {
+                            Some(Message::Finish(Ok(finish))) => {
                                 println!("finish");
                                 answers.slots.remove(&finish.get_question_id());
                                 finish.get_release_result_caps();
@@ -442,7 +460,7 @@ impl RpcConnectionState {
                                 println!("resolve");
                                 Nobody
                             }
-                            Some(Message::Release(rel)) => {
+                            Some(Message::Release(Ok(rel))) => {
                                 if rel.get_reference_count() == 1 {
                                     exports.erase(rel.get_id());
                                 } else {
@@ -457,7 +475,7 @@ impl RpcConnectionState {
                             Some(Message::Save(_save)) => {
                                 Nobody
                             }
-                            Some(Message::Restore(restore)) => {
+                            Some(Message::Restore(Ok(restore))) => {
                                 let clienthook = restorer.restore(restore.get_object_id()).unwrap();
                                 let idx = exports.push(Export::new(clienthook.copy()));
 
@@ -469,7 +487,7 @@ impl RpcConnectionState {
                                     ret.set_answer_id(answer_id);
                                     let payload = ret.init_results();
                                     payload.init_cap_table(1);
-                                    payload.get_cap_table()[0].set_sender_hosted(idx as u32);
+                                    payload.get_cap_table().unwrap()[0].set_sender_hosted(idx as u32);
                                     payload.get_content().set_as_capability(clienthook);
 
                                 }
@@ -492,6 +510,10 @@ impl RpcConnectionState {
                             Some(Message::Join(_join)) => {
                                 Nobody
                             }
+                            Some(_) => {
+                                println!("malformed message");
+                                Nobody
+                            }
                             None => {
                                 println!("unknown message");
                                 Nobody
@@ -499,9 +521,9 @@ impl RpcConnectionState {
                         };
 
                         fn get_call_ids(message : &OwnedSpaceMessageReader) -> (QuestionId, u64, u16) {
-                            let root : Message::Reader = message.get_root();
+                            let root : Message::Reader = message.get_root().unwrap();
                             match root.which() {
-                                Some(Message::Call(call)) =>
+                                Some(Message::Call(Ok(call))) =>
                                     (call.get_question_id(), call.get_interface_id(), call.get_method_id()),
                                 _ => fail!(),
                             }
@@ -562,18 +584,18 @@ impl RpcConnectionState {
                     Outgoing(OutgoingMessage { message : mut m,
                                                answer_chan,
                                                question_chan} ) => {
-                        let root = m.get_root::<Message::Builder>();
+                        let root = m.get_root::<Message::Builder>().unwrap();
                         // add a question to the question table
                         match root.which() {
                             Some(Message::Return(_)) => {}
-                            Some(Message::Call(call)) => {
+                            Some(Message::Call(Ok(call))) => {
                                 let (question, ref_count) = Question::new(answer_chan);
                                 let id = questions.push(question);
                                 call.set_question_id(id);
                                 let qref = QuestionRef::new(id, ref_count, rpc_chan.clone());
                                 if !question_chan.send_opt(qref).is_ok() { fail!() }
                             }
-                            Some(Message::Restore(res)) => {
+                            Some(Message::Restore(Ok(res))) => {
                                 let (question, ref_count) = Question::new(answer_chan);
                                 let id = questions.push(question);
                                 res.set_question_id(id);
@@ -610,9 +632,9 @@ impl RpcConnectionState {
                     ReturnEvent(mut message) => {
                         serialize::write_message(&mut outpipe, message).is_ok();
 
-                        let root = message.get_root::<Message::Builder>();
+                        let root = message.get_root::<Message::Builder>().unwrap();
                         let answer_id_opt = match root.which() {
-                            Some(Message::Return(ret)) => {
+                            Some(Message::Return(Ok(ret))) => {
                                 Some(ret.get_answer_id())
                             }
                             _ => {None}
@@ -657,7 +679,7 @@ impl ClientHook for ImportClient {
                 -> capability::Request<AnyPointer::Builder, AnyPointer::Reader, AnyPointer::Pipeline> {
         let mut message = box MallocMessageBuilder::new(*BuilderOptions::new().fail_fast(false));
         {
-            let root : Message::Builder = message.get_root();
+            let root : Message::Builder = message.init_root();
             let call = root.init_call();
             call.set_interface_id(interface_id);
             call.set_method_id(method_id);
@@ -698,7 +720,7 @@ impl ClientHook for PipelineClient {
                 -> capability::Request<AnyPointer::Builder, AnyPointer::Reader, AnyPointer::Pipeline> {
         let mut message = box MallocMessageBuilder::new(*BuilderOptions::new().fail_fast(false));
         {
-            let root : Message::Builder = message.get_root();
+            let root : Message::Builder = message.init_root();
             let call = root.init_call();
             call.set_interface_id(interface_id);
             call.set_method_id(method_id);
@@ -747,7 +769,7 @@ impl ClientHook for PromisedAnswerClient {
                 -> capability::Request<AnyPointer::Builder, AnyPointer::Reader, AnyPointer::Pipeline> {
         let mut message = box MallocMessageBuilder::new(*BuilderOptions::new().fail_fast(false));
         {
-            let root : Message::Builder = message.get_root();
+            let root : Message::Builder = message.init_root();
             let call = root.init_call();
             call.set_interface_id(interface_id);
             call.set_method_id(method_id);
@@ -822,14 +844,14 @@ fn write_outgoing_cap_table(rpc_chan : &std::comm::Sender<RpcEvent>, message : &
         }
         caps
     };
-    let root : Message::Builder = message.get_root();
+    let root : Message::Builder = message.get_root().unwrap();
     match root.which() {
-        Some(Message::Call(call)) => {
-            write_payload(rpc_chan, cap_table.as_slice(), call.get_params())
+        Some(Message::Call(Ok(call))) => {
+            write_payload(rpc_chan, cap_table.as_slice(), call.get_params().unwrap())
         }
-        Some(Message::Return(ret)) => {
+        Some(Message::Return(Ok(ret))) => {
             match ret.which() {
-                Some(Return::Results(payload)) => {
+                Some(Return::Results(Ok(payload))) => {
                     write_payload(rpc_chan, cap_table.as_slice(), payload);
                 }
                 _ => {}
@@ -850,7 +872,7 @@ impl RpcResponse {
 }
 
 impl ResponseHook for RpcResponse {
-    fn get<'a>(&'a mut self) -> AnyPointer::Reader<'a> {
+    fn get<'a>(&'a mut self) -> Result<AnyPointer::Reader<'a>> {
         self.message.get_root_internal()
     }
 }
@@ -897,9 +919,9 @@ impl RequestHook for PromisedAnswerRpcRequest {
         let box PromisedAnswerRpcRequest { rpc_chan, mut message, mut answer_ref, ops } = self;
         let (answer_tx, answer_rx) = std::comm::channel();
 
-        let root = message.get_root::<Message::Builder>();
+        let root = message.get_root::<Message::Builder>().unwrap();
         let (interface_id, method_id) = match root.which() {
-            Some(Message::Call(call)) => {
+            Some(Message::Call(Ok(call))) => {
                 (call.get_interface_id(), call.get_method_id())
             }
             _ => {
@@ -984,9 +1006,9 @@ impl RpcCallContext {
     pub fn new(params_message : Box<OwnedSpaceMessageReader>,
                rpc_chan : std::comm::Sender<RpcEvent>) -> RpcCallContext {
         let answer_id = {
-            let root : Message::Reader = params_message.get_root();
+            let root : Message::Reader = params_message.get_root().unwrap();
             match root.which() {
-                Some(Message::Call(call)) => {
+                Some(Message::Call(Ok(call))) => {
                     call.get_question_id()
                 }
                 _ => fail!(),
@@ -1009,25 +1031,25 @@ impl RpcCallContext {
 }
 
 impl CallContextHook for RpcCallContext {
-    fn get<'a>(&'a mut self) -> (AnyPointer::Reader<'a>, AnyPointer::Builder<'a>) {
+    fn get<'a>(&'a mut self) -> Result<(AnyPointer::Reader<'a>, AnyPointer::Builder<'a>)> {
 
         let params = {
-            let root : Message::Reader = self.params_message.get_root();
+            let root : Message::Reader = try!(self.params_message.get_root());
             match root.which() {
                 Some(Message::Call(call)) => {
-                    call.get_params().get_content()
+                    try!(try!(call).get_params()).get_content()
                 }
                 _ => fail!(),
             }
         };
 
         let results = {
-            let root : Message::Builder = self.results_message.get_root();
+            let root : Message::Builder = try!(self.results_message.get_root());
             match root.which() {
                 Some(Message::Return(ret)) => {
-                    match ret.which() {
+                    match try!(ret).which() {
                         Some(Return::Results(results)) => {
-                            results.get_content()
+                            try!(results).get_content()
                         }
                         _ => fail!(),
                     }
@@ -1036,7 +1058,7 @@ impl CallContextHook for RpcCallContext {
             }
         };
 
-        (params, results)
+        Ok((params, results))
     }
     fn fail(mut ~self) {
         self.aborter.succeeded = false;
@@ -1062,8 +1084,8 @@ impl LocalResponse {
 }
 
 impl ResponseHook for LocalResponse {
-    fn get<'a>(&'a mut self) -> AnyPointer::Reader<'a> {
-        self.message.get_root_internal().as_reader()
+    fn get<'a>(&'a mut self) -> Result<AnyPointer::Reader<'a>> {
+        Ok(self.message.get_root_internal().as_reader())
     }
 }
 
@@ -1098,25 +1120,25 @@ impl PromisedAnswerRpcCallContext {
 }
 
 impl CallContextHook for PromisedAnswerRpcCallContext {
-    fn get<'a>(&'a mut self) -> (AnyPointer::Reader<'a>, AnyPointer::Builder<'a>) {
+    fn get<'a>(&'a mut self) -> Result<(AnyPointer::Reader<'a>, AnyPointer::Builder<'a>)> {
 
         let params = {
-            let root : Message::Builder = self.params_message.get_root();
+            let root : Message::Builder = try!(self.params_message.get_root());
             match root.which() {
                 Some(Message::Call(call)) => {
-                    call.get_params().get_content().as_reader()
+                    try!(try!(call).get_params()).get_content().as_reader()
                 }
                 _ => fail!(),
             }
         };
 
         let results = {
-            let root : Message::Builder = self.results_message.get_root();
+            let root : Message::Builder = try!(self.results_message.get_root());
             match root.which() {
                 Some(Message::Return(ret)) => {
-                    match ret.which() {
+                    match try!(ret).which() {
                         Some(Return::Results(results)) => {
-                            results.get_content()
+                            try!(results).get_content()
                         }
                         _ => fail!(),
                     }
@@ -1125,15 +1147,15 @@ impl CallContextHook for PromisedAnswerRpcCallContext {
             }
         };
 
-        (params, results)
+        Ok((params, results))
     }
     fn fail(~self) {
         let box PromisedAnswerRpcCallContext {
             params_message : _, mut results_message, rpc_chan : _, answer_chan} = self;
 
-        let message : Message::Builder = results_message.get_root();
+        let message : Message::Builder = results_message.get_root().unwrap();
         match message.which() {
-            Some(Message::Return(ret)) => {
+            Some(Message::Return(Ok(ret))) => {
                 let exc = ret.init_exception();
                 exc.set_reason("aborted");
             }
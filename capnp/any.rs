@@ -12,6 +12,11 @@ pub mod AnyPointer {
     use layout::{PointerReader, PointerBuilder, FromStructReader, FromStructBuilder,
                  HasStructSize, ToStructReader};
     use blob::{Text, Data};
+    use common::{MessageSize, Word};
+    use error::Result;
+    use list::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::Orphanage;
+    use visitor::Visitor;
 
     pub struct Reader<'a> {
         reader : PointerReader<'a>
@@ -29,33 +34,59 @@ pub mod AnyPointer {
         }
 
         #[inline]
-        pub fn get_as_struct<T : FromStructReader<'a>>(&self) -> T {
-            FromStructReader::new(self.reader.get_struct(std::ptr::null()))
+        pub fn get_pointer(&self) -> PointerReader<'a> {
+            self.reader
         }
 
-        pub fn get_as_text(&self) -> Text::Reader<'a> {
+        pub fn total_size(&self) -> Result<MessageSize> {
+            self.reader.total_size()
+        }
+
+        // Walks the pointed-to object and everything reachable from it.
+        pub fn visit(&self, visitor : &mut Visitor) -> Result<()> {
+            self.reader.visit(visitor)
+        }
+
+        pub fn canonicalize(&self) -> Result<Vec<Word>> {
+            self.reader.canonicalize()
+        }
+
+        pub fn equals(&self, other : &Reader) -> Result<bool> {
+            self.reader.equals(&other.reader)
+        }
+
+        pub fn stable_hash(&self) -> Result<u64> {
+            self.reader.stable_hash()
+        }
+
+        #[inline]
+        pub fn get_as_struct<T : FromStructReader<'a>>(&self) -> Result<T> {
+            Ok(FromStructReader::new(try!(self.reader.get_struct(std::ptr::null()))))
+        }
+
+        pub fn get_as_text(&self) -> Result<Text::Reader<'a>> {
             self.reader.get_text(std::ptr::null(), 0)
         }
 
-        pub fn get_as_data(&self) -> Data::Reader<'a> {
+        pub fn get_as_data(&self) -> Result<Data::Reader<'a>> {
             self.reader.get_data(std::ptr::null(), 0)
         }
 
-        pub fn get_as_capability<T : FromClientHook>(&self) -> T {
-            FromClientHook::new(self.reader.get_capability())
+        pub fn get_as_capability<T : FromClientHook>(&self) -> Result<T> {
+            Ok(FromClientHook::new(try!(self.reader.get_capability())))
         }
 
 
         //# Used by RPC system to implement pipelining. Applications
         //# generally shouldn't use this directly.
-        pub fn get_pipelined_cap(&self, ops : &[PipelineOp::Type]) -> Box<ClientHook:Send> {
+        pub fn get_pipelined_cap(&self, ops : &[PipelineOp::Type]) -> Result<Box<ClientHook:Send>> {
             let mut pointer = self.reader;
 
             for op in ops.iter() {
                 match op {
                     &PipelineOp::Noop =>  { }
                     &PipelineOp::GetPointerField(idx) => {
-                        pointer = pointer.get_struct(std::ptr::null()).get_pointer_field(idx as uint)
+                        pointer = try!(pointer.get_struct(std::ptr::null())).get_pointer_field(idx as uint)
                     }
                 }
             }
@@ -64,6 +95,12 @@ pub mod AnyPointer {
         }
     }
 
+    impl <'a> FromPointerReader<'a> for Reader<'a> {
+        fn get_from_pointer(reader : &PointerReader<'a>, _default_value : *Word) -> Result<Reader<'a>> {
+            Ok(Reader::new(*reader))
+        }
+    }
+
     pub struct Builder<'a> {
         builder : PointerBuilder<'a>
     }
@@ -74,9 +111,9 @@ pub mod AnyPointer {
             Builder { builder : builder }
         }
 
-        pub fn get_as_struct<T : FromStructBuilder<'a> + HasStructSize>(&self) -> T {
-            FromStructBuilder::new(
-                self.builder.get_struct(HasStructSize::struct_size(None::<T>), std::ptr::null()))
+        pub fn get_as_struct<T : FromStructBuilder<'a> + HasStructSize>(&self) -> Result<T> {
+            Ok(FromStructBuilder::new(
+                try!(self.builder.get_struct(HasStructSize::struct_size(None::<T>), std::ptr::null()))))
         }
 
         pub fn init_as_struct<T : FromStructBuilder<'a> + HasStructSize>(&self) -> T {
@@ -85,8 +122,8 @@ pub mod AnyPointer {
                     HasStructSize::struct_size(None::<T>)))
         }
 
-        pub fn set_as_struct<T : ToStructReader<'a>>(&self, value : &T) {
-            self.builder.set_struct(&value.struct_reader());
+        pub fn set_as_struct<T : ToStructReader<'a>>(&self, value : &T) -> Result<()> {
+            self.builder.set_struct(&value.struct_reader())
         }
 
         // XXX value should be a user client.
@@ -102,17 +139,45 @@ pub mod AnyPointer {
             self.builder.set_data(value);
         }
 
+        pub fn set_as(&self, value : Reader) -> Result<()> {
+            self.builder.copy_from(value.reader)
+        }
+
         #[inline]
         pub fn clear(&self) {
             self.builder.clear()
         }
 
+        #[inline]
+        pub fn get_pointer(&self) -> PointerBuilder<'a> {
+            self.builder
+        }
+
+        // An orphanage for the message that contains this pointer.
+        pub fn get_orphanage(&self) -> Orphanage<'a> {
+            Orphanage::new(self.builder.get_arena())
+        }
+
         #[inline]
         pub fn as_reader(&self) -> Reader<'a> {
             Reader { reader : self.builder.as_reader() }
         }
     }
 
+    impl <'a> FromPointerBuilder<'a> for Builder<'a> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, _default_value : *Word) -> Result<Builder<'a>> {
+            Ok(Builder::new(builder))
+        }
+    }
+
+    impl <'a> InitPointerBuilder<'a> for Builder<'a> {
+        fn init_pointer(builder : PointerBuilder<'a>, _size : uint) -> Builder<'a> {
+            let result = Builder::new(builder);
+            result.clear();
+            result
+        }
+    }
+
     pub struct Pipeline {
         hook : Box<PipelineHook>,
         ops : Vec<PipelineOp::Type>,
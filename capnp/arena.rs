@@ -5,15 +5,46 @@
  */
 
 use std;
+use std::cell::Cell;
 use std::vec::Vec;
-use libc;
 use capability::ClientHook;
 use common::*;
 use common::ptr_sub;
+use error::{Result, SegmentLoadFailed};
 use message;
 
 pub type SegmentId = u32;
 
+pub struct ReadLimiter {
+    pub limit : Cell<u64>,
+}
+
+impl ReadLimiter {
+    pub fn new(limit : u64) -> ReadLimiter {
+        ReadLimiter { limit : Cell::new(limit) }
+    }
+
+    #[inline]
+    pub fn can_read(&self, amount : u64) -> bool {
+        let current = self.limit.get();
+        if amount > current {
+            false
+        } else {
+            self.limit.set(current - amount);
+            true
+        }
+    }
+
+    pub fn unread(&self, amount : u64) {
+        // Be careful not to overflow here.
+        let old_value = self.limit.get();
+        let new_value = old_value + amount;
+        if new_value > old_value {
+            self.limit.set(new_value);
+        }
+    }
+}
+
 pub struct SegmentReader {
     pub arena : ArenaPtr,
     pub ptr : *Word,
@@ -32,7 +63,18 @@ impl SegmentReader {
         let thisBegin : uint = self.ptr.to_uint();
         let thisEnd : uint = unsafe { self.ptr.offset(self.size as int).to_uint() };
         return from.to_uint() >= thisBegin && to.to_uint() <= thisEnd && from.to_uint() <= to.to_uint();
-        // TODO readLimiter
+    }
+
+    // Charges `virtual_amount` words against the arena's read limit. Returns
+    // false if the limit has been exhausted.
+    #[inline]
+    pub fn amplified_read(&self, virtual_amount : u64) -> bool {
+        self.arena.can_read(virtual_amount)
+    }
+
+    #[inline]
+    pub fn unread(&self, amount : u64) {
+        self.arena.unread(amount)
     }
 }
 
@@ -83,6 +125,15 @@ impl SegmentBuilder {
         }
     }
 
+    // Zeroes the used part of the segment and makes all of it available again.
+    pub fn reset(&mut self) {
+        unsafe {
+            let start : *mut Word = std::mem::transmute(self.reader.ptr);
+            std::ptr::zero_memory(start, self.current_size());
+            self.pos = start;
+        }
+    }
+
     #[inline]
     pub fn get_ptr_unchecked(&self, offset : WordCount) -> *mut Word {
         unsafe {
@@ -111,6 +162,14 @@ pub struct ReaderArena {
 
     pub cap_table : Vec<Option<Box<ClientHook:Send>>>,
 
+    pub read_limiter : ReadLimiter,
+
+    // If set, called before any segment other than the first is
+    // accessed, to make sure that the segment has been read in.
+    // Returns false if the segments could not be read. The loader keeps
+    // the underlying I/O error for its owner to report.
+    pub segment_loader : Option<(unsafe fn(*mut u8) -> bool, *mut u8)>,
+
     pub fail_fast : bool,
 }
 
@@ -125,6 +184,8 @@ impl ReaderArena {
             },
             more_segments : Vec::new(),
             cap_table : Vec::new(),
+            read_limiter : ReadLimiter::new(options.traversal_limit_in_words),
+            segment_loader : None,
             fail_fast : options.fail_fast,
         };
 
@@ -149,11 +210,26 @@ impl ReaderArena {
         arena
     }
 
-    pub fn try_get_segment(&self, id : SegmentId) -> *SegmentReader {
+    // Returns null if there is no segment with the given id, and
+    // SegmentLoadFailed if the segment could not be read in.
+    pub fn try_get_segment(&self, id : SegmentId) -> Result<*SegmentReader> {
         if id == 0 {
-            return &self.segment0 as *SegmentReader;
+            return Ok(&self.segment0 as *SegmentReader);
+        }
+
+        match self.segment_loader {
+            Some((load, context)) => {
+                if !unsafe { load(context) } {
+                    return Err(SegmentLoadFailed);
+                }
+            }
+            None => {}
+        }
+
+        if (id as uint) <= self.more_segments.len() {
+            Ok(&self.more_segments.as_slice()[id as uint - 1] as *SegmentReader)
         } else {
-            unsafe { self.more_segments.as_slice().unsafe_ref(id as uint - 1) as *SegmentReader }
+            Ok(std::ptr::null())
         }
     }
 
@@ -167,17 +243,18 @@ impl ReaderArena {
 pub struct BuilderArena {
     pub segment0 : SegmentBuilder,
     pub more_segments : Vec<Box<SegmentBuilder>>,
-    pub allocation_strategy : message::AllocationStrategy,
-    pub owned_memory : Vec<*mut Word>,
-    pub nextSize : uint,
+    // Segments kept by reset() for reuse, with the next one to hand out last.
+    free_segments : Vec<Box<SegmentBuilder>>,
+    pub allocator : Box<message::SegmentAllocator>,
+    pub owned_memory : Vec<(*mut Word, WordCount)>,
     pub cap_table : Vec<Option<Box<ClientHook:Send>>>,
     pub fail_fast : bool,
 }
 
 impl Drop for BuilderArena {
     fn drop(&mut self) {
-        for &segment_ptr in self.owned_memory.iter() {
-            unsafe { libc::free(std::mem::transmute(segment_ptr)); }
+        for &(segment_ptr, size) in self.owned_memory.iter() {
+            self.allocator.deallocate_segment(segment_ptr, size);
         }
     }
 }
@@ -192,17 +269,29 @@ impl BuilderArena {
     pub fn new(allocationStrategy : message::AllocationStrategy,
                first_segment : FirstSegment,
                fail_fast : bool) -> Box<BuilderArena> {
+        let first_segment_words = match first_segment {
+            NumWords(n) => n,
+            ZeroedWords(ref w) => w.len()
+        };
+        BuilderArena::new_with_allocator(
+            box message::HeapAllocator::new(first_segment_words, allocationStrategy),
+            first_segment, fail_fast)
+    }
 
-        let (first_segment, num_words, owned_memory) : (*mut Word, uint, Vec<*mut Word>) = unsafe {
+    // With `NumWords(n)`, the first segment is requested from `allocator`
+    // with a minimum size of `n` words.
+    pub fn new_with_allocator(mut allocator : Box<message::SegmentAllocator>,
+                              first_segment : FirstSegment,
+                              fail_fast : bool) -> Box<BuilderArena> {
+
+        let (first_segment, num_words, owned_memory) : (*mut Word, uint, Vec<(*mut Word, WordCount)>) =
             match first_segment {
                 NumWords(n) => {
-                    let ptr = std::mem::transmute(
-                        libc::calloc(n as libc::size_t,
-                                          BYTES_PER_WORD as libc::size_t));
-                    (ptr, n, vec!(ptr))
+                    let (ptr, size) = allocator.allocate_segment(n);
+                    (ptr, size, vec!((ptr, size)))
                 }
                 ZeroedWords(w) => (w.as_mut_ptr(), w.len(), Vec::new())
-            }};
+            };
 
         let mut result = box BuilderArena {
             segment0 : SegmentBuilder {
@@ -214,9 +303,9 @@ impl BuilderArena {
                 pos : first_segment,
             },
             more_segments : Vec::new(),
-            allocation_strategy : allocationStrategy,
+            free_segments : Vec::new(),
+            allocator : allocator,
             owned_memory : owned_memory,
-            nextSize : num_words,
             cap_table : Vec::new(),
             fail_fast : fail_fast,
         };
@@ -228,17 +317,8 @@ impl BuilderArena {
     }
 
     pub fn allocate_owned_memory(&mut self, minimumSize : WordCount) -> (*mut Word, WordCount) {
-        let size = std::cmp::max(minimumSize, self.nextSize);
-        let new_words : *mut Word = unsafe {
-            std::mem::transmute(libc::calloc(size as libc::size_t,
-                                                   BYTES_PER_WORD as libc::size_t)) };
-
-        self.owned_memory.push(new_words);
-
-        match self.allocation_strategy {
-            message::GrowHeuristically => { self.nextSize += size; }
-            _ => { }
-        }
+        let (new_words, size) = self.allocator.allocate_segment(minimumSize);
+        self.owned_memory.push((new_words, size));
         (new_words, size)
     }
 
@@ -253,19 +333,26 @@ impl BuilderArena {
 
             //# Need to fall back to additional segments.
 
-            let id = {
-                let len = self.more_segments.len();
-                if len == 0 { 1 }
-                else {
-                    let result_ptr = &mut *self.more_segments.as_mut_slice()[len-1] as *mut SegmentBuilder;
-                    match self.more_segments.as_mut_slice()[len - 1].allocate(amount) {
-                        Some(result) => { return (result_ptr, result) }
-                        None => { len + 1 }
-                    }
-                }};
+            let len = self.more_segments.len();
+            if len > 0 {
+                let result_ptr = &mut *self.more_segments.as_mut_slice()[len - 1] as *mut SegmentBuilder;
+                match (*result_ptr).allocate(amount) {
+                    Some(result) => { return (result_ptr, result) }
+                    None => {}
+                }
+            }
+            let id = (len + 1) as SegmentId;
 
-            let (words, size) = self.allocate_owned_memory(amount);
-            let mut new_builder = box SegmentBuilder::new(self, id as u32, words, size);
+            let mut new_builder = match self.take_free_segment(amount) {
+                Some(mut segment) => {
+                    segment.id = id;
+                    segment
+                }
+                None => {
+                    let (words, size) = self.allocate_owned_memory(amount);
+                    box SegmentBuilder::new(self, id, words, size)
+                }
+            };
             let builder_ptr = &mut *new_builder as *mut SegmentBuilder;
 
             self.more_segments.push(new_builder);
@@ -274,6 +361,45 @@ impl BuilderArena {
         }
     }
 
+    // Takes the next segment kept by reset(), if there is one that can hold
+    // `amount` words. Kept segments that are too small are given back to
+    // the allocator.
+    fn take_free_segment(&mut self, amount : WordCount) -> Option<Box<SegmentBuilder>> {
+        loop {
+            let segment = match self.free_segments.pop() {
+                Some(segment) => segment,
+                None => return None,
+            };
+            if segment.reader.size >= amount {
+                return Some(segment);
+            }
+            let ptr = segment.reader.ptr as *mut Word;
+            match self.owned_memory.iter().position(|&(p, _)| p == ptr) {
+                Some(i) => {
+                    let (p, size) = self.owned_memory.remove(i).unwrap();
+                    self.allocator.deallocate_segment(p, size);
+                }
+                None => {}
+            }
+        }
+    }
+
+    // Discards the message while keeping its segments, so that they can
+    // be reused without going back to the allocator. Only the used part of
+    // each segment needs to be zeroed. The segments are handed out again in
+    // the order in which they were first allocated, and only once they are
+    // needed, so a recycled builder writes the same segments as a new one.
+    pub fn reset(&mut self) {
+        self.segment0.reset();
+        let used = std::mem::replace(&mut self.more_segments, Vec::new());
+        for segment in used.move_iter().rev() {
+            let mut segment = segment;
+            segment.reset();
+            self.free_segments.push(segment);
+        }
+        self.cap_table = Vec::new();
+    }
+
     pub fn get_segment(&mut self, id : SegmentId) -> *mut SegmentBuilder {
         if id == 0 {
             &mut self.segment0 as *mut SegmentBuilder
@@ -313,6 +439,12 @@ impl BuilderArena {
         self.cap_table.push(Some(cap));
         self.cap_table.len() as u32 - 1
     }
+
+    pub fn drop_cap(&mut self, index : u32) {
+        if (index as uint) < self.cap_table.len() {
+            self.cap_table.as_mut_slice()[index as uint] = None;
+        }
+    }
 }
 
 pub enum ArenaPtr {
@@ -322,7 +454,7 @@ pub enum ArenaPtr {
 }
 
 impl ArenaPtr {
-    pub fn try_get_segment(&self, id : SegmentId) -> *SegmentReader {
+    pub fn try_get_segment(&self, id : SegmentId) -> Result<*SegmentReader> {
         unsafe {
             match self {
                 &ReaderArenaPtr(reader) => {
@@ -330,9 +462,11 @@ impl ArenaPtr {
                 }
                 &BuilderArenaPtr(builder) => {
                     if id == 0 {
-                        &(*builder).segment0.reader as *SegmentReader
+                        Ok(&(*builder).segment0.reader as *SegmentReader)
+                    } else if (id as uint) <= (*builder).more_segments.len() {
+                        Ok(&(*builder).more_segments.as_slice()[id as uint - 1].reader as *SegmentReader)
                     } else {
-                        &(*builder).more_segments.as_slice()[id as uint - 1].reader as *SegmentReader
+                        Ok(std::ptr::null())
                     }
                 }
                 &Null => {
@@ -376,6 +510,35 @@ impl ArenaPtr {
         }
     }
 
+    // Builder arenas are trusted, so only readers are subject to the limit.
+    pub fn can_read(&self, amount : u64) -> bool {
+        unsafe {
+            match self {
+                &ReaderArenaPtr(reader) => {
+                    (*reader).read_limiter.can_read(amount)
+                }
+                &BuilderArenaPtr(_) => true,
+                &Null => {
+                    fail!()
+                }
+            }
+        }
+    }
+
+    pub fn unread(&self, amount : u64) {
+        unsafe {
+            match self {
+                &ReaderArenaPtr(reader) => {
+                    (*reader).read_limiter.unread(amount)
+                }
+                &BuilderArenaPtr(_) => {}
+                &Null => {
+                    fail!()
+                }
+            }
+        }
+    }
+
     pub fn fail_fast(&self) -> bool {
         unsafe {
             match self {
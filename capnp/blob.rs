@@ -6,6 +6,11 @@
 
 pub mod Text {
     use std;
+    use common::Word;
+    use error::Result;
+    use layout::{OrphanBuilder, PointerBuilder, PointerReader};
+    use list::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
 
     pub type Reader<'a> = &'a str;
 
@@ -36,10 +41,42 @@ pub mod Text {
         }
     }
 
+    impl <'a> FromOrphanBuilder<'a> for Builder<'a> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_text(orphanage.arena(), size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a>> {
+            builder.as_text()
+        }
+    }
+
+    impl <'a> FromPointerReader<'a> for Reader<'a> {
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a>> {
+            reader.get_text(default_value, 0)
+        }
+    }
+
+    impl <'a> FromPointerBuilder<'a> for Builder<'a> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a>> {
+            builder.get_text(default_value, 0)
+        }
+    }
+
+    impl <'a> InitPointerBuilder<'a> for Builder<'a> {
+        fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a> {
+            builder.init_text(size)
+        }
+    }
+
 }
 
 pub mod Data {
     use std;
+    use common::Word;
+    use error::Result;
+    use layout::{OrphanBuilder, PointerBuilder, PointerReader};
+    use list::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
 
     pub type Reader<'a> = &'a [u8];
 
@@ -59,4 +96,31 @@ pub mod Data {
         }
     }
 
+    impl <'a> FromOrphanBuilder<'a> for Builder<'a> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_data(orphanage.arena(), size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a>> {
+            builder.as_data()
+        }
+    }
+
+    impl <'a> FromPointerReader<'a> for Reader<'a> {
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a>> {
+            reader.get_data(default_value, 0)
+        }
+    }
+
+    impl <'a> FromPointerBuilder<'a> for Builder<'a> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a>> {
+            builder.get_data(default_value, 0)
+        }
+    }
+
+    impl <'a> InitPointerBuilder<'a> for Builder<'a> {
+        fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a> {
+            builder.init_data(size)
+        }
+    }
+
 }
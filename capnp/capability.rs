@@ -6,6 +6,7 @@
 
 use any::{AnyPointer};
 use common::{MessageSize};
+use error;
 use layout::{FromStructReader, FromStructBuilder, HasStructSize};
 use message::{MallocMessageBuilder};
 use std;
@@ -18,7 +19,7 @@ pub struct ResultFuture<Results, Pipeline> {
 }
 
 pub trait ResponseHook:Send {
-    fn get<'a>(&'a mut self) -> AnyPointer::Reader<'a>;
+    fn get<'a>(&'a mut self) -> error::Result<AnyPointer::Reader<'a>>;
 }
 
 pub trait RequestHook {
@@ -98,14 +99,14 @@ impl <Params, Results> CallContext<Params, Results> {
 
 impl <'a, Params : FromStructReader<'a>, Results : FromStructBuilder<'a> + HasStructSize>
 CallContext<Params, Results> {
-    pub fn get<'a>(&'a mut self) -> (Params, Results) {
-        let (any_params, any_results) = self.hook.get();
-        (any_params.get_as_struct(), any_results.get_as_struct())
+    pub fn get<'a>(&'a mut self) -> error::Result<(Params, Results)> {
+        let (any_params, any_results) = try!(self.hook.get());
+        Ok((try!(any_params.get_as_struct()), try!(any_results.get_as_struct())))
     }
 }
 
 pub trait CallContextHook {
-    fn get<'a>(&'a mut self) -> (AnyPointer::Reader<'a>, AnyPointer::Builder<'a>);
+    fn get<'a>(&'a mut self) -> error::Result<(AnyPointer::Reader<'a>, AnyPointer::Builder<'a>)>;
     fn fail(~self);
     fn done(~self);
 }
@@ -0,0 +1,775 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+// Reading and building messages whose types are only known at runtime.
+// A DynamicStruct pairs a struct in a message with the schema_capnp::Node
+// that describes it, and its fields are read and written by name as
+// DynamicValues. Nodes for the struct, enum and group types that a struct
+// refers to are looked up in a SchemaLoader.
+
+use error::{Result, NoSuchField, DynamicTypeMismatch, UnknownSchemaNode, InactiveUnionMember};
+use layout;
+use layout::{FieldSize, StructSize};
+use schema_capnp;
+
+pub struct SchemaLoader<'a> {
+    nodes : Vec<schema_capnp::Node::Reader<'a>>,
+}
+
+impl <'a> SchemaLoader<'a> {
+    pub fn new() -> SchemaLoader<'a> {
+        SchemaLoader { nodes : Vec::new() }
+    }
+
+    pub fn add(&mut self, node : schema_capnp::Node::Reader<'a>) {
+        self.nodes.push(node);
+    }
+
+    pub fn get(&self, id : u64) -> Result<schema_capnp::Node::Reader<'a>> {
+        for node in self.nodes.iter() {
+            if node.get_id() == id { return Ok(*node); }
+        }
+        Err(UnknownSchemaNode(id))
+    }
+}
+
+pub struct DynamicEnum<'a> {
+    pub schema : schema_capnp::Node::Reader<'a>,
+    pub value : u16,
+}
+
+impl <'a> DynamicEnum<'a> {
+    pub fn new(schema : schema_capnp::Node::Reader<'a>, value : u16) -> DynamicEnum<'a> {
+        DynamicEnum { schema : schema, value : value }
+    }
+
+    pub fn get_value(&self) -> u16 { self.value }
+
+    // None if the value is not one of the enumerants that the schema knows
+    // about, for example because it was written with a newer schema.
+    pub fn get_enumerant(&self) -> Result<Option<schema_capnp::Enumerant::Reader<'a>>> {
+        match self.schema.which() {
+            Some(schema_capnp::Node::Enum(e)) => {
+                let enumerants = try!(e.get_enumerants());
+                if (self.value as uint) < enumerants.size() {
+                    Ok(Some(enumerants[self.value as uint]))
+                } else {
+                    Ok(None)
+                }
+            }
+            _ => Err(DynamicTypeMismatch),
+        }
+    }
+}
+
+pub mod DynamicValue {
+    use blob::{Text, Data};
+    use any::AnyPointer;
+    use capability::ClientHook;
+    use dynamic::{DynamicEnum, DynamicList, DynamicStruct};
+
+    pub enum Which<'a, T, D, L, S, P> {
+        Void,
+        Bool(bool),
+        Int8(i8),
+        Int16(i16),
+        Int32(i32),
+        Int64(i64),
+        Uint8(u8),
+        Uint16(u16),
+        Uint32(u32),
+        Uint64(u64),
+        Float32(f32),
+        Float64(f64),
+        Text(T),
+        Data(D),
+        List(L),
+        Enum(DynamicEnum<'a>),
+        Struct(S),
+        AnyPointer(P),
+        Capability(Box<ClientHook:Send>),
+    }
+
+    pub type Reader<'a> = Which<'a, Text::Reader<'a>, Data::Reader<'a>, DynamicList::Reader<'a>,
+                                DynamicStruct::Reader<'a>, AnyPointer::Reader<'a>>;
+    pub type Builder<'a> = Which<'a, Text::Builder<'a>, Data::Builder<'a>, DynamicList::Builder<'a>,
+                                 DynamicStruct::Builder<'a>, AnyPointer::Builder<'a>>;
+}
+
+pub mod DynamicStruct {
+    use std;
+    use any::AnyPointer;
+    use dynamic::{DynamicEnum, DynamicList, DynamicValue, SchemaLoader,
+                  find_field, struct_size, clear_fields, check_active};
+    use error::{Result, DynamicTypeMismatch};
+    use layout::{StructReader, StructBuilder};
+    use schema_capnp;
+    use schema_capnp::{Field, Type, Value};
+
+    pub struct Reader<'a> {
+        pub schema : schema_capnp::Node::Reader<'a>,
+        pub loader : &'a SchemaLoader<'a>,
+        pub reader : StructReader<'a>,
+    }
+
+    impl <'a> Reader<'a> {
+        pub fn new(schema : schema_capnp::Node::Reader<'a>, loader : &'a SchemaLoader<'a>,
+                   value : AnyPointer::Reader<'a>) -> Result<Reader<'a>> {
+            Ok(Reader { schema : schema, loader : loader,
+                        reader : try!(value.get_pointer().get_struct(std::ptr::null())) })
+        }
+
+        pub fn get_schema(&self) -> schema_capnp::Node::Reader<'a> { self.schema }
+
+        pub fn get(&self, name : &str) -> Result<DynamicValue::Reader<'a>> {
+            self.get_field(try!(find_field(self.schema, name)))
+        }
+
+        // Fails with InactiveUnionMember if `field` is a member of the
+        // struct's union other than the one that is set.
+        pub fn get_field(&self, field : Field::Reader<'a>) -> Result<DynamicValue::Reader<'a>> {
+            try!(check_active(self.schema, self.reader, field));
+            let slot = match field.which() {
+                Some(Field::Group(group)) => {
+                    return Ok(DynamicValue::Struct(
+                        Reader { schema : try!(self.loader.get(group.get_type_id())),
+                                 loader : self.loader, reader : self.reader }));
+                }
+                Some(Field::Slot(slot)) => slot,
+                None => return Err(DynamicTypeMismatch),
+            };
+            let offset = slot.get_offset() as uint;
+            let typ = try!(slot.get_type());
+            let default_value = try!(slot.get_default_value());
+            let r = self.reader;
+            Ok(match (typ.which(), default_value.which()) {
+                (Some(Type::Void(())), _) => DynamicValue::Void,
+                (Some(Type::Bool(())), Some(Value::Bool(d))) =>
+                    DynamicValue::Bool(r.get_bool_field_mask(offset, d)),
+                (Some(Type::Int8(())), Some(Value::Int8(d))) =>
+                    DynamicValue::Int8(r.get_data_field_mask(offset, d)),
+                (Some(Type::Int16(())), Some(Value::Int16(d))) =>
+                    DynamicValue::Int16(r.get_data_field_mask(offset, d)),
+                (Some(Type::Int32(())), Some(Value::Int32(d))) =>
+                    DynamicValue::Int32(r.get_data_field_mask(offset, d)),
+                (Some(Type::Int64(())), Some(Value::Int64(d))) =>
+                    DynamicValue::Int64(r.get_data_field_mask(offset, d)),
+                (Some(Type::Uint8(())), Some(Value::Uint8(d))) =>
+                    DynamicValue::Uint8(r.get_data_field_mask(offset, d)),
+                (Some(Type::Uint16(())), Some(Value::Uint16(d))) =>
+                    DynamicValue::Uint16(r.get_data_field_mask(offset, d)),
+                (Some(Type::Uint32(())), Some(Value::Uint32(d))) =>
+                    DynamicValue::Uint32(r.get_data_field_mask(offset, d)),
+                (Some(Type::Uint64(())), Some(Value::Uint64(d))) =>
+                    DynamicValue::Uint64(r.get_data_field_mask(offset, d)),
+                (Some(Type::Float32(())), Some(Value::Float32(d))) =>
+                    DynamicValue::Float32(r.get_data_field_mask(offset, d)),
+                (Some(Type::Float64(())), Some(Value::Float64(d))) =>
+                    DynamicValue::Float64(r.get_data_field_mask(offset, d)),
+                (Some(Type::Enum(e)), Some(Value::Enum(d))) =>
+                    DynamicValue::Enum(DynamicEnum::new(try!(self.loader.get(e.get_type_id())),
+                                                        r.get_data_field_mask(offset, d))),
+                (Some(Type::Text(())), Some(Value::Text(d))) => {
+                    let pointer = r.get_pointer_field(offset);
+                    if pointer.is_null() {
+                        DynamicValue::Text(try!(d))
+                    } else {
+                        DynamicValue::Text(try!(pointer.get_text(std::ptr::null(), 0)))
+                    }
+                }
+                (Some(Type::Data(())), Some(Value::Data(d))) => {
+                    let pointer = r.get_pointer_field(offset);
+                    if pointer.is_null() {
+                        DynamicValue::Data(try!(d))
+                    } else {
+                        DynamicValue::Data(try!(pointer.get_data(std::ptr::null(), 0)))
+                    }
+                }
+                (Some(Type::List(l)), Some(Value::List(d))) => {
+                    let pointer = r.get_pointer_field(offset);
+                    let pointer = if pointer.is_null() { d } else { AnyPointer::Reader::new(pointer) };
+                    DynamicValue::List(
+                        try!(DynamicList::Reader::new(try!(l.get_element_type()), self.loader, pointer)))
+                }
+                (Some(Type::Struct(st)), Some(Value::Struct(d))) => {
+                    let pointer = r.get_pointer_field(offset);
+                    let pointer = if pointer.is_null() { d } else { AnyPointer::Reader::new(pointer) };
+                    DynamicValue::Struct(
+                        try!(Reader::new(try!(self.loader.get(st.get_type_id())), self.loader, pointer)))
+                }
+                (Some(Type::Interface(_)), _) =>
+                    DynamicValue::Capability(try!(r.get_pointer_field(offset).get_capability())),
+                (Some(Type::AnyPointer(_)), _) =>
+                    DynamicValue::AnyPointer(AnyPointer::Reader::new(r.get_pointer_field(offset))),
+                _ => return Err(DynamicTypeMismatch),
+            })
+        }
+
+        pub fn has(&self, name : &str) -> Result<bool> {
+            let field = try!(find_field(self.schema, name));
+            if field.get_discriminant_value() != Field::NO_DISCRIMINANT {
+                match try!(self.which()) {
+                    Some(active) if active.get_discriminant_value() == field.get_discriminant_value() => {}
+                    _ => return Ok(false),
+                }
+            }
+            match field.which() {
+                Some(Field::Slot(slot)) => {
+                    match try!(slot.get_type()).which() {
+                        Some(Type::Text(())) | Some(Type::Data(())) | Some(Type::List(_)) |
+                        Some(Type::Struct(_)) | Some(Type::Interface(_)) | Some(Type::AnyPointer(_)) =>
+                            Ok(!self.reader.get_pointer_field(slot.get_offset() as uint).is_null()),
+                        _ => Ok(true),
+                    }
+                }
+                _ => Ok(true),
+            }
+        }
+
+        // The member of the struct's unnamed union that is currently set, or
+        // None if the struct has no union or the discriminant is unknown.
+        pub fn which(&self) -> Result<Option<Field::Reader<'a>>> {
+            match self.schema.which() {
+                Some(schema_capnp::Node::Struct(st)) => {
+                    if st.get_discriminant_count() == 0 { return Ok(None); }
+                    let discriminant =
+                        self.reader.get_data_field::<u16>(st.get_discriminant_offset() as uint);
+                    let fields = try!(st.get_fields());
+                    for ii in range(0, fields.size()) {
+                        let field = fields[ii];
+                        if field.get_discriminant_value() == discriminant { return Ok(Some(field)); }
+                    }
+                    Ok(None)
+                }
+                _ => Err(DynamicTypeMismatch),
+            }
+        }
+    }
+
+    pub struct Builder<'a> {
+        pub schema : schema_capnp::Node::Reader<'a>,
+        pub loader : &'a SchemaLoader<'a>,
+        pub builder : StructBuilder<'a>,
+    }
+
+    impl <'a> Builder<'a> {
+        // Initializes the pointed-to struct if it is null.
+        pub fn new(schema : schema_capnp::Node::Reader<'a>, loader : &'a SchemaLoader<'a>,
+                   value : AnyPointer::Builder<'a>) -> Result<Builder<'a>> {
+            let size = try!(struct_size(schema));
+            Ok(Builder { schema : schema, loader : loader,
+                         builder : try!(value.get_pointer().get_struct(size, std::ptr::null())) })
+        }
+
+        pub fn as_reader(&self) -> Reader<'a> {
+            Reader { schema : self.schema, loader : self.loader, reader : self.builder.as_reader() }
+        }
+
+        pub fn get_schema(&self) -> schema_capnp::Node::Reader<'a> { self.schema }
+
+        pub fn get(&self, name : &str) -> Result<DynamicValue::Builder<'a>> {
+            self.get_field(try!(find_field(self.schema, name)))
+        }
+
+        pub fn get_field(&self, field : Field::Reader<'a>) -> Result<DynamicValue::Builder<'a>> {
+            //# Reading an inactive member could copy a default over the
+            //# active member's pointer.
+            try!(check_active(self.schema, self.builder.as_reader(), field));
+            let slot = match field.which() {
+                Some(Field::Group(group)) => {
+                    return Ok(DynamicValue::Struct(
+                        Builder { schema : try!(self.loader.get(group.get_type_id())),
+                                  loader : self.loader, builder : self.builder }));
+                }
+                Some(Field::Slot(slot)) => slot,
+                None => return Err(DynamicTypeMismatch),
+            };
+            let offset = slot.get_offset() as uint;
+            let typ = try!(slot.get_type());
+            let default_value = try!(slot.get_default_value());
+            let b = self.builder;
+            Ok(match (typ.which(), default_value.which()) {
+                (Some(Type::Void(())), _) => DynamicValue::Void,
+                (Some(Type::Bool(())), Some(Value::Bool(d))) =>
+                    DynamicValue::Bool(b.get_bool_field_mask(offset, d)),
+                (Some(Type::Int8(())), Some(Value::Int8(d))) =>
+                    DynamicValue::Int8(b.get_data_field_mask(offset, d)),
+                (Some(Type::Int16(())), Some(Value::Int16(d))) =>
+                    DynamicValue::Int16(b.get_data_field_mask(offset, d)),
+                (Some(Type::Int32(())), Some(Value::Int32(d))) =>
+                    DynamicValue::Int32(b.get_data_field_mask(offset, d)),
+                (Some(Type::Int64(())), Some(Value::Int64(d))) =>
+                    DynamicValue::Int64(b.get_data_field_mask(offset, d)),
+                (Some(Type::Uint8(())), Some(Value::Uint8(d))) =>
+                    DynamicValue::Uint8(b.get_data_field_mask(offset, d)),
+                (Some(Type::Uint16(())), Some(Value::Uint16(d))) =>
+                    DynamicValue::Uint16(b.get_data_field_mask(offset, d)),
+                (Some(Type::Uint32(())), Some(Value::Uint32(d))) =>
+                    DynamicValue::Uint32(b.get_data_field_mask(offset, d)),
+                (Some(Type::Uint64(())), Some(Value::Uint64(d))) =>
+                    DynamicValue::Uint64(b.get_data_field_mask(offset, d)),
+                (Some(Type::Float32(())), Some(Value::Float32(d))) =>
+                    DynamicValue::Float32(b.get_data_field_mask(offset, d)),
+                (Some(Type::Float64(())), Some(Value::Float64(d))) =>
+                    DynamicValue::Float64(b.get_data_field_mask(offset, d)),
+                (Some(Type::Enum(e)), Some(Value::Enum(d))) =>
+                    DynamicValue::Enum(DynamicEnum::new(try!(self.loader.get(e.get_type_id())),
+                                                        b.get_data_field_mask(offset, d))),
+                (Some(Type::Text(())), Some(Value::Text(d))) => {
+                    //# Copy the default in, so that the result can be modified.
+                    let pointer = b.get_pointer_field(offset);
+                    if pointer.is_null() { pointer.set_text(try!(d)); }
+                    DynamicValue::Text(try!(pointer.get_text(std::ptr::null(), 0)))
+                }
+                (Some(Type::Data(())), Some(Value::Data(d))) => {
+                    let pointer = b.get_pointer_field(offset);
+                    if pointer.is_null() { pointer.set_data(try!(d)); }
+                    DynamicValue::Data(try!(pointer.get_data(std::ptr::null(), 0)))
+                }
+                (Some(Type::List(l)), Some(Value::List(d))) => {
+                    let pointer = b.get_pointer_field(offset);
+                    if pointer.is_null() { try!(pointer.copy_from(d.get_pointer())); }
+                    DynamicValue::List(
+                        try!(DynamicList::Builder::get_from_pointer(try!(l.get_element_type()),
+                                                                    self.loader, pointer)))
+                }
+                (Some(Type::Struct(st)), Some(Value::Struct(d))) => {
+                    let pointer = b.get_pointer_field(offset);
+                    if pointer.is_null() { try!(pointer.copy_from(d.get_pointer())); }
+                    DynamicValue::Struct(
+                        try!(Builder::new(try!(self.loader.get(st.get_type_id())), self.loader,
+                                          AnyPointer::Builder::new(pointer))))
+                }
+                (Some(Type::Interface(_)), _) =>
+                    DynamicValue::Capability(try!(b.get_pointer_field(offset).get_capability())),
+                (Some(Type::AnyPointer(_)), _) =>
+                    DynamicValue::AnyPointer(AnyPointer::Builder::new(b.get_pointer_field(offset))),
+                _ => return Err(DynamicTypeMismatch),
+            })
+        }
+
+        pub fn has(&self, name : &str) -> Result<bool> {
+            self.as_reader().has(name)
+        }
+
+        pub fn which(&self) -> Result<Option<Field::Reader<'a>>> {
+            self.as_reader().which()
+        }
+
+        // Fails with DynamicTypeMismatch unless `value` has the field's type.
+        pub fn set(&self, name : &str, value : DynamicValue::Reader) -> Result<()> {
+            let field = try!(find_field(self.schema, name));
+            let slot = match field.which() {
+                Some(Field::Slot(slot)) => slot,
+                _ => return Err(DynamicTypeMismatch),
+            };
+            let offset = slot.get_offset() as uint;
+            let typ = try!(slot.get_type());
+            let default_value = try!(slot.get_default_value());
+            let b = self.builder;
+            match (typ.which(), default_value.which(), value) {
+                (Some(Type::Void(())), _, DynamicValue::Void) => {}
+                (Some(Type::Bool(())), Some(Value::Bool(d)), DynamicValue::Bool(v)) =>
+                    b.set_bool_field_mask(offset, v, d),
+                (Some(Type::Int8(())), Some(Value::Int8(d)), DynamicValue::Int8(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Int16(())), Some(Value::Int16(d)), DynamicValue::Int16(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Int32(())), Some(Value::Int32(d)), DynamicValue::Int32(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Int64(())), Some(Value::Int64(d)), DynamicValue::Int64(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Uint8(())), Some(Value::Uint8(d)), DynamicValue::Uint8(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Uint16(())), Some(Value::Uint16(d)), DynamicValue::Uint16(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Uint32(())), Some(Value::Uint32(d)), DynamicValue::Uint32(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Uint64(())), Some(Value::Uint64(d)), DynamicValue::Uint64(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Float32(())), Some(Value::Float32(d)), DynamicValue::Float32(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Float64(())), Some(Value::Float64(d)), DynamicValue::Float64(v)) =>
+                    b.set_data_field_mask(offset, v, d),
+                (Some(Type::Enum(e)), Some(Value::Enum(d)), DynamicValue::Enum(v)) => {
+                    if e.get_type_id() != v.schema.get_id() { return Err(DynamicTypeMismatch); }
+                    b.set_data_field_mask(offset, v.value, d)
+                }
+                (Some(Type::Text(())), _, DynamicValue::Text(v)) =>
+                    b.get_pointer_field(offset).set_text(v),
+                (Some(Type::Data(())), _, DynamicValue::Data(v)) =>
+                    b.get_pointer_field(offset).set_data(v),
+                (Some(Type::List(_)), _, DynamicValue::List(v)) =>
+                    try!(b.get_pointer_field(offset).set_list(&v.reader)),
+                (Some(Type::Struct(st)), _, DynamicValue::Struct(v)) => {
+                    if st.get_type_id() != v.schema.get_id() { return Err(DynamicTypeMismatch); }
+                    try!(b.get_pointer_field(offset).set_struct(&v.reader))
+                }
+                (Some(Type::Interface(_)), _, DynamicValue::Capability(v)) =>
+                    b.get_pointer_field(offset).set_capability(v),
+                (Some(Type::AnyPointer(_)), _, DynamicValue::AnyPointer(v)) =>
+                    try!(b.get_pointer_field(offset).copy_from(v.get_pointer())),
+                _ => return Err(DynamicTypeMismatch),
+            }
+            self.set_discriminant(field);
+            Ok(())
+        }
+
+        // `size` is the element count for lists and the byte count for text
+        // and data. It is ignored for structs and groups.
+        pub fn init(&self, name : &str, size : uint) -> Result<DynamicValue::Builder<'a>> {
+            let field = try!(find_field(self.schema, name));
+            let result = match field.which() {
+                Some(Field::Group(group)) => {
+                    let schema = try!(self.loader.get(group.get_type_id()));
+                    try!(clear_fields(self.builder, schema, self.loader));
+                    DynamicValue::Struct(Builder { schema : schema, loader : self.loader,
+                                                   builder : self.builder })
+                }
+                Some(Field::Slot(slot)) => {
+                    let pointer = self.builder.get_pointer_field(slot.get_offset() as uint);
+                    match try!(slot.get_type()).which() {
+                        Some(Type::Text(())) => DynamicValue::Text(pointer.init_text(size)),
+                        Some(Type::Data(())) => DynamicValue::Data(pointer.init_data(size)),
+                        Some(Type::List(l)) =>
+                            DynamicValue::List(
+                                try!(DynamicList::Builder::init_pointer(try!(l.get_element_type()),
+                                                                        self.loader, pointer, size))),
+                        Some(Type::Struct(st)) => {
+                            let schema = try!(self.loader.get(st.get_type_id()));
+                            DynamicValue::Struct(
+                                Builder { schema : schema, loader : self.loader,
+                                          builder : pointer.init_struct(try!(struct_size(schema))) })
+                        }
+                        Some(Type::AnyPointer(_)) => {
+                            pointer.clear();
+                            DynamicValue::AnyPointer(AnyPointer::Builder::new(pointer))
+                        }
+                        _ => return Err(DynamicTypeMismatch),
+                    }
+                }
+                None => return Err(DynamicTypeMismatch),
+            };
+            self.set_discriminant(field);
+            Ok(result)
+        }
+
+        fn set_discriminant(&self, field : Field::Reader) {
+            let discriminant = field.get_discriminant_value();
+            if discriminant == Field::NO_DISCRIMINANT { return; }
+            match self.schema.which() {
+                Some(schema_capnp::Node::Struct(st)) => {
+                    self.builder.set_data_field::<u16>(st.get_discriminant_offset() as uint,
+                                                       discriminant);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub mod DynamicList {
+    use std;
+    use any::AnyPointer;
+    use dynamic::{DynamicEnum, DynamicStruct, DynamicValue, SchemaLoader,
+                  element_size, struct_size};
+    use error::{Result, DynamicTypeMismatch};
+    use layout::{ListReader, ListBuilder, PointerBuilder, PrimitiveElement};
+    use schema_capnp::Type;
+
+    pub struct Reader<'a> {
+        pub element_type : Type::Reader<'a>,
+        pub loader : &'a SchemaLoader<'a>,
+        pub reader : ListReader<'a>,
+    }
+
+    impl <'a> Reader<'a> {
+        pub fn new(element_type : Type::Reader<'a>, loader : &'a SchemaLoader<'a>,
+                   value : AnyPointer::Reader<'a>) -> Result<Reader<'a>> {
+            let reader = try!(value.get_pointer().get_list(try!(element_size(element_type)),
+                                                           std::ptr::null()));
+            Ok(Reader { element_type : element_type, loader : loader, reader : reader })
+        }
+
+        pub fn size(&self) -> uint { self.reader.size() }
+
+        pub fn get(&self, index : uint) -> Result<DynamicValue::Reader<'a>> {
+            assert!(index < self.size());
+            let r = &self.reader;
+            Ok(match self.element_type.which() {
+                Some(Type::Void(())) => DynamicValue::Void,
+                Some(Type::Bool(())) => DynamicValue::Bool(PrimitiveElement::get(r, index)),
+                Some(Type::Int8(())) => DynamicValue::Int8(PrimitiveElement::get(r, index)),
+                Some(Type::Int16(())) => DynamicValue::Int16(PrimitiveElement::get(r, index)),
+                Some(Type::Int32(())) => DynamicValue::Int32(PrimitiveElement::get(r, index)),
+                Some(Type::Int64(())) => DynamicValue::Int64(PrimitiveElement::get(r, index)),
+                Some(Type::Uint8(())) => DynamicValue::Uint8(PrimitiveElement::get(r, index)),
+                Some(Type::Uint16(())) => DynamicValue::Uint16(PrimitiveElement::get(r, index)),
+                Some(Type::Uint32(())) => DynamicValue::Uint32(PrimitiveElement::get(r, index)),
+                Some(Type::Uint64(())) => DynamicValue::Uint64(PrimitiveElement::get(r, index)),
+                Some(Type::Float32(())) => DynamicValue::Float32(PrimitiveElement::get(r, index)),
+                Some(Type::Float64(())) => DynamicValue::Float64(PrimitiveElement::get(r, index)),
+                Some(Type::Enum(e)) =>
+                    DynamicValue::Enum(DynamicEnum::new(try!(self.loader.get(e.get_type_id())),
+                                                        PrimitiveElement::get(r, index))),
+                Some(Type::Text(())) =>
+                    DynamicValue::Text(try!(r.get_pointer_element(index).get_text(std::ptr::null(), 0))),
+                Some(Type::Data(())) =>
+                    DynamicValue::Data(try!(r.get_pointer_element(index).get_data(std::ptr::null(), 0))),
+                Some(Type::List(l)) =>
+                    DynamicValue::List(
+                        try!(Reader::new(try!(l.get_element_type()), self.loader,
+                                         AnyPointer::Reader::new(r.get_pointer_element(index))))),
+                Some(Type::Struct(st)) =>
+                    DynamicValue::Struct(
+                        DynamicStruct::Reader { schema : try!(self.loader.get(st.get_type_id())),
+                                                loader : self.loader,
+                                                reader : try!(r.get_struct_element(index)) }),
+                Some(Type::Interface(_)) =>
+                    DynamicValue::Capability(try!(r.get_pointer_element(index).get_capability())),
+                Some(Type::AnyPointer(_)) =>
+                    DynamicValue::AnyPointer(AnyPointer::Reader::new(r.get_pointer_element(index))),
+                None => return Err(DynamicTypeMismatch),
+            })
+        }
+    }
+
+    pub struct Builder<'a> {
+        pub element_type : Type::Reader<'a>,
+        pub loader : &'a SchemaLoader<'a>,
+        pub builder : ListBuilder<'a>,
+    }
+
+    impl <'a> Builder<'a> {
+        pub fn new(element_type : Type::Reader<'a>, loader : &'a SchemaLoader<'a>,
+                   value : AnyPointer::Builder<'a>) -> Result<Builder<'a>> {
+            Builder::get_from_pointer(element_type, loader, value.get_pointer())
+        }
+
+        pub fn init_pointer(element_type : Type::Reader<'a>, loader : &'a SchemaLoader<'a>,
+                            pointer : PointerBuilder<'a>, size : uint) -> Result<Builder<'a>> {
+            let builder = match element_type.which() {
+                Some(Type::Struct(st)) =>
+                    pointer.init_struct_list(size, try!(struct_size(try!(loader.get(st.get_type_id()))))),
+                _ => pointer.init_list(try!(element_size(element_type)), size),
+            };
+            Ok(Builder { element_type : element_type, loader : loader, builder : builder })
+        }
+
+        pub fn get_from_pointer(element_type : Type::Reader<'a>, loader : &'a SchemaLoader<'a>,
+                                pointer : PointerBuilder<'a>) -> Result<Builder<'a>> {
+            let builder = match element_type.which() {
+                Some(Type::Struct(st)) =>
+                    try!(pointer.get_struct_list(try!(struct_size(try!(loader.get(st.get_type_id())))),
+                                                 std::ptr::null())),
+                _ => try!(pointer.get_list(try!(element_size(element_type)), std::ptr::null())),
+            };
+            Ok(Builder { element_type : element_type, loader : loader, builder : builder })
+        }
+
+        pub fn size(&self) -> uint { self.builder.size() }
+
+        pub fn get(&self, index : uint) -> Result<DynamicValue::Builder<'a>> {
+            assert!(index < self.size());
+            let b = &self.builder;
+            Ok(match self.element_type.which() {
+                Some(Type::Void(())) => DynamicValue::Void,
+                Some(Type::Bool(())) => DynamicValue::Bool(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Int8(())) => DynamicValue::Int8(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Int16(())) => DynamicValue::Int16(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Int32(())) => DynamicValue::Int32(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Int64(())) => DynamicValue::Int64(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Uint8(())) => DynamicValue::Uint8(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Uint16(())) => DynamicValue::Uint16(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Uint32(())) => DynamicValue::Uint32(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Uint64(())) => DynamicValue::Uint64(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Float32(())) => DynamicValue::Float32(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Float64(())) => DynamicValue::Float64(PrimitiveElement::get_from_builder(b, index)),
+                Some(Type::Enum(e)) =>
+                    DynamicValue::Enum(DynamicEnum::new(try!(self.loader.get(e.get_type_id())),
+                                                        PrimitiveElement::get_from_builder(b, index))),
+                Some(Type::Text(())) =>
+                    DynamicValue::Text(try!(b.get_pointer_element(index).get_text(std::ptr::null(), 0))),
+                Some(Type::Data(())) =>
+                    DynamicValue::Data(try!(b.get_pointer_element(index).get_data(std::ptr::null(), 0))),
+                Some(Type::List(l)) =>
+                    DynamicValue::List(
+                        try!(Builder::get_from_pointer(try!(l.get_element_type()), self.loader,
+                                                       b.get_pointer_element(index)))),
+                Some(Type::Struct(st)) =>
+                    DynamicValue::Struct(
+                        DynamicStruct::Builder { schema : try!(self.loader.get(st.get_type_id())),
+                                                 loader : self.loader,
+                                                 builder : b.get_struct_element(index) }),
+                Some(Type::Interface(_)) =>
+                    DynamicValue::Capability(try!(b.get_pointer_element(index).get_capability())),
+                Some(Type::AnyPointer(_)) =>
+                    DynamicValue::AnyPointer(AnyPointer::Builder::new(b.get_pointer_element(index))),
+                None => return Err(DynamicTypeMismatch),
+            })
+        }
+
+        // Struct elements live inline in the list and cannot be replaced
+        // wholesale; use get() and set their fields instead.
+        pub fn set(&self, index : uint, value : DynamicValue::Reader) -> Result<()> {
+            assert!(index < self.size());
+            let b = &self.builder;
+            match (self.element_type.which(), value) {
+                (Some(Type::Void(())), DynamicValue::Void) => {}
+                (Some(Type::Bool(())), DynamicValue::Bool(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Int8(())), DynamicValue::Int8(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Int16(())), DynamicValue::Int16(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Int32(())), DynamicValue::Int32(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Int64(())), DynamicValue::Int64(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Uint8(())), DynamicValue::Uint8(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Uint16(())), DynamicValue::Uint16(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Uint32(())), DynamicValue::Uint32(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Uint64(())), DynamicValue::Uint64(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Float32(())), DynamicValue::Float32(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Float64(())), DynamicValue::Float64(v)) => PrimitiveElement::set(b, index, v),
+                (Some(Type::Enum(e)), DynamicValue::Enum(v)) => {
+                    if e.get_type_id() != v.schema.get_id() { return Err(DynamicTypeMismatch); }
+                    PrimitiveElement::set(b, index, v.value)
+                }
+                (Some(Type::Text(())), DynamicValue::Text(v)) =>
+                    b.get_pointer_element(index).set_text(v),
+                (Some(Type::Data(())), DynamicValue::Data(v)) =>
+                    b.get_pointer_element(index).set_data(v),
+                (Some(Type::List(_)), DynamicValue::List(v)) =>
+                    try!(b.get_pointer_element(index).set_list(&v.reader)),
+                (Some(Type::Interface(_)), DynamicValue::Capability(v)) =>
+                    b.get_pointer_element(index).set_capability(v),
+                (Some(Type::AnyPointer(_)), DynamicValue::AnyPointer(v)) =>
+                    try!(b.get_pointer_element(index).copy_from(v.get_pointer())),
+                _ => return Err(DynamicTypeMismatch),
+            }
+            Ok(())
+        }
+
+        // For lists of text, data and lists.
+        pub fn init(&self, index : uint, size : uint) -> Result<DynamicValue::Builder<'a>> {
+            assert!(index < self.size());
+            let pointer = self.builder.get_pointer_element(index);
+            Ok(match self.element_type.which() {
+                Some(Type::Text(())) => DynamicValue::Text(pointer.init_text(size)),
+                Some(Type::Data(())) => DynamicValue::Data(pointer.init_data(size)),
+                Some(Type::List(l)) =>
+                    DynamicValue::List(
+                        try!(Builder::init_pointer(try!(l.get_element_type()), self.loader,
+                                                   pointer, size))),
+                _ => return Err(DynamicTypeMismatch),
+            })
+        }
+    }
+}
+
+fn find_field<'a>(node : schema_capnp::Node::Reader<'a>,
+                  name : &str) -> Result<schema_capnp::Field::Reader<'a>> {
+    match node.which() {
+        Some(schema_capnp::Node::Struct(st)) => {
+            let fields = try!(st.get_fields());
+            for ii in range(0, fields.size()) {
+                let field = fields[ii];
+                if try!(field.get_name()) == name { return Ok(field); }
+            }
+            Err(NoSuchField)
+        }
+        _ => Err(DynamicTypeMismatch),
+    }
+}
+
+fn check_active(node : schema_capnp::Node::Reader, reader : layout::StructReader,
+                field : schema_capnp::Field::Reader) -> Result<()> {
+    let discriminant = field.get_discriminant_value();
+    if discriminant == schema_capnp::Field::NO_DISCRIMINANT { return Ok(()); }
+    match node.which() {
+        Some(schema_capnp::Node::Struct(st)) => {
+            if reader.get_data_field::<u16>(st.get_discriminant_offset() as uint) == discriminant {
+                Ok(())
+            } else {
+                Err(InactiveUnionMember)
+            }
+        }
+        _ => Err(DynamicTypeMismatch),
+    }
+}
+
+fn struct_size(node : schema_capnp::Node::Reader) -> Result<StructSize> {
+    use schema_capnp::ElementSize;
+    match node.which() {
+        Some(schema_capnp::Node::Struct(st)) => {
+            let encoding = match st.get_preferred_list_encoding() {
+                Some(ElementSize::Empty) => layout::Void,
+                Some(ElementSize::Bit) => layout::Bit,
+                Some(ElementSize::Byte) => layout::Byte,
+                Some(ElementSize::TwoBytes) => layout::TwoBytes,
+                Some(ElementSize::FourBytes) => layout::FourBytes,
+                Some(ElementSize::EightBytes) => layout::EightBytes,
+                Some(ElementSize::Pointer) => layout::Pointer,
+                Some(ElementSize::InlineComposite) | None => layout::InlineComposite,
+            };
+            Ok(StructSize { data : st.get_data_word_count(), pointers : st.get_pointer_count(),
+                            preferred_list_encoding : encoding })
+        }
+        _ => Err(DynamicTypeMismatch),
+    }
+}
+
+// The encoding of a list whose elements are of type `typ`.
+fn element_size(typ : schema_capnp::Type::Reader) -> Result<FieldSize> {
+    use schema_capnp::Type;
+    match typ.which() {
+        Some(Type::Void(())) => Ok(layout::Void),
+        Some(Type::Bool(())) => Ok(layout::Bit),
+        Some(Type::Int8(())) | Some(Type::Uint8(())) => Ok(layout::Byte),
+        Some(Type::Int16(())) | Some(Type::Uint16(())) | Some(Type::Enum(_)) => Ok(layout::TwoBytes),
+        Some(Type::Int32(())) | Some(Type::Uint32(())) | Some(Type::Float32(())) => Ok(layout::FourBytes),
+        Some(Type::Int64(())) | Some(Type::Uint64(())) | Some(Type::Float64(())) => Ok(layout::EightBytes),
+        Some(Type::Struct(_)) => Ok(layout::InlineComposite),
+        Some(_) => Ok(layout::Pointer),
+        None => Err(DynamicTypeMismatch),
+    }
+}
+
+// Resets the fields of a group to their defaults, as when the group is
+// initialized.
+fn clear_fields(builder : layout::StructBuilder, node : schema_capnp::Node::Reader,
+                loader : &SchemaLoader) -> Result<()> {
+    use schema_capnp::{Field, Type};
+    let st = match node.which() {
+        Some(schema_capnp::Node::Struct(st)) => st,
+        _ => return Err(DynamicTypeMismatch),
+    };
+    if st.get_discriminant_count() > 0 {
+        builder.set_data_field::<u16>(st.get_discriminant_offset() as uint, 0);
+    }
+    let fields = try!(st.get_fields());
+    for ii in range(0, fields.size()) {
+        match fields[ii].which() {
+            Some(Field::Group(group)) =>
+                try!(clear_fields(builder, try!(loader.get(group.get_type_id())), loader)),
+            Some(Field::Slot(slot)) => {
+                let offset = slot.get_offset() as uint;
+                match try!(slot.get_type()).which() {
+                    Some(Type::Void(())) => {}
+                    Some(Type::Bool(())) => builder.set_bool_field(offset, false),
+                    Some(Type::Int8(())) | Some(Type::Uint8(())) =>
+                        builder.set_data_field::<u8>(offset, 0),
+                    Some(Type::Int16(())) | Some(Type::Uint16(())) | Some(Type::Enum(_)) =>
+                        builder.set_data_field::<u16>(offset, 0),
+                    Some(Type::Int32(())) | Some(Type::Uint32(())) | Some(Type::Float32(())) =>
+                        builder.set_data_field::<u32>(offset, 0),
+                    Some(Type::Int64(())) | Some(Type::Uint64(())) | Some(Type::Float64(())) =>
+                        builder.set_data_field::<u64>(offset, 0),
+                    Some(_) => builder.get_pointer_field(offset).clear(),
+                    None => return Err(DynamicTypeMismatch),
+                }
+            }
+            None => return Err(DynamicTypeMismatch),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,320 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+use dynamic::{SchemaLoader, DynamicEnum, DynamicStruct, DynamicValue};
+use error::{NoSuchField, DynamicTypeMismatch, InactiveUnionMember};
+use message::{MessageBuilder, MallocMessageBuilder};
+use schema_capnp::{ElementSize, Field, Node};
+
+// struct Thing {
+//   count @0 :UInt32 = 7;
+//   name @1 :Text;
+//   union {
+//     small @2 :UInt16;
+//     flag @3 :Bool;
+//   }
+// }
+fn build_schema(node : Node::Builder) {
+    node.set_id(0x1234);
+    let st = node.init_struct();
+    st.set_data_word_count(1);
+    st.set_pointer_count(1);
+    st.set_preferred_list_encoding(ElementSize::InlineComposite);
+    st.set_discriminant_count(2);
+    st.set_discriminant_offset(3);
+    let fields = st.init_fields(4);
+
+    fields[0].set_name("count");
+    let slot = fields[0].init_slot();
+    slot.set_offset(0);
+    slot.init_type().set_uint32(());
+    slot.init_default_value().set_uint32(7);
+
+    fields[1].set_name("name");
+    let slot = fields[1].init_slot();
+    slot.set_offset(0);
+    slot.init_type().set_text(());
+    slot.init_default_value().set_text("");
+
+    fields[2].set_name("small");
+    fields[2].set_discriminant_value(0);
+    let slot = fields[2].init_slot();
+    slot.set_offset(2);
+    slot.init_type().set_uint16(());
+    slot.init_default_value().set_uint16(0);
+
+    fields[3].set_name("flag");
+    fields[3].set_discriminant_value(1);
+    let slot = fields[3].init_slot();
+    slot.set_offset(32);
+    slot.init_type().set_bool(());
+    slot.init_default_value().set_bool(false);
+}
+
+#[test]
+fn dynamic_struct() {
+    let mut schema_message = MallocMessageBuilder::new_default();
+    let node = schema_message.init_root::<Node::Builder>();
+    build_schema(node);
+    let mut loader = SchemaLoader::new();
+    loader.add(node.as_reader());
+
+    let mut message = MallocMessageBuilder::new_default();
+    let thing = DynamicStruct::Builder::new(node.as_reader(), &loader,
+                                            message.get_root_internal()).unwrap();
+
+    match thing.get("count").unwrap() {
+        DynamicValue::Uint32(7) => {}
+        _ => fail!("expected the default value"),
+    }
+
+    thing.set("count", DynamicValue::Uint32(42)).unwrap();
+    thing.set("name", DynamicValue::Text("widget")).unwrap();
+    thing.set("flag", DynamicValue::Bool(true)).unwrap();
+
+    assert!(thing.set("count", DynamicValue::Int8(1)).err() == Some(DynamicTypeMismatch));
+    assert!(thing.set("missing", DynamicValue::Void).err() == Some(NoSuchField));
+
+    let reader = thing.as_reader();
+    match reader.get("count").unwrap() {
+        DynamicValue::Uint32(42) => {}
+        _ => fail!("expected 42"),
+    }
+    match reader.get("name").unwrap() {
+        DynamicValue::Text(name) => assert_eq!(name, "widget"),
+        _ => fail!("expected text"),
+    }
+    assert_eq!(reader.which().unwrap().unwrap().get_name().unwrap(), "flag");
+    assert!(reader.has("flag").unwrap());
+    assert!(!reader.has("small").unwrap());
+    assert!(reader.get("small").err() == Some(InactiveUnionMember));
+    assert!(thing.get("small").err() == Some(InactiveUnionMember));
+
+    thing.set("small", DynamicValue::Uint16(5)).unwrap();
+    assert_eq!(reader.which().unwrap().unwrap().get_discriminant_value(), 0);
+    match reader.get("small").unwrap() {
+        DynamicValue::Uint16(5) => {}
+        _ => fail!("expected 5"),
+    }
+}
+
+static POINT_ID : u64 = 0x10;
+static COLOR_ID : u64 = 0x20;
+static SHAPE_ID : u64 = 0x30;
+static LABEL_ID : u64 = 0x31;
+
+fn init_struct_node<'a>(message : &'a mut MallocMessageBuilder, id : u64,
+                        data_words : u16, pointers : u16)
+                        -> (Node::Builder<'a>, Node::Struct::Builder<'a>) {
+    let node = message.init_root::<Node::Builder>();
+    node.set_id(id);
+    let st = node.init_struct();
+    st.set_data_word_count(data_words);
+    st.set_pointer_count(pointers);
+    st.set_preferred_list_encoding(ElementSize::InlineComposite);
+    (node, st)
+}
+
+fn init_slot<'a>(field : Field::Builder<'a>, name : &str, offset : u32) -> Field::Slot::Builder<'a> {
+    field.set_name(name);
+    let slot = field.init_slot();
+    slot.set_offset(offset);
+    slot
+}
+
+// struct Point { x @0 :Int32; y @1 :Int32; }
+fn point_node<'a>(message : &'a mut MallocMessageBuilder) -> Node::Reader<'a> {
+    let (node, st) = init_struct_node(message, POINT_ID, 1, 0);
+    let fields = st.init_fields(2);
+    let slot = init_slot(fields[0], "x", 0);
+    slot.init_type().set_int32(());
+    slot.init_default_value().set_int32(0);
+    let slot = init_slot(fields[1], "y", 1);
+    slot.init_type().set_int32(());
+    slot.init_default_value().set_int32(0);
+    node.as_reader()
+}
+
+// enum Color { red @0; green @1; }
+fn color_node<'a>(message : &'a mut MallocMessageBuilder) -> Node::Reader<'a> {
+    let node = message.init_root::<Node::Builder>();
+    node.set_id(COLOR_ID);
+    let enumerants = node.init_enum().init_enumerants(2);
+    enumerants[0].set_name("red");
+    enumerants[1].set_name("green");
+    node.as_reader()
+}
+
+// struct Shape {
+//   points @0 :List(Point);
+//   grid @1 :List(List(UInt16));
+//   color @2 :Color;
+//   label :group { text @3 :Text; size @4 :UInt16; }
+// }
+fn shape_node<'a>(message : &'a mut MallocMessageBuilder) -> Node::Reader<'a> {
+    let (node, st) = init_struct_node(message, SHAPE_ID, 1, 3);
+    let fields = st.init_fields(4);
+
+    let slot = init_slot(fields[0], "points", 0);
+    slot.init_type().init_list().init_element_type().init_struct().set_type_id(POINT_ID);
+    slot.init_default_value().init_list();
+
+    let slot = init_slot(fields[1], "grid", 1);
+    slot.init_type().init_list().init_element_type().init_list().init_element_type().set_uint16(());
+    slot.init_default_value().init_list();
+
+    let slot = init_slot(fields[2], "color", 0);
+    slot.init_type().init_enum().set_type_id(COLOR_ID);
+    slot.init_default_value().set_enum(0);
+
+    fields[3].set_name("label");
+    fields[3].init_group().set_type_id(LABEL_ID);
+    node.as_reader()
+}
+
+fn label_node<'a>(message : &'a mut MallocMessageBuilder) -> Node::Reader<'a> {
+    let (node, st) = init_struct_node(message, LABEL_ID, 1, 3);
+    st.set_is_group(true);
+    let fields = st.init_fields(2);
+    let slot = init_slot(fields[0], "text", 2);
+    slot.init_type().set_text(());
+    slot.init_default_value().set_text("");
+    let slot = init_slot(fields[1], "size", 1);
+    slot.init_type().set_uint16(());
+    slot.init_default_value().set_uint16(0);
+    node.as_reader()
+}
+
+#[test]
+fn dynamic_lists() {
+    let mut point_message = MallocMessageBuilder::new_default();
+    let mut color_message = MallocMessageBuilder::new_default();
+    let mut shape_message = MallocMessageBuilder::new_default();
+    let mut label_message = MallocMessageBuilder::new_default();
+    let shape = shape_node(&mut shape_message);
+    let mut loader = SchemaLoader::new();
+    loader.add(point_node(&mut point_message));
+    loader.add(color_node(&mut color_message));
+    loader.add(shape);
+    loader.add(label_node(&mut label_message));
+
+    let mut message = MallocMessageBuilder::new_default();
+    let builder = DynamicStruct::Builder::new(shape, &loader, message.get_root_internal()).unwrap();
+
+    match builder.init("points", 2).unwrap() {
+        DynamicValue::List(points) => match points.get(1).unwrap() {
+            DynamicValue::Struct(point) => point.set("x", DynamicValue::Int32(5)).unwrap(),
+            _ => fail!("expected a struct"),
+        },
+        _ => fail!("expected a list"),
+    }
+
+    match builder.init("grid", 2).unwrap() {
+        DynamicValue::List(grid) => match grid.init(0, 3).unwrap() {
+            DynamicValue::List(row) => row.set(2, DynamicValue::Uint16(9)).unwrap(),
+            _ => fail!("expected a list"),
+        },
+        _ => fail!("expected a list"),
+    }
+
+    let reader = builder.as_reader();
+    match reader.get("points").unwrap() {
+        DynamicValue::List(points) => {
+            assert_eq!(points.size(), 2);
+            match points.get(1).unwrap() {
+                DynamicValue::Struct(point) => match point.get("x").unwrap() {
+                    DynamicValue::Int32(5) => {}
+                    _ => fail!("expected 5"),
+                },
+                _ => fail!("expected a struct"),
+            }
+        }
+        _ => fail!("expected a list"),
+    }
+
+    match reader.get("grid").unwrap() {
+        DynamicValue::List(grid) => {
+            assert_eq!(grid.size(), 2);
+            match grid.get(0).unwrap() {
+                DynamicValue::List(row) => {
+                    assert_eq!(row.size(), 3);
+                    match row.get(2).unwrap() {
+                        DynamicValue::Uint16(9) => {}
+                        _ => fail!("expected 9"),
+                    }
+                }
+                _ => fail!("expected a list"),
+            }
+            match grid.get(1).unwrap() {
+                DynamicValue::List(row) => assert_eq!(row.size(), 0),
+                _ => fail!("expected a list"),
+            }
+        }
+        _ => fail!("expected a list"),
+    }
+}
+
+#[test]
+fn dynamic_enum_and_group() {
+    let mut point_message = MallocMessageBuilder::new_default();
+    let mut color_message = MallocMessageBuilder::new_default();
+    let mut shape_message = MallocMessageBuilder::new_default();
+    let mut label_message = MallocMessageBuilder::new_default();
+    let color = color_node(&mut color_message);
+    let shape = shape_node(&mut shape_message);
+    let mut loader = SchemaLoader::new();
+    loader.add(point_node(&mut point_message));
+    loader.add(color);
+    loader.add(shape);
+    loader.add(label_node(&mut label_message));
+
+    let mut message = MallocMessageBuilder::new_default();
+    let builder = DynamicStruct::Builder::new(shape, &loader, message.get_root_internal()).unwrap();
+    let reader = builder.as_reader();
+
+    // An enumerant that this schema does not know about.
+    builder.set("color", DynamicValue::Enum(DynamicEnum::new(color, 5))).unwrap();
+    match reader.get("color").unwrap() {
+        DynamicValue::Enum(e) => {
+            assert_eq!(e.get_value(), 5);
+            assert!(e.get_enumerant().unwrap().is_none());
+        }
+        _ => fail!("expected an enum"),
+    }
+
+    builder.set("color", DynamicValue::Enum(DynamicEnum::new(color, 1))).unwrap();
+    match reader.get("color").unwrap() {
+        DynamicValue::Enum(e) =>
+            assert_eq!(e.get_enumerant().unwrap().unwrap().get_name().unwrap(), "green"),
+        _ => fail!("expected an enum"),
+    }
+
+    match builder.get("label").unwrap() {
+        DynamicValue::Struct(label) => {
+            label.set("text", DynamicValue::Text("hello")).unwrap();
+            label.set("size", DynamicValue::Uint16(12)).unwrap();
+        }
+        _ => fail!("expected a group"),
+    }
+    assert!(reader.has("label").unwrap());
+
+    match builder.init("label", 0).unwrap() {
+        DynamicValue::Struct(label) => {
+            assert!(!label.has("text").unwrap());
+            match label.get("size").unwrap() {
+                DynamicValue::Uint16(0) => {}
+                _ => fail!("expected 0"),
+            }
+        }
+        _ => fail!("expected a group"),
+    }
+
+    // Initializing the group leaves the rest of the struct alone.
+    match reader.get("color").unwrap() {
+        DynamicValue::Enum(e) => assert_eq!(e.get_value(), 1),
+        _ => fail!("expected an enum"),
+    }
+}
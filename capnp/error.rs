@@ -0,0 +1,104 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+use std;
+
+// Ways in which a message can turn out to be malformed. When
+// ReaderOptions::fail_fast is true, these are returned to the caller
+// instead of being papered over with default values.
+#[deriving(Eq, Clone)]
+pub enum Error {
+    RootOutOfBounds,
+    OutOfBoundsFarPointer,
+    OutOfBoundsStructPointer,
+    OutOfBoundsListPointer,
+    OutOfBoundsTextPointer,
+    OutOfBoundsDataPointer,
+    InvalidSegmentId(u32),
+    MalformedDoubleFarPointer,
+    NestingLimitExceeded,
+    ReadLimitExceeded,
+    AmplifiedListPointer,
+    UnexpectedFarPointer,
+    UnknownPointerType,
+    ExpectedStructPointer,
+    ExpectedListPointer,
+    ExpectedCapabilityPointer,
+    NullCapabilityPointer,
+    InvalidCapabilityPointer(uint),
+    InlineCompositeNonStruct,
+    InlineCompositeOverrun,
+    IncompatibleListElementSize,
+    ExpectedByteList,
+    TextNotNulTerminated,
+    TextNotUtf8,
+    CanonicalCapability,
+    CapabilityComparison,
+    SegmentLoadFailed,
+    NoSuchField,
+    DynamicTypeMismatch,
+    UnknownSchemaNode(u64),
+    InactiveUnionMember,
+}
+
+impl Error {
+    pub fn description(&self) -> &'static str {
+        match *self {
+            RootOutOfBounds => "Root location out of bounds.",
+            OutOfBoundsFarPointer => "Message contains out-of-bounds far pointer.",
+            OutOfBoundsStructPointer => "Message contains out-of-bounds struct pointer.",
+            OutOfBoundsListPointer => "Message contains out-of-bounds list pointer.",
+            OutOfBoundsTextPointer => "Message contains out-of-bounds text pointer.",
+            OutOfBoundsDataPointer => "Message contains out-of-bounds data pointer.",
+            InvalidSegmentId(_) => "Message contains far pointer to unknown segment.",
+            MalformedDoubleFarPointer =>
+                "Message contains double-far pointer whose landing pad is not a far pointer.",
+            NestingLimitExceeded =>
+                "Message is too deeply-nested or contains cycles. See ReaderOptions.",
+            ReadLimitExceeded =>
+                "Exceeded message traversal limit. See ReaderOptions.",
+            AmplifiedListPointer => "Message contains amplified list pointer.",
+            UnexpectedFarPointer => "Unexpected FAR pointer.",
+            UnknownPointerType => "Unknown pointer type.",
+            ExpectedStructPointer =>
+                "Message contains non-struct pointer where struct pointer was expected.",
+            ExpectedListPointer =>
+                "Message contains non-list pointer where list pointer was expected.",
+            ExpectedCapabilityPointer =>
+                "Message contains non-capability pointer where capability pointer was expected.",
+            NullCapabilityPointer => "Message contains null capability pointer.",
+            InvalidCapabilityPointer(_) => "Message contains invalid capability pointer.",
+            InlineCompositeNonStruct => "InlineComposite lists of non-STRUCT type are not supported.",
+            InlineCompositeOverrun => "InlineComposite list's elements overrun its word count.",
+            IncompatibleListElementSize => "Message contains list with incompatible element type.",
+            ExpectedByteList =>
+                "Message contains list pointer of non-bytes where text or data was expected.",
+            TextNotNulTerminated => "Message contains text that is not NUL-terminated.",
+            TextNotUtf8 => "Message contains text that is not valid UTF-8.",
+            CanonicalCapability => "Cannot create a canonical message with a capability.",
+            CapabilityComparison => "Cannot compare or hash messages that contain capabilities.",
+            SegmentLoadFailed => "Failed to read the rest of the message from its input stream.",
+            NoSuchField => "Struct has no field with that name.",
+            DynamicTypeMismatch => "Value does not match the type given by the schema.",
+            UnknownSchemaNode(_) => "Schema node has not been loaded.",
+            InactiveUnionMember => "Union member is not the one that is currently set.",
+        }
+    }
+}
+
+impl std::fmt::Show for Error {
+    fn fmt(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            InvalidSegmentId(id) => write!(f, "{} (segment id {})", self.description(), id),
+            InvalidCapabilityPointer(index) =>
+                write!(f, "{} (index {})", self.description(), index),
+            UnknownSchemaNode(id) => write!(f, "{} (id {:x})", self.description(), id),
+            _ => write!(f, "{}", self.description()),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -281,3 +281,36 @@ impl <'a> BufferedOutputStream for ArrayOutputStream<'a> {
         }
     }
 }
+
+// Discards everything written to it, keeping count of how many bytes
+// went by. Useful for measuring the output of a `PackedOutputStream`.
+pub struct CountingOutputStream {
+    buf : Vec<u8>,
+    pub count : uint,
+}
+
+impl CountingOutputStream {
+    pub fn new() -> CountingOutputStream {
+        CountingOutputStream { buf : Vec::from_elem(8192, 0u8), count : 0 }
+    }
+}
+
+impl Writer for CountingOutputStream {
+    fn write(&mut self, buf : &[u8]) -> IoResult<()> {
+        self.count += buf.len();
+        Ok(())
+    }
+}
+
+impl BufferedOutputStream for CountingOutputStream {
+    unsafe fn get_write_buffer(&mut self) -> (*mut u8, *mut u8) {
+        let len = self.buf.len();
+        (self.buf.as_mut_ptr(), self.buf.as_mut_slice().unsafe_mut_ref(len) as *mut u8)
+    }
+    unsafe fn write_ptr(&mut self, _ptr : *mut u8, size : uint) -> IoResult<()> {
+        //# Whatever was written is discarded, so the whole buffer is
+        //# available again.
+        self.count += size;
+        Ok(())
+    }
+}
@@ -10,7 +10,11 @@ use endian::{WireValue, Endian};
 use mask::*;
 use arena::*;
 use blob::*;
+use error::*;
+use message;
 use std;
+use std::vec::Vec;
+use visitor::Visitor;
 
 #[repr(u8)]
 #[deriving(Eq)]
@@ -314,29 +318,37 @@ struct SegmentAnd<T> {
 }
 
 macro_rules! require(
-    ($condition:expr, $segment:expr, $message:expr, $fail:stmt) => (
+    ($condition:expr, $segment:expr, $error:expr, $fail:stmt) => (
         if !($condition) {
-            error!($message);
-            if ($segment).arena.fail_fast() {
-                fail!();
-            }
-            $fail
+            require_fail!($segment, $error, $fail);
         }
         );
     )
 
 macro_rules! require_fail(
-    ($segment:expr, $message:expr, $fail:stmt) => (
+    ($segment:expr, $error:expr, $fail:stmt) => (
         {
-            error!($message);
+            let error : Error = $error;
+            error!("{}", error);
             if ($segment).arena.fail_fast() {
-                fail!();
+                return Err(error);
             }
             $fail;
         }
         );
     )
 
+macro_rules! require_ok(
+    ($result:expr, $segment:expr, $fail:stmt) => (
+        match $result {
+            Ok(value) => value,
+            Err(error) => {
+                require_fail!($segment, error, $fail);
+            }
+        }
+        );
+    )
+
 
 mod WireHelpers {
     use std;
@@ -345,6 +357,7 @@ mod WireHelpers {
     use layout::*;
     use arena::*;
     use blob::*;
+    use error::*;
 
     #[inline]
     pub fn round_bytes_up_to_words(bytes : ByteCount) -> WordCount {
@@ -362,7 +375,6 @@ mod WireHelpers {
         ((bits + 63) / (BITS_PER_WORD as u64)) as WordCount
     }
 
-    #[allow(dead_code)]
     #[inline]
     pub fn round_bits_up_to_bytes(bits : BitCount64) -> ByteCount {
         ((bits + 7) / (BITS_PER_BYTE as u64)) as ByteCount
@@ -370,9 +382,26 @@ mod WireHelpers {
 
     #[inline]
     pub unsafe fn bounds_check(segment : *SegmentReader,
-                               start : *Word, end : *Word) -> bool {
+                               start : *Word, end : *Word, error : Error) -> Result<()> {
         //# If segment is null, this is an unchecked message, so we don't do bounds checks.
-        return segment.is_null() || (*segment).contains_interval(start, end);
+        if segment.is_null() { return Ok(()) }
+
+        if !(*segment).contains_interval(start, end) {
+            return Err(error);
+        }
+
+        //# Every word we hand out is charged against the read limit, so that a
+        //# small malicious message can't make us do an unbounded amount of work.
+        amplified_read(segment, ptr_sub(end, start) as u64)
+    }
+
+    #[inline]
+    pub unsafe fn amplified_read(segment : *SegmentReader, virtual_amount : u64) -> Result<()> {
+        if segment.is_null() || (*segment).amplified_read(virtual_amount) {
+            Ok(())
+        } else {
+            Err(ReadLimitExceeded)
+        }
     }
 
     #[inline]
@@ -460,42 +489,50 @@ mod WireHelpers {
     #[inline]
     pub unsafe fn follow_fars(reff: &mut *WirePointer,
                               refTarget: *Word,
-                              segment : &mut *SegmentReader) -> *Word {
+                              segment : &mut *SegmentReader) -> Result<*Word> {
 
         //# If the segment is null, this is an unchecked message,
         //# so there are no FAR pointers.
         if !(*segment).is_null() && (**reff).kind() == WirePointerKind::Far {
-            *segment =
-                (**segment).arena.try_get_segment((**reff).far_ref().segment_id.get());
+            let far_segment_id = (**reff).far_ref().segment_id.get();
+            let far_segment = try!((**segment).arena.try_get_segment(far_segment_id));
+            if far_segment.is_null() {
+                return Err(InvalidSegmentId(far_segment_id));
+            }
 
-            let ptr : *Word = (**segment).get_start_ptr().offset(
+            let ptr : *Word = (*far_segment).get_start_ptr().offset(
                 (**reff).far_position_in_segment() as int);
 
             let padWords : int = if (**reff).is_double_far() { 2 } else { 1 };
-            require!(bounds_check(*segment, ptr, ptr.offset(padWords)),
-                     **segment,
-                     "Message contains out-of-bounds far pointer.",
-                     return std::ptr::null());
+            try!(bounds_check(far_segment, ptr, ptr.offset(padWords), OutOfBoundsFarPointer));
 
             let pad : *WirePointer = std::mem::transmute(ptr);
 
             if !(**reff).is_double_far() {
+                *segment = far_segment;
                 *reff = pad;
-                return (*pad).target();
+                return Ok((*pad).target());
             } else {
                 //# Landing pad is another far pointer. It is
                 //# followed by a tag describing the pointed-to
                 //# object.
 
-                *reff = pad.offset(1);
+                if (*pad).kind() != WirePointerKind::Far {
+                    return Err(MalformedDoubleFarPointer);
+                }
 
-                *segment =
-                    (**segment).arena.try_get_segment((*pad).far_ref().segment_id.get());
+                let tag_segment_id = (*pad).far_ref().segment_id.get();
+                let tag_segment = try!((*far_segment).arena.try_get_segment(tag_segment_id));
+                if tag_segment.is_null() {
+                    return Err(InvalidSegmentId(tag_segment_id));
+                }
 
-                return (**segment).get_start_ptr().offset((*pad).far_position_in_segment() as int);
+                *segment = tag_segment;
+                *reff = pad.offset(1);
+                return Ok((*tag_segment).get_start_ptr().offset((*pad).far_position_in_segment() as int));
             }
         } else {
-            return refTarget;
+            return Ok(refTarget);
         }
     }
 
@@ -505,10 +542,17 @@ mod WireHelpers {
         //# reachable.
 
         match (*reff).kind() {
-            WirePointerKind::Struct | WirePointerKind::List | WirePointerKind::Other => {
+            WirePointerKind::Struct | WirePointerKind::List => {
                 zero_object_helper(segment,
                                  reff, (*reff).mut_target())
             }
+            WirePointerKind::Other => {
+                if (*reff).is_capability() {
+                    (*(*segment).get_arena()).drop_cap((*reff).cap_ref().index.get());
+                } else {
+                    error!("{}", UnknownPointerType);
+                }
+            }
             WirePointerKind::Far => {
                 segment = (*(*segment).get_arena()).get_segment((*reff).far_ref().segment_id.get());
                 let pad : *mut WirePointer =
@@ -535,7 +579,7 @@ mod WireHelpers {
                                      tag : *mut WirePointer,
                                      ptr: *mut Word) {
         match (*tag).kind() {
-            WirePointerKind::Other => { fail!("Don't know how to handle OTHER") }
+            WirePointerKind::Other => { error!("{}", UnknownPointerType) }
             WirePointerKind::Struct => {
                 let pointerSection : *mut WirePointer =
                     std::mem::transmute(
@@ -569,8 +613,10 @@ mod WireHelpers {
                     InlineComposite => {
                         let elementTag : *mut WirePointer = std::mem::transmute(ptr);
 
-                        assert!((*elementTag).kind() == WirePointerKind::Struct,
-                                "Don't know how to handle non-STRUCT inline composite");
+                        if (*elementTag).kind() != WirePointerKind::Struct {
+                            error!("{}", InlineCompositeNonStruct);
+                            return;
+                        }
 
                         let data_size = (*elementTag).struct_ref().data_size.get();
                         let pointer_count = (*elementTag).struct_ref().ptr_count.get();
@@ -590,7 +636,7 @@ mod WireHelpers {
                     }
                 }
             }
-            WirePointerKind::Far => { fail!("Unexpected FAR pointer") }
+            WirePointerKind::Far => { error!("{}", UnexpectedFarPointer) }
         }
     }
 
@@ -611,30 +657,31 @@ mod WireHelpers {
 
     pub unsafe fn total_size(mut segment : *SegmentReader,
                              mut reff : *WirePointer,
-                             mut nesting_limit : int) -> MessageSize {
+                             mut nesting_limit : int) -> Result<MessageSize> {
         let mut result = MessageSize { word_count : 0, cap_count : 0};
 
-        if (*reff).is_null() { return result };
+        if (*reff).is_null() { return Ok(result) };
 
-        require!(nesting_limit > 0, *segment, "Message is too deeply nested.", return result);
+        require!(nesting_limit > 0, *segment, NestingLimitExceeded, return Ok(result));
 
         nesting_limit -= 1;
 
-        let ptr = follow_fars(&mut reff, (*reff).target(), &mut segment);
+        let ptr = require_ok!(follow_fars(&mut reff, (*reff).target(), &mut segment),
+                             *segment, return Ok(result));
 
         match (*reff).kind() {
             WirePointerKind::Struct => {
-                require!(bounds_check(segment, ptr, ptr.offset((*reff).struct_ref().word_size() as int)),
-                         *segment,
-                        "Message contains out-of-bounds struct pointer.",
-                         return result);
+                require_ok!(bounds_check(segment, ptr, ptr.offset((*reff).struct_ref().word_size() as int),
+                                         OutOfBoundsStructPointer),
+                            *segment,
+                            return Ok(result));
                 result.word_count += (*reff).struct_ref().word_size() as u64;
 
                 let pointer_section : *WirePointer =
                     std::mem::transmute(ptr.offset((*reff).struct_ref().data_size.get() as int));
                 let count : int = (*reff).struct_ref().ptr_count.get() as int;
                 for i in range(0, count) {
-                    result.plus_eq(total_size(segment, pointer_section.offset(i), nesting_limit));
+                    result.plus_eq(try!(total_size(segment, pointer_section.offset(i), nesting_limit)));
                 }
             }
             WirePointerKind::List => {
@@ -644,34 +691,34 @@ mod WireHelpers {
                         let total_words = round_bits_up_to_words(
                             (*reff).list_ref().element_count() as u64 *
                                 data_bits_per_element((*reff).list_ref().element_size()) as u64);
-                        require!(bounds_check(segment, ptr, ptr.offset(total_words as int)),
-                                 *segment,
-                                 "Message contains out-of-bounds list pointer.",
-                                 return result);
+                        require_ok!(bounds_check(segment, ptr, ptr.offset(total_words as int),
+                                                 OutOfBoundsListPointer),
+                                    *segment,
+                                    return Ok(result));
                         result.word_count += total_words as u64;
                     }
                     Pointer => {
                         let count = (*reff).list_ref().element_count();
-                        require!(bounds_check(segment, ptr, ptr.offset((count * WORDS_PER_POINTER) as int)),
-                                 *segment,
-                                 "Message contains out-of-bounds list pointer.",
-                                 return result);
+                        require_ok!(bounds_check(segment, ptr, ptr.offset((count * WORDS_PER_POINTER) as int),
+                                                 OutOfBoundsListPointer),
+                                    *segment,
+                                    return Ok(result));
 
                         result.word_count += count as u64 * WORDS_PER_POINTER as u64;
 
                         for i in range(0, count as int) {
                             result.plus_eq(
-                                total_size(segment, std::mem::transmute::<*Word,*WirePointer>(ptr).offset(i),
-                                           nesting_limit));
+                                try!(total_size(segment, std::mem::transmute::<*Word,*WirePointer>(ptr).offset(i),
+                                                nesting_limit)));
                         }
                     }
                     InlineComposite => {
                         let word_count = (*reff).list_ref().inline_composite_word_count();
-                        require!(bounds_check(segment, ptr,
-                                              ptr.offset(word_count as int + POINTER_SIZE_IN_WORDS as int)),
-                                 *segment,
-                                 "Message contains out-of-bounds list pointer.",
-                                 return result);
+                        require_ok!(bounds_check(segment, ptr,
+                                                 ptr.offset(word_count as int + POINTER_SIZE_IN_WORDS as int),
+                                                 OutOfBoundsListPointer),
+                                    *segment,
+                                    return Ok(result));
 
                         result.word_count += word_count as u64 + POINTER_SIZE_IN_WORDS as u64;
 
@@ -680,13 +727,13 @@ mod WireHelpers {
 
                         require!((*element_tag).kind() == WirePointerKind::Struct,
                                  *segment,
-                                 "Don't know how to handle non-STRUCT inline composite.",
-                                 return result);
+                                 InlineCompositeNonStruct,
+                                 return Ok(result));
 
                         require!((*element_tag).struct_ref().word_size() * count <= word_count,
                                  *segment,
-                                 "InlineComposite list's elements overrun its word count",
-                                 return result);
+                                 InlineCompositeOverrun,
+                                 return Ok(result));
 
                         let data_size = (*element_tag).struct_ref().data_size.get();
                         let pointer_count = (*element_tag).struct_ref().ptr_count.get();
@@ -697,8 +744,8 @@ mod WireHelpers {
 
                             for _ in range(0, pointer_count) {
                                 result.plus_eq(
-                                    total_size(segment, std::mem::transmute::<*Word,*WirePointer>(pos),
-                                               nesting_limit));
+                                    try!(total_size(segment, std::mem::transmute::<*Word,*WirePointer>(pos),
+                                                    nesting_limit)));
                                 pos = pos.offset(POINTER_SIZE_IN_WORDS as int);
                             }
                         }
@@ -706,18 +753,166 @@ mod WireHelpers {
                 }
             }
             WirePointerKind::Far => {
-                fail!("Unexpected FAR pointer.");
+                require_fail!(*segment, UnexpectedFarPointer, return Ok(result));
             }
             WirePointerKind::Other => {
                 if (*reff).is_capability() {
                     result.cap_count += 1;
                 } else {
-                    require_fail!(*segment, "Unknown pointer type.", return result);
+                    require_fail!(*segment, UnknownPointerType, return Ok(result));
                 }
             }
         }
 
-        result
+        Ok(result)
+    }
+
+    // Walks the object that `reff` points to in preorder, reporting what it
+    // finds to `visitor`. Follows the same checks as total_size().
+    pub unsafe fn visit(mut segment : *SegmentReader,
+                        mut reff : *WirePointer,
+                        mut nesting_limit : int,
+                        visitor : &mut Visitor) -> Result<()> {
+        if (*reff).is_null() { return Ok(()) };
+
+        require!(nesting_limit > 0, *segment, NestingLimitExceeded, return Ok(()));
+
+        nesting_limit -= 1;
+
+        if !segment.is_null() && (*reff).kind() == WirePointerKind::Far {
+            visitor.visit_far_pointer((*reff).far_ref().segment_id.get(), (*reff).is_double_far());
+        }
+
+        let ptr = require_ok!(follow_fars(&mut reff, (*reff).target(), &mut segment),
+                              *segment, return Ok(()));
+
+        match (*reff).kind() {
+            WirePointerKind::Struct => {
+                require_ok!(bounds_check(segment, ptr, ptr.offset((*reff).struct_ref().word_size() as int),
+                                         OutOfBoundsStructPointer),
+                            *segment,
+                            return Ok(()));
+
+                let data_size = (*reff).struct_ref().data_size.get();
+                let count = (*reff).struct_ref().ptr_count.get();
+                visitor.visit_struct(data_size as WordCount, count as WirePointerCount);
+
+                let pointer_section : *WirePointer = std::mem::transmute(ptr.offset(data_size as int));
+                for i in range(0, count as int) {
+                    try!(visit(segment, pointer_section.offset(i), nesting_limit, visitor));
+                }
+                visitor.leave_struct();
+            }
+            WirePointerKind::List => {
+                let element_size = (*reff).list_ref().element_size();
+                match element_size {
+                    Void | Bit | Byte | TwoBytes | FourBytes | EightBytes => {
+                        let count = (*reff).list_ref().element_count();
+                        let total_words = round_bits_up_to_words(
+                            count as u64 * data_bits_per_element(element_size) as u64);
+                        require_ok!(bounds_check(segment, ptr, ptr.offset(total_words as int),
+                                                 OutOfBoundsListPointer),
+                                    *segment,
+                                    return Ok(()));
+                        if element_size == Void {
+                            require!(amplified_read(segment, count as u64).is_ok(),
+                                     *segment,
+                                     AmplifiedListPointer,
+                                     return Ok(()));
+                        }
+
+                        if element_size == Byte {
+                            std::slice::raw::buf_as_slice(std::mem::transmute::<*Word,*u8>(ptr), count, |bytes| {
+                                let text = if count > 0 && bytes[count - 1] == 0 {
+                                    std::str::from_utf8(bytes.slice_to(count - 1))
+                                } else {
+                                    None
+                                };
+                                match text {
+                                    Some(text) => visitor.visit_text(text),
+                                    None => visitor.visit_data(bytes)
+                                }
+                            });
+                        } else {
+                            visitor.visit_list(element_size, count);
+                            visitor.leave_list();
+                        }
+                    }
+                    Pointer => {
+                        let count = (*reff).list_ref().element_count();
+                        require_ok!(bounds_check(segment, ptr, ptr.offset((count * WORDS_PER_POINTER) as int),
+                                                 OutOfBoundsListPointer),
+                                    *segment,
+                                    return Ok(()));
+
+                        visitor.visit_list(Pointer, count);
+                        for i in range(0, count as int) {
+                            try!(visit(segment, std::mem::transmute::<*Word,*WirePointer>(ptr).offset(i),
+                                       nesting_limit, visitor));
+                        }
+                        visitor.leave_list();
+                    }
+                    InlineComposite => {
+                        let word_count = (*reff).list_ref().inline_composite_word_count();
+                        require_ok!(bounds_check(segment, ptr,
+                                                 ptr.offset(word_count as int + POINTER_SIZE_IN_WORDS as int),
+                                                 OutOfBoundsListPointer),
+                                    *segment,
+                                    return Ok(()));
+
+                        let element_tag : *WirePointer = std::mem::transmute(ptr);
+                        let count = (*element_tag).inline_composite_list_element_count();
+
+                        require!((*element_tag).kind() == WirePointerKind::Struct,
+                                 *segment,
+                                 InlineCompositeNonStruct,
+                                 return Ok(()));
+
+                        require!((*element_tag).struct_ref().word_size() * count <= word_count,
+                                 *segment,
+                                 InlineCompositeOverrun,
+                                 return Ok(()));
+
+                        if (*element_tag).struct_ref().word_size() == 0 {
+                            require!(amplified_read(segment, count as u64).is_ok(),
+                                     *segment,
+                                     AmplifiedListPointer,
+                                     return Ok(()));
+                        }
+
+                        let data_size = (*element_tag).struct_ref().data_size.get();
+                        let pointer_count = (*element_tag).struct_ref().ptr_count.get();
+
+                        visitor.visit_list(InlineComposite, count);
+                        let mut pos : *Word = ptr.offset(POINTER_SIZE_IN_WORDS as int);
+                        for _ in range(0, count) {
+                            visitor.visit_struct(data_size as WordCount, pointer_count as WirePointerCount);
+                            pos = pos.offset(data_size as int);
+
+                            for _ in range(0, pointer_count) {
+                                try!(visit(segment, std::mem::transmute::<*Word,*WirePointer>(pos),
+                                           nesting_limit, visitor));
+                                pos = pos.offset(POINTER_SIZE_IN_WORDS as int);
+                            }
+                            visitor.leave_struct();
+                        }
+                        visitor.leave_list();
+                    }
+                }
+            }
+            WirePointerKind::Far => {
+                require_fail!(*segment, UnexpectedFarPointer, return Ok(()));
+            }
+            WirePointerKind::Other => {
+                if (*reff).is_capability() {
+                    visitor.visit_capability((*reff).cap_ref().index.get() as uint);
+                } else {
+                    require_fail!(*segment, UnknownPointerType, return Ok(()));
+                }
+            }
+        }
+
+        Ok(())
     }
 
     pub unsafe fn transfer_pointer(dst_segment : *mut SegmentBuilder, dst : *mut WirePointer,
@@ -750,7 +945,14 @@ mod WireHelpers {
         // Like the other transfer_pointer, but splits src into a tag and a
         // target. Particularly useful for OrphanBuilder.
 
-        if dst_segment == src_segment {
+        if (*src_tag).kind() == WirePointerKind::Struct && (*src_tag).struct_ref().word_size() == 0 {
+            //# Zero-sized structs are never allocated anywhere, so there
+            //# is nothing to point at.
+            (*dst).set_kind_and_target_for_empty_struct();
+            std::ptr::copy_nonoverlapping_memory(&mut (*dst).upper32bits,
+                                                 &(*src_tag).upper32bits,
+                                                 1);
+        } else if dst_segment == src_segment {
             //# Same segment, so create a direct pointer.
             (*dst).set_kind_and_target((*src_tag).kind(), src_ptr, dst_segment);
 
@@ -767,7 +969,19 @@ mod WireHelpers {
             match (*src_segment).allocate(1) {
                 None => {
                     //# Darn, need a double-far.
-                    fail!("unimplemented");
+                    let (far_segment, landing_pad_word) = (*(*src_segment).get_arena()).allocate(2);
+                    let landing_pad : *mut WirePointer = std::mem::transmute(landing_pad_word);
+
+                    (*landing_pad).set_far(false, (*src_segment).get_word_offset_to(src_ptr));
+                    (*landing_pad).mut_far_ref().set((*src_segment).get_segment_id());
+
+                    let tag = landing_pad.offset(1);
+                    (*tag).set_kind_with_zero_offset((*src_tag).kind());
+                    std::ptr::copy_nonoverlapping_memory(&mut (*tag).upper32bits,
+                                                         & (*src_tag).upper32bits, 1);
+
+                    (*dst).set_far(true, (*far_segment).get_word_offset_to(landing_pad_word));
+                    (*dst).mut_far_ref().set((*far_segment).get_segment_id());
                 }
                 Some(landing_pad_word) => {
                     //# Simple landing pad is just a pointer.
@@ -804,19 +1018,20 @@ mod WireHelpers {
 
     #[inline]
     pub unsafe fn get_writable_struct_pointer<'a>(mut reff : *mut WirePointer,
+                                                  mut ref_target : *mut Word,
                                                   mut segment : *mut SegmentBuilder,
                                                   size : StructSize,
-                                                  default_value : *Word) -> StructBuilder<'a> {
-        let ref_target = (*reff).mut_target();
+                                                  mut default_value : *Word) -> Result<StructBuilder<'a>> {
         let mut first_time = true;
 
         'use_default: loop {
             if (*reff).is_null() | !first_time {
                 if default_value.is_null() ||
                     (*std::mem::transmute::<*Word,*WirePointer>(default_value)).is_null() {
-                        return init_struct_pointer(reff, segment, size);
+                        return Ok(init_struct_pointer(reff, segment, size));
                     }
-                unimplemented!()
+                ref_target = try!(copy_default_value(segment, reff, default_value));
+                default_value = std::ptr::null();
             }
             first_time = false;
 
@@ -825,7 +1040,7 @@ mod WireHelpers {
             let old_ptr = follow_builder_fars(&mut old_ref, ref_target, &mut old_segment);
             require!((*old_ref).kind() == WirePointerKind::Struct,
                      (*segment).reader,
-                     "Message contains non-struct pointer where struct pointer was expected.",
+                     ExpectedStructPointer,
                      continue 'use_default);
 
             let old_data_size = (*old_ref).struct_ref().data_size.get();
@@ -865,23 +1080,23 @@ mod WireHelpers {
 
                 std::ptr::zero_memory(old_ptr, old_data_size as uint + old_pointer_count as uint);
 
-                return StructBuilder {
+                return Ok(StructBuilder {
                     segment : segment,
                     data : std::mem::transmute(ptr),
                     pointers : new_pointer_section,
                     data_size : new_data_size as u32 * BITS_PER_WORD as u32,
                     pointer_count : new_pointer_count,
                     bit0offset : 0
-                };
+                });
             } else {
-                return StructBuilder {
+                return Ok(StructBuilder {
                     segment : old_segment,
                     data : std::mem::transmute(old_ptr),
                     pointers : old_pointer_section,
                     data_size : old_data_size as u32 * BITS_PER_WORD as u32,
                     pointer_count : old_pointer_count,
                     bit0offset : 0
-                };
+                });
             }
         }
     }
@@ -950,14 +1165,13 @@ mod WireHelpers {
 
     #[inline]
     pub unsafe fn get_writable_list_pointer<'a>(orig_ref : *mut WirePointer,
+                                                mut orig_ref_target : *mut Word,
                                                 orig_segment : *mut SegmentBuilder,
                                                 element_size : FieldSize,
-                                                default_value : *Word) -> ListBuilder<'a> {
+                                                mut default_value : *Word) -> Result<ListBuilder<'a>> {
         assert!(element_size != InlineComposite,
                 "Use get_struct_list_{element,field}() for structs");
 
-        let orig_ref_target = (*orig_ref).mut_target();
-
         let mut first_time = true;
 
         'use_default : loop {
@@ -965,9 +1179,10 @@ mod WireHelpers {
             if (*orig_ref).is_null() || !first_time {
                 if default_value.is_null() ||
                     (*std::mem::transmute::<*Word,*WirePointer>(default_value)).is_null() {
-                        return ListBuilder::new_default();
+                        return Ok(ListBuilder::new_default());
                     }
-                unimplemented!()
+                orig_ref_target = try!(copy_default_value(orig_segment, orig_ref, default_value));
+                default_value = std::ptr::null();
             }
             first_time = false;
 
@@ -982,7 +1197,7 @@ mod WireHelpers {
             let mut ptr = follow_builder_fars(&mut reff, orig_ref_target, &mut segment);
 
             require!((*reff).kind() == WirePointerKind::List, (*segment).reader,
-                     "Called get_list_\\{field,element\\}() but existing pointer is not a list",
+                     ExpectedListPointer,
                      continue 'use_default);
 
             let old_size = (*reff).list_ref().element_size();
@@ -1002,7 +1217,7 @@ mod WireHelpers {
 
                 // capnproto-c++ doesn't go to useDefault here --- why not?
                 require!((*tag).kind() == WirePointerKind::Struct, (*segment).reader,
-                         "InlineComposite list with non-STRUCT elements not supported.",
+                         InlineCompositeNonStruct,
                          continue 'use_default);
 
                 ptr = ptr.offset(POINTER_SIZE_IN_WORDS as int);
@@ -1014,12 +1229,12 @@ mod WireHelpers {
                     Void => {} //# Anything is a valid upgrade from Void.
                     Bit | Byte | TwoBytes | FourBytes | EightBytes => {
                         require!(data_size >= 1, (*segment).reader,
-                                 "Existing list value is incompatible with expected type.",
+                                 IncompatibleListElementSize,
                                  continue 'use_default);
                     }
                     Pointer => {
                         require!(pointer_count >= 1, (*segment).reader,
-                                 "Existing list value is incompatible with expected type.",
+                                 IncompatibleListElementSize,
                                  continue 'use_default);
                         //# Adjust the pointer to point at the reference segment.
                         ptr = ptr.offset(data_size as int);
@@ -1030,56 +1245,57 @@ mod WireHelpers {
                 }
                 //# OK, looks valid.
 
-                return ListBuilder {
+                return Ok(ListBuilder {
                     segment : segment,
                     ptr : std::mem::transmute(ptr),
                     element_count : (*tag).inline_composite_list_element_count(),
                     step : (*tag).struct_ref().word_size() * BITS_PER_WORD,
                     struct_data_size : data_size as u32 * BITS_PER_WORD as u32,
                     struct_pointer_count : pointer_count
-                };
+                });
             } else {
                 let data_size = data_bits_per_element(old_size);
                 let pointer_count = pointers_per_element(old_size);
 
                 require!(data_size >= data_bits_per_element(element_size),
                          (*segment).reader,
-                         "Existing list value is incompatible with expected type.",
+                         IncompatibleListElementSize,
                          continue 'use_default);
                 require!(pointer_count >= pointers_per_element(element_size),
                          (*segment).reader,
-                         "Existing list value is incompatible with expected type.",
+                         IncompatibleListElementSize,
                          continue 'use_default);
 
                 let step = data_size + pointer_count * BITS_PER_POINTER;
 
-                return ListBuilder {
+                return Ok(ListBuilder {
                     segment : segment,
                     ptr : std::mem::transmute(ptr),
                     step : step,
                     element_count : (*reff).list_ref().element_count(),
                     struct_data_size : data_size as u32,
                     struct_pointer_count : pointer_count as u16
-                };
+                });
             }
         }
     }
 
     #[inline]
-    pub unsafe fn get_writable_struct_list_pointer<'a>(orig_ref : *mut WirePointer,
-                                                       orig_segment : *mut SegmentBuilder,
+    pub unsafe fn get_writable_struct_list_pointer<'a>(mut orig_ref : *mut WirePointer,
+                                                       mut orig_ref_target : *mut Word,
+                                                       mut orig_segment : *mut SegmentBuilder,
                                                        element_size : StructSize,
-                                                       default_value : *Word) -> ListBuilder<'a> {
-        let orig_ref_target = (*orig_ref).mut_target();
+                                                       mut default_value : *Word) -> Result<ListBuilder<'a>> {
         let mut first_time = true;
 
         'use_default : loop {
             if (*orig_ref).is_null() || !first_time {
                 if default_value.is_null() ||
                     (*std::mem::transmute::<*Word,*WirePointer>(default_value)).is_null() {
-                        return ListBuilder::new_default();
+                        return Ok(ListBuilder::new_default());
                     }
-                unimplemented!()
+                orig_ref_target = try!(copy_default_value(orig_segment, orig_ref, default_value));
+                default_value = std::ptr::null();
             }
             first_time = false;
 
@@ -1092,7 +1308,7 @@ mod WireHelpers {
             let mut old_ptr = follow_builder_fars(&mut old_ref, orig_ref_target, &mut old_segment);
 
             require!((*old_ref).kind() == WirePointerKind::List, (*old_segment).reader,
-                     "Called getList\\{Field,Element\\} but existing pointer is not a list.",
+                     ExpectedListPointer,
                      continue 'use_default);
 
             let old_size = (*old_ref).list_ref().element_size();
@@ -1103,7 +1319,7 @@ mod WireHelpers {
                 let old_tag : *WirePointer = std::mem::transmute(old_ptr);
                 old_ptr = old_ptr.offset(POINTER_SIZE_IN_WORDS as int);
                 require!((*old_tag).kind() == WirePointerKind::Struct, (*old_segment).reader,
-                         "InlineComposite list with non-STRUCT elements not supported.",
+                         InlineCompositeNonStruct,
                          continue 'use_default);
 
                 let old_data_size = (*old_tag).struct_ref().data_size.get();
@@ -1113,37 +1329,160 @@ mod WireHelpers {
 
                 if old_data_size >= element_size.data && old_pointer_count >= element_size.pointers {
                     //# Old size is at least as large as we need. Ship it.
-                    return ListBuilder {
+                    return Ok(ListBuilder {
                         segment : old_segment,
                         ptr : std::mem::transmute(old_ptr),
                         element_count : element_count,
                         step : old_step * BITS_PER_WORD,
                         struct_data_size : old_data_size as u32 * BITS_PER_WORD as u32,
                         struct_pointer_count : old_pointer_count
-                    };
+                    });
                 }
 
                 //# The structs in this list are smaller than expected,
                 //# probably written using an older version of the
                 //# protocol. We need to make a copy and expand them.
 
-                unimplemented!();
+                let new_data_size = std::cmp::max(old_data_size, element_size.data);
+                let new_pointer_count = std::cmp::max(old_pointer_count, element_size.pointers);
+                let new_step = new_data_size as uint + new_pointer_count as uint * WORDS_PER_POINTER;
+                let total_size = new_step * element_count;
+
+                //# Don't let allocate() zero out the object just yet.
+                zero_pointer_and_fars(orig_segment, orig_ref);
+
+                let mut new_ptr = allocate(&mut orig_ref, &mut orig_segment,
+                                           total_size + POINTER_SIZE_IN_WORDS, WirePointerKind::List);
+                (*orig_ref).mut_list_ref().set_inline_composite(total_size);
+
+                let new_tag : *mut WirePointer = std::mem::transmute(new_ptr);
+                (*new_tag).set_kind_and_inline_composite_list_element_count(WirePointerKind::Struct,
+                                                                            element_count);
+                (*new_tag).mut_struct_ref().set(new_data_size, new_pointer_count);
+                new_ptr = new_ptr.offset(POINTER_SIZE_IN_WORDS as int);
+
+                let mut src : *mut Word = old_ptr;
+                let mut dst : *mut Word = new_ptr;
+                for _ in range(0, element_count) {
+                    //# Copy data section.
+                    std::ptr::copy_nonoverlapping_memory(dst, src as *Word, old_data_size as uint);
+
+                    //# Copy pointer section.
+                    let new_pointer_section : *mut WirePointer =
+                        std::mem::transmute(dst.offset(new_data_size as int));
+                    let old_pointer_section : *mut WirePointer =
+                        std::mem::transmute(src.offset(old_data_size as int));
+                    for i in range::<int>(0, old_pointer_count as int) {
+                        transfer_pointer(orig_segment, new_pointer_section.offset(i),
+                                         old_segment, old_pointer_section.offset(i));
+                    }
+
+                    dst = dst.offset(new_step as int);
+                    src = src.offset(old_step as int);
+                }
+
+                //# Zero out old location. See explanation in get_writable_struct_pointer().
+                //# Make sure to include the tag word.
+                std::ptr::zero_memory(old_ptr.offset(-1),
+                                      1 + old_step * element_count);
+
+                return Ok(ListBuilder {
+                    segment : orig_segment,
+                    ptr : std::mem::transmute(new_ptr),
+                    element_count : element_count,
+                    step : new_step * BITS_PER_WORD,
+                    struct_data_size : new_data_size as u32 * BITS_PER_WORD as u32,
+                    struct_pointer_count : new_pointer_count
+                });
             } else if old_size == element_size.preferred_list_encoding {
                 //# Old size matches exactly.
 
                 let data_size = data_bits_per_element(old_size);
                 let pointer_count = pointers_per_element(old_size);
                 let step = data_size + pointer_count * BITS_PER_POINTER;
-                return ListBuilder {
+                return Ok(ListBuilder {
                     segment : old_segment,
                     ptr : std::mem::transmute(old_ptr),
                     step : step,
                     element_count : (*old_ref).list_ref().element_count(),
                     struct_data_size : data_size as u32,
                     struct_pointer_count : pointer_count as u16
-                };
+                });
             } else {
-                unimplemented!()
+                let old_data_size = data_bits_per_element(old_size);
+                let old_pointer_count = pointers_per_element(old_size);
+                let old_step = old_data_size + old_pointer_count * BITS_PER_POINTER;
+                let element_count = (*old_ref).list_ref().element_count();
+
+                if old_size == Void {
+                    //# Nothing to copy, just allocate a new list.
+                    return Ok(init_struct_list_pointer(orig_ref, orig_segment, element_count, element_size));
+                }
+
+                //# Upgrading to an inline composite list.
+
+                //# A bit can't be addressed as the first byte of a struct's data section.
+                require!(old_size != Bit, (*old_segment).reader,
+                         IncompatibleListElementSize,
+                         continue 'use_default);
+
+                let mut new_data_size = element_size.data;
+                let mut new_pointer_count = element_size.pointers;
+
+                if old_size == Pointer {
+                    new_pointer_count = std::cmp::max(new_pointer_count, 1);
+                } else {
+                    //# Old list contains data elements, so we need at least 1 word of data.
+                    new_data_size = std::cmp::max(new_data_size, 1);
+                }
+
+                let new_step = new_data_size as uint + new_pointer_count as uint * WORDS_PER_POINTER;
+                let total_words = element_count * new_step;
+
+                //# Don't let allocate() zero out the object just yet.
+                zero_pointer_and_fars(orig_segment, orig_ref);
+
+                let mut new_ptr = allocate(&mut orig_ref, &mut orig_segment,
+                                           total_words + POINTER_SIZE_IN_WORDS, WirePointerKind::List);
+                (*orig_ref).mut_list_ref().set_inline_composite(total_words);
+
+                let tag : *mut WirePointer = std::mem::transmute(new_ptr);
+                (*tag).set_kind_and_inline_composite_list_element_count(WirePointerKind::Struct,
+                                                                        element_count);
+                (*tag).mut_struct_ref().set(new_data_size, new_pointer_count);
+                new_ptr = new_ptr.offset(POINTER_SIZE_IN_WORDS as int);
+
+                if old_size == Pointer {
+                    let mut dst : *mut Word = new_ptr.offset(new_data_size as int);
+                    let mut src : *mut WirePointer = std::mem::transmute(old_ptr);
+                    for _ in range(0, element_count) {
+                        transfer_pointer(orig_segment, std::mem::transmute(dst), old_segment, src);
+                        dst = dst.offset(new_step as int);
+                        src = src.offset(1);
+                    }
+                } else {
+                    let mut dst : *mut u8 = std::mem::transmute(new_ptr);
+                    let mut src : *u8 = std::mem::transmute(old_ptr);
+                    let old_byte_step = old_data_size / BITS_PER_BYTE;
+                    for _ in range(0, element_count) {
+                        std::ptr::copy_nonoverlapping_memory(dst, src, old_byte_step);
+                        src = src.offset(old_byte_step as int);
+                        dst = dst.offset((new_step * BYTES_PER_WORD) as int);
+                    }
+                }
+
+                //# Zero out old location. See explanation in get_writable_struct_pointer().
+                std::ptr::zero_memory(std::mem::transmute::<*mut Word,*mut u8>(old_ptr),
+                                      round_bits_up_to_bytes(old_step as u64 * element_count as u64));
+
+                return Ok(ListBuilder {
+                    segment : orig_segment,
+                    ptr : std::mem::transmute(new_ptr),
+                    element_count : element_count,
+                    step : new_step * BITS_PER_WORD,
+                    struct_data_size : new_data_size as u32 * BITS_PER_WORD as u32,
+                    struct_pointer_count : new_pointer_count
+                });
             }
         }
     }
@@ -1179,9 +1518,10 @@ mod WireHelpers {
 
     #[inline]
     pub unsafe fn get_writable_text_pointer<'a>(mut reff : *mut WirePointer,
+                                                ref_target : *mut Word,
                                                 mut segment : *mut SegmentBuilder,
                                                 default_value : *Word,
-                                                default_size : ByteCount) -> Text::Builder<'a> {
+                                                default_size : ByteCount) -> Result<Text::Builder<'a>> {
         unsafe fn use_default<'a>(reff : *mut WirePointer,
                                   segment : *mut SegmentBuilder,
                                   default_value : *Word, default_size : ByteCount) -> Text::Builder<'a> {
@@ -1197,20 +1537,19 @@ mod WireHelpers {
         }
 
         if (*reff).is_null() {
-            return use_default(reff, segment, default_value, default_size);
+            return Ok(use_default(reff, segment, default_value, default_size));
         }
-        let ref_target = (*reff).mut_target();
         let ptr = follow_builder_fars(&mut reff, ref_target, &mut segment);
 
         require!((*reff).kind() == WirePointerKind::List, (*segment).reader,
-                "Called getText\\{Field,Element\\}() but existing pointer is not a list.",
-                 return use_default(reff, segment, default_value, default_size));
+                ExpectedListPointer,
+                 return Ok(use_default(reff, segment, default_value, default_size)));
         require!((*reff).list_ref().element_size() == Byte, (*segment).reader,
-                "Called getText\\{Field,Element\\}() but existing list pointer is not byte-sized.",
-                 return use_default(reff, segment, default_value, default_size));
+                ExpectedByteList,
+                 return Ok(use_default(reff, segment, default_value, default_size)));
 
         //# Subtract 1 from the size for the NUL terminator.
-        return Text::Builder::new(std::mem::transmute(ptr), (*reff).list_ref().element_count() - 1);
+        return Ok(Text::Builder::new(std::mem::transmute(ptr), (*reff).list_ref().element_count() - 1));
     }
 
     #[inline]
@@ -1239,9 +1578,10 @@ mod WireHelpers {
 
     #[inline]
     pub unsafe fn get_writable_data_pointer<'a>(mut reff : *mut WirePointer,
+                                                ref_target : *mut Word,
                                                 mut segment : *mut SegmentBuilder,
                                                 default_value : *Word,
-                                                default_size : ByteCount) -> Data::Builder<'a> {
+                                                default_size : ByteCount) -> Result<Data::Builder<'a>> {
         unsafe fn use_default<'a>(reff : *mut WirePointer,
                                   segment : *mut SegmentBuilder,
                                   default_value : *Word,
@@ -1258,44 +1598,68 @@ mod WireHelpers {
         }
 
         if (*reff).is_null() {
-            return use_default(reff, segment, default_value, default_size);
+            return Ok(use_default(reff, segment, default_value, default_size));
         }
-        let ref_target = (*reff).mut_target();
         let ptr = follow_builder_fars(&mut reff, ref_target, &mut segment);
 
         require!((*reff).kind() == WirePointerKind::List, (*segment).reader,
-                 "Called getData\\{Field,Element\\}() but existing pointer is not a list.",
-                 return use_default(reff, segment, default_value, default_size));
+                 ExpectedListPointer,
+                 return Ok(use_default(reff, segment, default_value, default_size)));
         require!((*reff).list_ref().element_size() == Byte, (*segment).reader,
-                 "Called getData\\{Field,Element\\}() but existing list pointer is not byte-sized.",
-                 return use_default(reff, segment, default_value, default_size));
+                 ExpectedByteList,
+                 return Ok(use_default(reff, segment, default_value, default_size)));
 
-        return Data::new_builder(std::mem::transmute(ptr), (*reff).list_ref().element_count());
+        return Ok(Data::new_builder(std::mem::transmute(ptr), (*reff).list_ref().element_count()));
     }
 
     pub unsafe fn set_struct_pointer<'a>(mut segment : *mut SegmentBuilder,
                                          mut reff : *mut WirePointer,
-                                         value : StructReader) -> super::SegmentAnd<*mut Word> {
-        let data_size : WordCount = round_bits_up_to_words(value.data_size as u64);
-        let total_size : WordCount = data_size + value.pointer_count as uint * WORDS_PER_POINTER;
+                                         value : StructReader,
+                                         canonical : bool) -> Result<super::SegmentAnd<*mut Word>> {
+        let mut data_bytes : ByteCount = round_bits_up_to_bytes(value.data_size as u64);
+        let mut pointer_count = value.pointer_count;
+
+        if canonical {
+            if value.data_size == 1 {
+                //# Handle the truncation case where it's a false in a 1-bit struct.
+                if !value.get_bool_field(0) {
+                    data_bytes = 0;
+                }
+            } else {
+                //# Truncate the data section.
+                while data_bytes > 0 && *value.data.offset(data_bytes as int - 1) == 0 {
+                    data_bytes -= 1;
+                }
+            }
+
+            //# Truncate the pointer section.
+            while pointer_count > 0 && (*value.pointers.offset(pointer_count as int - 1)).is_null() {
+                pointer_count -= 1;
+            }
+        }
+
+        let data_size : WordCount = round_bytes_up_to_words(data_bytes);
+        let total_size : WordCount = data_size + pointer_count as uint * WORDS_PER_POINTER;
 
         let ptr = allocate(&mut reff, &mut segment, total_size, WirePointerKind::Struct);
-        (*reff).mut_struct_ref().set(data_size as u16, value.pointer_count);
+        (*reff).mut_struct_ref().set(data_size as u16, pointer_count);
 
         if value.data_size == 1 {
-            *std::mem::transmute::<*mut Word, *mut u8>(ptr) = value.get_bool_field(0) as u8
+            //# Data size could be made 0 by truncation.
+            if data_bytes != 0 {
+                *std::mem::transmute::<*mut Word, *mut u8>(ptr) = value.get_bool_field(0) as u8
+            }
         } else {
-            std::ptr::copy_nonoverlapping_memory::<Word>(ptr, std::mem::transmute(value.data),
-                                                         value.data_size as uint / BITS_PER_WORD);
+            std::ptr::copy_nonoverlapping_memory::<u8>(std::mem::transmute(ptr), value.data, data_bytes);
         }
 
         let pointer_section : *mut WirePointer = std::mem::transmute(ptr.offset(data_size as int));
-        for i in range(0, value.pointer_count as int) {
-            copy_pointer(segment, pointer_section.offset(i), value.segment, value.pointers.offset(i),
-                         value.nesting_limit);
+        for i in range(0, pointer_count as int) {
+            try!(copy_pointer(segment, pointer_section.offset(i), value.segment, value.pointers.offset(i),
+                              value.nesting_limit, canonical));
         }
 
-        super::SegmentAnd { segment : segment, value : ptr }
+        Ok(super::SegmentAnd { segment : segment, value : ptr })
     }
 
     pub unsafe fn set_capability_pointer(segment : *mut SegmentBuilder,
@@ -1306,10 +1670,11 @@ mod WireHelpers {
 
     pub unsafe fn set_list_pointer<'a>(mut segment : *mut SegmentBuilder,
                                        mut reff : *mut WirePointer,
-                                       value : ListReader) -> super::SegmentAnd<*mut Word> {
+                                       value : ListReader,
+                                       canonical : bool) -> Result<super::SegmentAnd<*mut Word>> {
         let total_size = round_bits_up_to_words((value.element_count * value.step) as u64);
 
-        if value.step <= BITS_PER_WORD {
+        if value.element_size != InlineComposite {
             //# List of non-structs.
             let ptr = allocate(&mut reff, &mut segment, total_size, WirePointerKind::List);
 
@@ -1317,34 +1682,52 @@ mod WireHelpers {
                 //# List of pointers.
                 (*reff).mut_list_ref().set(Pointer, value.element_count);
                 for i in range(0, value.element_count as int) {
-                    copy_pointer(segment, std::mem::transmute::<*mut Word,*mut WirePointer>(ptr).offset(i),
-                                 value.segment, std::mem::transmute::<*u8,*WirePointer>(value.ptr).offset(i),
-                                 value.nesting_limit);
+                    try!(copy_pointer(segment, std::mem::transmute::<*mut Word,*mut WirePointer>(ptr).offset(i),
+                                      value.segment, std::mem::transmute::<*u8,*WirePointer>(value.ptr).offset(i),
+                                      value.nesting_limit, canonical));
                 }
             } else {
                 //# List of data.
-                let element_size = match value.step {
-                    0 => Void,
-                    1 => Bit,
-                    8 => Byte,
-                    16 => TwoBytes,
-                    32 => FourBytes,
-                    64 => EightBytes,
-                    _ => { fail!("invalid list step size: {}", value.step) }
-                };
-
-                (*reff).mut_list_ref().set(element_size, value.element_count);
+                (*reff).mut_list_ref().set(value.element_size, value.element_count);
                 std::ptr::copy_memory(ptr, std::mem::transmute::<*u8,*Word>(value.ptr), total_size);
             }
 
-            super::SegmentAnd { segment : segment, value : ptr }
+            Ok(super::SegmentAnd { segment : segment, value : ptr })
         } else {
             //# List of structs.
-            let ptr = allocate(&mut reff, &mut segment, total_size + POINTER_SIZE_IN_WORDS, WirePointerKind::List);
-            (*reff).mut_list_ref().set_inline_composite(total_size);
+            let src_data_size = round_bits_up_to_words(value.struct_data_size as u64);
+            let src_pointer_count = value.struct_pointer_count;
+            let mut data_size = src_data_size;
+            let mut pointer_count = src_pointer_count;
+
+            if canonical {
+                //# Every element must use the same size, so truncate to the
+                //# largest truncated element.
+                data_size = 0;
+                pointer_count = 0;
+                for i in range(0, value.element_count) {
+                    let element = value.get_struct_element_unchecked(i);
+                    let element_data : *Word = std::mem::transmute(element.data);
+                    let mut local_data_size = src_data_size;
+                    while local_data_size > 0 &&
+                        (*std::mem::transmute::<*Word,*WireValue<u64>>(
+                            element_data.offset(local_data_size as int - 1))).get() == 0 {
+                        local_data_size -= 1;
+                    }
+                    if local_data_size > data_size { data_size = local_data_size; }
+
+                    let mut local_pointer_count = src_pointer_count;
+                    while local_pointer_count > 0 &&
+                        (*element.pointers.offset(local_pointer_count as int - 1)).is_null() {
+                        local_pointer_count -= 1;
+                    }
+                    if local_pointer_count > pointer_count { pointer_count = local_pointer_count; }
+                }
+            }
 
-            let data_size = round_bits_up_to_words(value.struct_data_size as u64);
-            let pointer_count = value.struct_pointer_count;
+            let word_count = (data_size + pointer_count as uint * WORDS_PER_POINTER) * value.element_count;
+            let ptr = allocate(&mut reff, &mut segment, word_count + POINTER_SIZE_IN_WORDS, WirePointerKind::List);
+            (*reff).mut_list_ref().set_inline_composite(word_count);
 
             let tag : *mut WirePointer = std::mem::transmute(ptr);
             (*tag).set_kind_and_inline_composite_list_element_count(WirePointerKind::Struct, value.element_count);
@@ -1353,25 +1736,38 @@ mod WireHelpers {
 
             let mut src : *Word = std::mem::transmute(value.ptr);
             for _ in range(0, value.element_count) {
-                std::ptr::copy_nonoverlapping_memory(dst, src,
-                                                     value.struct_data_size as uint / BITS_PER_WORD);
+                std::ptr::copy_nonoverlapping_memory(dst, src, data_size);
                 dst = dst.offset(data_size as int);
-                src = src.offset(data_size as int);
+                src = src.offset(src_data_size as int);
 
                 for _ in range(0, pointer_count) {
-                    copy_pointer(segment, std::mem::transmute(dst),
-                                 value.segment, std::mem::transmute(src), value.nesting_limit);
+                    try!(copy_pointer(segment, std::mem::transmute(dst),
+                                      value.segment, std::mem::transmute(src), value.nesting_limit,
+                                      canonical));
                     dst = dst.offset(POINTER_SIZE_IN_WORDS as int);
                     src = src.offset(POINTER_SIZE_IN_WORDS as int);
                 }
+                src = src.offset(((src_pointer_count - pointer_count) as uint * WORDS_PER_POINTER) as int);
             }
-            super::SegmentAnd { segment : segment, value : ptr }
+            Ok(super::SegmentAnd { segment : segment, value : ptr })
         }
     }
 
+    // Copies a default value into the message so that it can be modified,
+    // and returns the new target of `reff`.
+    #[inline]
+    unsafe fn copy_default_value(segment : *mut SegmentBuilder, reff : *mut WirePointer,
+                                 default_value : *Word) -> Result<*mut Word> {
+        try!(copy_pointer(segment, reff, std::ptr::null(),
+                          std::mem::transmute::<*Word,*WirePointer>(default_value),
+                          std::int::MAX, false));
+        Ok((*reff).mut_target())
+    }
+
     pub unsafe fn copy_pointer(dst_segment : *mut SegmentBuilder, dst : *mut WirePointer,
                                mut src_segment : *SegmentReader, mut src : *WirePointer,
-                               nesting_limit : int) -> super::SegmentAnd<*mut Word> {
+                               nesting_limit : int,
+                               canonical : bool) -> Result<super::SegmentAnd<*mut Word>> {
 
         unsafe fn use_default(dst_segment : *mut SegmentBuilder, dst : *mut WirePointer)
             -> super::SegmentAnd<*mut Word> {
@@ -1382,24 +1778,23 @@ mod WireHelpers {
         let src_target = (*src).target();
 
         if (*src).is_null() {
-            return use_default(dst_segment, dst);
+            return Ok(use_default(dst_segment, dst));
         }
 
-        let mut ptr = follow_fars(&mut src, src_target, &mut src_segment);
-        if ptr.is_null() {
-            return use_default(dst_segment, dst);
-        }
+        let mut ptr = require_ok!(follow_fars(&mut src, src_target, &mut src_segment),
+                                  *src_segment,
+                                  return Ok(use_default(dst_segment, dst)));
 
         match (*src).kind() {
             WirePointerKind::Struct => {
                 require!(nesting_limit > 0, *src_segment,
-                        "Message is too deeply-nested or contains cycles.  See ReaderOptions.",
-                         return use_default(dst_segment, dst));
+                        NestingLimitExceeded,
+                         return Ok(use_default(dst_segment, dst)));
 
-                require!(bounds_check(src_segment, ptr, ptr.offset((*src).struct_ref().word_size() as int)),
-                         *src_segment,
-                        "Message contains out-of-bounds struct pointer.",
-                         return use_default(dst_segment, dst));
+                require_ok!(bounds_check(src_segment, ptr, ptr.offset((*src).struct_ref().word_size() as int),
+                                         OutOfBoundsStructPointer),
+                            *src_segment,
+                            return Ok(use_default(dst_segment, dst)));
 
                 return set_struct_pointer(
                     dst_segment, dst,
@@ -1410,48 +1805,60 @@ mod WireHelpers {
                         data_size : (*src).struct_ref().data_size.get() as u32 * BITS_PER_WORD as u32,
                         pointer_count : (*src).struct_ref().ptr_count.get(),
                         bit0offset : 0,
-                        nesting_limit : nesting_limit - 1 });
+                        nesting_limit : nesting_limit - 1 },
+                    canonical);
 
             }
             WirePointerKind::List => {
                 let element_size = (*src).list_ref().element_size();
                 require!(nesting_limit > 0, *src_segment,
-                        "Message is too deeply-nested or contains cycles. See ReaderOptions.",
-                         return use_default(dst_segment, dst));
+                        NestingLimitExceeded,
+                         return Ok(use_default(dst_segment, dst)));
 
                 if element_size == InlineComposite {
                     let word_count = (*src).list_ref().inline_composite_word_count();
                     let tag : *WirePointer = std::mem::transmute(ptr);
                     ptr = ptr.offset(POINTER_SIZE_IN_WORDS as int);
 
-                    require!(bounds_check(src_segment, ptr.offset(-1), ptr.offset(word_count as int)),
-                             *src_segment,
-                             "Message contains out-of-bounds list pointer.",
-                             return use_default(dst_segment, dst));
+                    require_ok!(bounds_check(src_segment, ptr.offset(-1), ptr.offset(word_count as int),
+                                             OutOfBoundsListPointer),
+                                *src_segment,
+                                return Ok(use_default(dst_segment, dst)));
 
                     require!((*tag).kind() == WirePointerKind::Struct,
                              *src_segment,
-                             "InlineComposite lists of non-STRUCT type are not supported.",
-                             return use_default(dst_segment, dst));
+                             InlineCompositeNonStruct,
+                             return Ok(use_default(dst_segment, dst)));
 
                     let element_count = (*tag).inline_composite_list_element_count();
                     let words_per_element = (*tag).struct_ref().word_size();
 
                     require!(words_per_element * element_count <= word_count,
                              *src_segment,
-                             "InlineComposite list's elements overrun its word count.",
-                             return use_default(dst_segment, dst));
+                             InlineCompositeOverrun,
+                             return Ok(use_default(dst_segment, dst)));
+
+                    if words_per_element == 0 {
+                        //# Watch out for lists of zero-sized structs, which can claim to be
+                        //# arbitrarily large without having sent actual data.
+                        require!(amplified_read(src_segment, element_count as u64).is_ok(),
+                                 *src_segment,
+                                 AmplifiedListPointer,
+                                 return Ok(use_default(dst_segment, dst)));
+                    }
                     return set_list_pointer(
                         dst_segment, dst,
                         ListReader {
                             segment : src_segment,
                             ptr : std::mem::transmute(ptr),
                             element_count : element_count,
+                            element_size : InlineComposite,
                             step : words_per_element * BITS_PER_WORD,
                             struct_data_size : (*tag).struct_ref().data_size.get() as u32 * BITS_PER_WORD as u32,
                             struct_pointer_count : (*tag).struct_ref().ptr_count.get(),
                             nesting_limit : nesting_limit - 1
-                        })
+                        },
+                        canonical)
                 } else {
                     let data_size = data_bits_per_element(element_size);
                     let pointer_count = pointers_per_element(element_size);
@@ -1459,10 +1866,19 @@ mod WireHelpers {
                     let element_count = (*src).list_ref().element_count();
                     let word_count = round_bits_up_to_words(element_count as u64 * step as u64);
 
-                    require!(bounds_check(src_segment, ptr, ptr.offset(word_count as int)),
-                             *src_segment,
-                             "Message contains out-of-bounds list pointer.",
-                             return use_default(dst_segment, dst));
+                    require_ok!(bounds_check(src_segment, ptr, ptr.offset(word_count as int),
+                                             OutOfBoundsListPointer),
+                                *src_segment,
+                                return Ok(use_default(dst_segment, dst)));
+
+                    if element_size == Void {
+                        //# Watch out for lists of void, which can claim to be
+                        //# arbitrarily large without having sent actual data.
+                        require!(amplified_read(src_segment, element_count as u64).is_ok(),
+                                 *src_segment,
+                                 AmplifiedListPointer,
+                                 return Ok(use_default(dst_segment, dst)));
+                    }
 
                     return set_list_pointer(
                         dst_segment, dst,
@@ -1470,28 +1886,34 @@ mod WireHelpers {
                             segment : src_segment,
                             ptr : std::mem::transmute(ptr),
                             element_count : element_count,
+                            element_size : element_size,
                             step : step,
                             struct_data_size : data_size as u32,
                             struct_pointer_count : pointer_count as u16,
                             nesting_limit : nesting_limit - 1
-                        })
+                        },
+                        canonical)
                 }
             }
             WirePointerKind::Far => {
-                fail!("Far pointer should have been handled above");
+                require_fail!(*src_segment, UnexpectedFarPointer,
+                              return Ok(use_default(dst_segment, dst)));
             }
             WirePointerKind::Other => {
-                require!((*src).is_capability(), *src_segment, "Unknown pointer type.",
-                         return use_default(dst_segment, dst));
+                require!((*src).is_capability(), *src_segment, UnknownPointerType,
+                         return Ok(use_default(dst_segment, dst)));
+                if canonical {
+                    return Err(CanonicalCapability);
+                }
                 match (*src_segment).arena.extract_cap((*src).cap_ref().index.get() as uint) {
                     Some(cap) => {
                         set_capability_pointer(dst_segment, dst, cap);
-                        return super::SegmentAnd { segment : dst_segment, value : std::ptr::mut_null() };
+                        return Ok(super::SegmentAnd { segment : dst_segment, value : std::ptr::mut_null() });
                     }
                     None => {
                         require_fail!(*src_segment,
-                                      "Message contained invalid capability pointer.",
-                                      return use_default(dst_segment, dst));
+                                      InvalidCapabilityPointer((*src).cap_ref().index.get() as uint),
+                                      return Ok(use_default(dst_segment, dst)));
                     }
                 }
             }
@@ -1501,65 +1923,67 @@ mod WireHelpers {
     #[inline]
     pub unsafe fn read_struct_pointer<'a>(mut segment: *SegmentReader,
                                           mut reff : *WirePointer,
-                                          default_value : *Word,
-                                          nesting_limit : int) -> StructReader<'a> {
-        let ref_target : *Word = (*reff).target();
+                                          mut default_value : *Word,
+                                          nesting_limit : int) -> Result<StructReader<'a>> {
+        let mut ref_target : *Word = (*reff).target();
         let mut first_time = true;
 
         'use_default : loop {
             if (*reff).is_null() || !first_time {
                 if default_value.is_null() ||
                     (*std::mem::transmute::<*Word,*WirePointer>(default_value)).is_null() {
-                        return StructReader::new_default();
+                        return Ok(StructReader::new_default());
                     }
-                //segment = std::ptr::null();
-                //reff = std::mem::transmute::<*Word,*WirePointer>(defaultValue);
-                unimplemented!()
+                segment = std::ptr::null();
+                reff = std::mem::transmute::<*Word,*WirePointer>(default_value);
+                ref_target = (*reff).target();
+                default_value = std::ptr::null();
             }
-            first_time = true;
+            first_time = false;
 
-            require!(nesting_limit > 0, *segment, "Message is too deeply-nested or contains cycles.",
+            // Default values are trusted, so the nesting limit does not apply to them.
+            require!(segment.is_null() || nesting_limit > 0, *segment, NestingLimitExceeded,
                      continue 'use_default);
 
-            let ptr = follow_fars(&mut reff, ref_target, &mut segment);
+            let ptr = require_ok!(follow_fars(&mut reff, ref_target, &mut segment),
+                                  *segment, continue 'use_default);
 
             let data_size_words = (*reff).struct_ref().data_size.get();
 
             require!((*reff).kind() == WirePointerKind::Struct, *segment,
-                     "Message contains non-struct pointer where struct pointer was expected.",
+                     ExpectedStructPointer,
                      continue 'use_default);
 
-            require!(bounds_check(segment, ptr,
-                                  ptr.offset((*reff).struct_ref().word_size() as int)),
-                     *segment,
-                     "Message contains out-of-bounds struct pointer.",
-                     continue 'use_default);
+            require_ok!(bounds_check(segment, ptr,
+                                     ptr.offset((*reff).struct_ref().word_size() as int),
+                                     OutOfBoundsStructPointer),
+                        *segment,
+                        continue 'use_default);
 
-            return StructReader {segment : segment,
+            return Ok(StructReader {segment : segment,
                           data : std::mem::transmute(ptr),
                           pointers : std::mem::transmute(ptr.offset(data_size_words as int)),
                           data_size : data_size_words as u32 * BITS_PER_WORD as BitCount32,
                           pointer_count : (*reff).struct_ref().ptr_count.get(),
                           bit0offset : 0,
-                          nesting_limit : nesting_limit - 1 };
+                          nesting_limit : nesting_limit - 1 });
         }
      }
 
     #[inline]
     pub unsafe fn read_capability_pointer(segment : *SegmentReader,
                                           reff : *WirePointer,
-                                          _nesting_limit : int) -> Box<ClientHook:Send> {
+                                          _nesting_limit : int) -> Result<Box<ClientHook:Send>> {
         if (*reff).is_null() {
-            fail!("broken cap factory is unimplemented");
+            // TODO return a broken capability, as capnproto-c++ does.
+            Err(NullCapabilityPointer)
         } else if !(*reff).is_capability() {
-            fail!("Message contains non-capability pointer where capability pointer was expected.");
+            Err(ExpectedCapabilityPointer)
         } else {
             let n = (*reff).cap_ref().index.get() as uint;
             match (*segment).arena.extract_cap(n) {
-                Some(client_hook) => { client_hook }
-                None => {
-                    fail!("Message contains invalid capability pointer: {}", n)
-                }
+                Some(client_hook) => { Ok(client_hook) }
+                None => { Err(InvalidCapabilityPointer(n)) }
             }
         }
     }
@@ -1567,28 +1991,33 @@ mod WireHelpers {
     #[inline]
     pub unsafe fn read_list_pointer<'a>(mut segment: *SegmentReader,
                                       mut reff : *WirePointer,
-                                      defaultValue : *Word,
+                                      mut defaultValue : *Word,
                                       expectedElementSize : FieldSize,
-                                      nesting_limit : int ) -> ListReader<'a> {
-        let ref_target : *Word = (*reff).target();
+                                      nesting_limit : int ) -> Result<ListReader<'a>> {
+        let mut ref_target : *Word = (*reff).target();
         let mut first_time = true;
 
         'use_default : loop {
             if (*reff).is_null() || !first_time {
                 if defaultValue.is_null() ||
                     (*std::mem::transmute::<*Word,*WirePointer>(defaultValue)).is_null() {
-                        return ListReader::new_default();
+                        return Ok(ListReader::new_default());
                     }
-                fail!("list default values unimplemented");
+                segment = std::ptr::null();
+                reff = std::mem::transmute::<*Word,*WirePointer>(defaultValue);
+                ref_target = (*reff).target();
+                defaultValue = std::ptr::null();
             }
             first_time = false;
 
-            require!(nesting_limit > 0, *segment, "nesting limit exceeded", continue 'use_default);
+            require!(segment.is_null() || nesting_limit > 0, *segment, NestingLimitExceeded,
+                     continue 'use_default);
 
-            let mut ptr : *Word = follow_fars(&mut reff, ref_target, &mut segment);
+            let mut ptr : *Word = require_ok!(follow_fars(&mut reff, ref_target, &mut segment),
+                                              *segment, continue 'use_default);
 
             require!((*reff).kind() == WirePointerKind::List, *segment,
-                     "Message contains non-list pointer where list pointer was expected",
+                     ExpectedListPointer,
                      continue 'use_default);
 
             let list_ref = (*reff).list_ref();
@@ -1601,15 +2030,15 @@ mod WireHelpers {
 
                     ptr = ptr.offset(1);
 
-                    require!(bounds_check(segment, ptr.offset(-1),
-                                          ptr.offset(wordCount as int)),
-                             *segment,
-                             "Message contains out-of-bounds list pointer",
-                             continue 'use_default);
+                    require_ok!(bounds_check(segment, ptr.offset(-1),
+                                             ptr.offset(wordCount as int),
+                                             OutOfBoundsListPointer),
+                                *segment,
+                                continue 'use_default);
 
                     require!((*tag).kind() == WirePointerKind::Struct,
                              *segment,
-                             "InlineComposite lists of non-STRUCT type are not supported",
+                             InlineCompositeNonStruct,
                              continue 'use_default);
 
                     let size = (*tag).inline_composite_list_element_count();
@@ -1618,9 +2047,18 @@ mod WireHelpers {
 
                     require!(size * wordsPerElement <= wordCount,
                              *segment,
-                             "InlineComposite list's elements overrun its word count",
+                             InlineCompositeOverrun,
                              continue 'use_default);
 
+                    if wordsPerElement == 0 {
+                        //# Watch out for lists of zero-sized structs, which can claim to be
+                        //# arbitrarily large without having sent actual data.
+                        require!(amplified_read(segment, size as u64).is_ok(),
+                                 *segment,
+                                 AmplifiedListPointer,
+                                 continue 'use_default);
+                    }
+
                     //# If a struct list was not expected, then presumably
                     //# a non-struct list was upgraded to a struct list.
                     //# We need to manipulate the pointer to point at the
@@ -1636,28 +2074,29 @@ mod WireHelpers {
                         Byte | TwoBytes | FourBytes | EightBytes => {
                             require!(struct_ref.data_size.get() > 0,
                                      *segment,
-                                    "Expected a primitive list, but got a list of pointer-only structs",
+                                     IncompatibleListElementSize,
                                      continue 'use_default);
                         }
                         Pointer => {
                             ptr = ptr.offset(struct_ref.data_size.get() as int);
                             require!(struct_ref.ptr_count.get() > 0,
                                      *segment,
-                                     "Expected a pointer list, but got a list of data-only structs",
+                                     IncompatibleListElementSize,
                                      continue 'use_default);
                         }
                         InlineComposite => {}
                     }
 
-                    return ListReader {
+                    return Ok(ListReader {
                         segment : segment,
                         ptr : std::mem::transmute(ptr),
                         element_count : size,
+                        element_size : InlineComposite,
                         step : wordsPerElement * BITS_PER_WORD,
                         struct_data_size : struct_ref.data_size.get() as u32 * (BITS_PER_WORD as u32),
                         struct_pointer_count : struct_ref.ptr_count.get() as u16,
                         nesting_limit : nesting_limit - 1
-                    };
+                    });
                 }
                 _ => {
 
@@ -1669,16 +2108,25 @@ mod WireHelpers {
                     let pointer_count = pointers_per_element(list_ref.element_size());
                     let step = data_size + pointer_count * BITS_PER_POINTER;
 
-                    require!(
+                    require_ok!(
                         bounds_check(
                             segment, ptr,
                             ptr.offset(
                                 round_bits_up_to_words(
-                                    (list_ref.element_count() * step) as u64) as int)),
+                                    (list_ref.element_count() * step) as u64) as int),
+                            OutOfBoundsListPointer),
                         *segment,
-                        "Message contains out-of-bounds list pointer.",
                         continue 'use_default);
 
+                    if list_ref.element_size() == Void {
+                        //# Watch out for lists of void, which can claim to be
+                        //# arbitrarily large without having sent actual data.
+                        require!(amplified_read(segment, list_ref.element_count() as u64).is_ok(),
+                                 *segment,
+                                 AmplifiedListPointer,
+                                 continue 'use_default);
+                    }
+
                     //# Verify that the elements are at least as large as
                     //# the expected type. Note that if we expected
                     //# InlineComposite, the expected sizes here will be
@@ -1693,21 +2141,22 @@ mod WireHelpers {
                         pointers_per_element(expectedElementSize);
 
                     require!(expectedDataBitsPerElement <= data_size, *segment,
-                             "Message contains list with imcompatible element type.",
+                             IncompatibleListElementSize,
                              continue 'use_default);
                     require!(expectedPointersPerElement <= pointer_count, *segment,
-                             "Message contains list with incompatible element type",
+                             IncompatibleListElementSize,
                              continue 'use_default);
 
-                    return ListReader {
+                    return Ok(ListReader {
                         segment : segment,
                         ptr : std::mem::transmute(ptr),
                         element_count : list_ref.element_count(),
+                        element_size : list_ref.element_size(),
                         step : step,
                         struct_data_size : data_size as u32,
                         struct_pointer_count : pointer_count as u16,
                         nesting_limit : nesting_limit - 1
-                    };
+                    });
                 }
             }
         }
@@ -1718,7 +2167,7 @@ mod WireHelpers {
                                         mut reff : *WirePointer,
                                         default_value : *Word,
                                         default_size : ByteCount
-                                        ) -> Text::Reader<'a> {
+                                        ) -> Result<Text::Reader<'a>> {
         unsafe fn use_default<'a>(default_value : *Word, default_size : ByteCount) -> Text::Reader<'a> {
             //   TODO?       if default_value.is_null() { default_value = &"" }
 
@@ -1727,45 +2176,46 @@ mod WireHelpers {
         }
 
         if (*reff).is_null() {
-            return use_default(default_value, default_size);
+            return Ok(use_default(default_value, default_size));
         }
 
         let ref_target = (*reff).target();
 
-        let ptr : *Word = follow_fars(&mut reff, ref_target, &mut segment);
+        let ptr : *Word = require_ok!(follow_fars(&mut reff, ref_target, &mut segment),
+                                      *segment, return Ok(use_default(default_value, default_size)));
 
         let list_ref = (*reff).list_ref();
 
         let size : uint = list_ref.element_count();
 
         require!((*reff).kind() == WirePointerKind::List, *segment,
-                 "Message contains non-list pointer where text was expected",
-                 return use_default(default_value, default_size));
+                 ExpectedListPointer,
+                 return Ok(use_default(default_value, default_size)));
 
         require!(list_ref.element_size() == Byte, *segment,
-                 "Message contains list pointer of non-bytes where text was expected.",
-                 return use_default(default_value, default_size));
+                 ExpectedByteList,
+                 return Ok(use_default(default_value, default_size)));
 
-        require!(bounds_check(segment, ptr,
-                              ptr.offset(round_bytes_up_to_words(size) as int)),
-                 *segment,
-                 "Message contains out-of-bounds text pointer.",
-                 return use_default(default_value, default_size));
+        require_ok!(bounds_check(segment, ptr,
+                                 ptr.offset(round_bytes_up_to_words(size) as int),
+                                 OutOfBoundsTextPointer),
+                    *segment,
+                 return Ok(use_default(default_value, default_size)));
 
-        require!(size > 0, *segment, "Message contains text that is not NUL-terminated",
-                 return use_default(default_value, default_size));
+        require!(size > 0, *segment, TextNotNulTerminated,
+                 return Ok(use_default(default_value, default_size)));
 
         let str_ptr = std::mem::transmute::<*Word,*u8>(ptr);
 
         require!((*str_ptr.offset((size - 1) as int)) == 0u8, *segment,
-                 "Message contains text that is not NUL-terminated",
-                 return use_default(default_value, default_size));
+                 TextNotNulTerminated,
+                 return Ok(use_default(default_value, default_size)));
 
         match Text::new_reader(str_ptr, size-1) {
-            Some(t) => return t,
+            Some(t) => return Ok(t),
             None => require_fail!(*segment,
-                                  "Text contains non-utf8 data.",
-                                  return use_default(default_value, default_size)),
+                                  TextNotUtf8,
+                                  return Ok(use_default(default_value, default_size))),
         }
     }
 
@@ -1774,38 +2224,39 @@ mod WireHelpers {
                                         mut reff : *WirePointer,
                                         default_value : *Word,
                                         default_size : ByteCount
-                                        ) -> Data::Reader<'a> {
+                                        ) -> Result<Data::Reader<'a>> {
         unsafe fn use_default<'a>(default_value : *Word, default_size : ByteCount) -> Data::Reader<'a> {
             return Data::new_reader(std::mem::transmute(default_value), default_size);
         }
 
         if (*reff).is_null() {
-            return use_default(default_value, default_size);
+            return Ok(use_default(default_value, default_size));
         }
 
         let ref_target = (*reff).target();
 
-        let ptr : *Word = follow_fars(&mut reff, ref_target, &mut segment);
+        let ptr : *Word = require_ok!(follow_fars(&mut reff, ref_target, &mut segment),
+                                      *segment, return Ok(use_default(default_value, default_size)));
 
         let list_ref = (*reff).list_ref();
 
         let size : uint = list_ref.element_count();
 
         require!((*reff).kind() == WirePointerKind::List, *segment,
-                 "Message contains non-list pointer where text was expected",
-                 return use_default(default_value, default_size));
+                 ExpectedListPointer,
+                 return Ok(use_default(default_value, default_size)));
 
         require!(list_ref.element_size() == Byte, *segment,
-                 "Message contains list pointer of non-bytes where data was expected",
-                 return use_default(default_value, default_size));
+                 ExpectedByteList,
+                 return Ok(use_default(default_value, default_size)));
 
-        require!(bounds_check(segment, ptr,
-                              ptr.offset(round_bytes_up_to_words(size) as int)),
-                 *segment,
-                 "Message contains out-of-bounds data pointer.",
-                 return use_default(default_value, default_size));
+        require_ok!(bounds_check(segment, ptr,
+                                 ptr.offset(round_bytes_up_to_words(size) as int),
+                                 OutOfBoundsDataPointer),
+                    *segment,
+                 return Ok(use_default(default_value, default_size)));
 
-        Data::new_reader(std::mem::transmute(ptr), size)
+        Ok(Data::new_reader(std::mem::transmute(ptr), size))
     }
 
 
@@ -1814,6 +2265,52 @@ mod WireHelpers {
 static zero : u64 = 0;
 fn zero_pointer() -> *WirePointer { unsafe {std::mem::transmute(&zero)}}
 
+// Sets up a single-segment message with room for a root pointer followed by
+// `word_count` words, lets `write` fill in the root pointer, and returns the
+// words of the segment.
+fn canonicalize(word_count : u64,
+                write : |*mut SegmentBuilder, *mut WirePointer| -> Result<()>) -> Result<Vec<Word>> {
+    let mut arena = BuilderArena::new(message::FixedSize,
+                                      NumWords(word_count as uint + POINTER_SIZE_IN_WORDS),
+                                      true);
+    let segment = &mut arena.segment0 as *mut SegmentBuilder;
+    let root = match arena.segment0.allocate(POINTER_SIZE_IN_WORDS) {
+        Some(root) => root,
+        None => fail!("could not allocate root pointer")
+    };
+    try!(write(segment, unsafe { std::mem::transmute(root) }));
+    assert!(arena.more_segments.len() == 0, "canonical message did not fit in one segment");
+
+    let used = arena.segment0.current_size();
+    let mut result = Vec::with_capacity(used);
+    for i in range(0, used) {
+        result.push(unsafe { *arena.segment0.get_ptr_unchecked(i) });
+    }
+    Ok(result)
+}
+
+// FNV-1a, used for hashes of message contents that are stable across
+// runs and platforms.
+struct StableHasher {
+    state : u64
+}
+
+impl StableHasher {
+    fn new() -> StableHasher {
+        StableHasher { state : 0xcbf29ce484222325 }
+    }
+
+    fn write_u8(&mut self, value : u8) {
+        self.state = (self.state ^ value as u64) * 0x100000001b3;
+    }
+
+    fn write_u64(&mut self, value : u64) {
+        for i in range(0u, 8) {
+            self.write_u8((value >> (i * 8)) as u8);
+        }
+    }
+}
+
 pub struct PointerReader<'a> {
     segment : *SegmentReader,
     pointer : *WirePointer,
@@ -1827,18 +2324,16 @@ impl <'a> PointerReader<'a> {
                         nesting_limit : 0x7fffffff }
     }
 
-    pub fn get_root<'b>(segment : *SegmentReader, mut location : *Word,
-                        nesting_limit : int) -> PointerReader<'b> {
+    pub fn get_root<'b>(segment : *SegmentReader, location : *Word,
+                        nesting_limit : int) -> Result<PointerReader<'b>> {
         unsafe {
-            require!(WireHelpers::bounds_check(segment, location,
-                                               location.offset(POINTER_SIZE_IN_WORDS as int)),
-                     *segment,
-                     "Root location out of bounds.",
-                     location = std::ptr::null());
+            try!(WireHelpers::bounds_check(segment, location,
+                                           location.offset(POINTER_SIZE_IN_WORDS as int),
+                                           RootOutOfBounds));
 
-            PointerReader { segment : segment,
-                            pointer : std::mem::transmute(location),
-                            nesting_limit : nesting_limit }
+            Ok(PointerReader { segment : segment,
+                               pointer : std::mem::transmute(location),
+                               nesting_limit : nesting_limit })
         }
     }
 
@@ -1852,7 +2347,7 @@ impl <'a> PointerReader<'a> {
         self.pointer.is_null() || unsafe { (*self.pointer).is_null() }
     }
 
-    pub fn get_struct(&self, default_value: *Word) -> StructReader<'a> {
+    pub fn get_struct(&self, default_value: *Word) -> Result<StructReader<'a>> {
         let reff : *WirePointer = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
         unsafe {
             WireHelpers::read_struct_pointer(self.segment, reff,
@@ -1860,7 +2355,8 @@ impl <'a> PointerReader<'a> {
         }
     }
 
-    pub fn get_list(&self, expected_element_size : FieldSize, default_value : *Word) -> ListReader<'a> {
+    pub fn get_list(&self, expected_element_size : FieldSize,
+                    default_value : *Word) -> Result<ListReader<'a>> {
         let reff = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
         unsafe {
             WireHelpers::read_list_pointer(self.segment,
@@ -1870,28 +2366,162 @@ impl <'a> PointerReader<'a> {
         }
     }
 
-    pub fn get_text(&self, default_value : *Word, default_size : ByteCount) -> Text::Reader<'a> {
+    pub fn get_text(&self, default_value : *Word, default_size : ByteCount) -> Result<Text::Reader<'a>> {
+        let reff = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
         unsafe {
-            WireHelpers::read_text_pointer(self.segment, self.pointer, default_value, default_size)
+            WireHelpers::read_text_pointer(self.segment, reff, default_value, default_size)
         }
     }
 
-    pub fn get_data(&self, default_value : *Word, default_size : ByteCount) -> Data::Reader<'a> {
+    pub fn get_data(&self, default_value : *Word, default_size : ByteCount) -> Result<Data::Reader<'a>> {
+        let reff = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
         unsafe {
-            WireHelpers::read_data_pointer(self.segment, self.pointer, default_value, default_size)
+            WireHelpers::read_data_pointer(self.segment, reff, default_value, default_size)
         }
     }
 
-    pub fn get_capability(&self) -> Box<ClientHook:Send> {
+    pub fn get_capability(&self) -> Result<Box<ClientHook:Send>> {
         let reff : *WirePointer = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
         unsafe {
             WireHelpers::read_capability_pointer(self.segment, reff, self.nesting_limit)
         }
     }
 
-    pub fn total_size(&self) -> MessageSize {
+    pub fn total_size(&self) -> Result<MessageSize> {
+        let reff = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
+        unsafe {
+            WireHelpers::total_size(self.segment, reff, self.nesting_limit)
+        }
+    }
+
+    pub fn visit(&self, visitor : &mut Visitor) -> Result<()> {
+        let reff = if self.pointer.is_null() { zero_pointer() } else { self.pointer };
+        unsafe {
+            WireHelpers::visit(self.segment, reff, self.nesting_limit, visitor)
+        }
+    }
+
+    // Copies the pointed-to object into a new single-segment message in
+    // canonical form and returns the words of that message.
+    pub fn canonicalize(&self) -> Result<Vec<Word>> {
+        let size = try!(self.total_size());
+        canonicalize(size.word_count, |segment, root| {
+            if !self.is_null() {
+                unsafe {
+                    try!(WireHelpers::copy_pointer(segment, root, self.segment, self.pointer,
+                                                   self.nesting_limit, true));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    // Checks that the pointed-to object is in canonical form and starts at
+    // `read_head`. On return, `read_head` points past the object and
+    // everything it transitively points to.
+    pub fn is_canonical(&self, read_head : &mut *Word) -> Result<bool> {
+        if self.is_null() {
+            //# The canonical form of a null pointer is null.
+            return Ok(true);
+        }
+
         unsafe {
-            WireHelpers::total_size(self.segment, self.pointer, self.nesting_limit)
+            match (*self.pointer).kind() {
+                WirePointerKind::Struct => {
+                    let struct_reader = try!(self.get_struct(std::ptr::null()));
+                    if struct_reader.data_size == 0 && struct_reader.pointer_count == 0 {
+                        return Ok(struct_reader.data == std::mem::transmute(self.pointer));
+                    }
+
+                    //# The struct's own words come first, then the objects
+                    //# that its pointers point to.
+                    let mut data_head = *read_head;
+                    *read_head = read_head.offset(
+                        (WireHelpers::round_bits_up_to_words(struct_reader.data_size as u64) +
+                         struct_reader.pointer_count as uint * WORDS_PER_POINTER) as int);
+                    let mut data_trunc = false;
+                    let mut ptr_trunc = false;
+                    let canonical = try!(struct_reader.is_canonical(&mut data_head, read_head,
+                                                                    &mut data_trunc, &mut ptr_trunc));
+                    Ok(canonical && data_trunc && ptr_trunc)
+                }
+                WirePointerKind::List => {
+                    let element_size = (*self.pointer).list_ref().element_size();
+                    try!(self.get_list(element_size, std::ptr::null())).is_canonical(read_head, self.pointer)
+                }
+                //# Far pointers and capabilities are not positional.
+                WirePointerKind::Far | WirePointerKind::Other => Ok(false)
+            }
+        }
+    }
+
+    // Returns the pointer describing the pointed-to object, looking through
+    // any far pointers.
+    fn follow_fars(&self) -> Result<*WirePointer> {
+        let mut reff = self.pointer;
+        let mut segment = self.segment;
+        unsafe {
+            try!(WireHelpers::follow_fars(&mut reff, (*reff).target(), &mut segment));
+        }
+        Ok(reff)
+    }
+
+    // Compares the pointed-to objects by value. Two structs are equal if they
+    // agree once missing data is read as zero and missing pointers as null,
+    // regardless of how they are laid out.
+    pub fn equals(&self, other : &PointerReader) -> Result<bool> {
+        if self.is_null() || other.is_null() {
+            return Ok(self.is_null() && other.is_null());
+        }
+
+        unsafe {
+            let reff = try!(self.follow_fars());
+            let other_reff = try!(other.follow_fars());
+            match ((*reff).kind(), (*other_reff).kind()) {
+                (WirePointerKind::Struct, WirePointerKind::Struct) => {
+                    try!(self.get_struct(std::ptr::null())).equals(
+                        &try!(other.get_struct(std::ptr::null())))
+                }
+                (WirePointerKind::List, WirePointerKind::List) => {
+                    let list = try!(self.get_list((*reff).list_ref().element_size(),
+                                                  std::ptr::null()));
+                    let other_list = try!(other.get_list((*other_reff).list_ref().element_size(),
+                                                         std::ptr::null()));
+                    list.equals(&other_list)
+                }
+                (WirePointerKind::Other, _) | (_, WirePointerKind::Other) => Err(CapabilityComparison),
+                _ => Ok(false)
+            }
+        }
+    }
+
+    // A hash of the pointed-to object that agrees with `equals()`.
+    pub fn stable_hash(&self) -> Result<u64> {
+        let mut hasher = StableHasher::new();
+        try!(self.hash_into(&mut hasher));
+        Ok(hasher.state)
+    }
+
+    fn hash_into(&self, hasher : &mut StableHasher) -> Result<()> {
+        if self.is_null() {
+            hasher.write_u8(0);
+            return Ok(());
+        }
+
+        unsafe {
+            let reff = try!(self.follow_fars());
+            match (*reff).kind() {
+                WirePointerKind::Struct => {
+                    hasher.write_u8(1);
+                    try!(self.get_struct(std::ptr::null())).hash_into(hasher)
+                }
+                WirePointerKind::List => {
+                    hasher.write_u8(2);
+                    try!(self.get_list((*reff).list_ref().element_size(),
+                                       std::ptr::null())).hash_into(hasher)
+                }
+                _ => Err(CapabilityComparison)
+            }
         }
     }
 }
@@ -1912,45 +2542,51 @@ impl <'a> PointerBuilder<'a> {
         unsafe { (*self.pointer).is_null() }
     }
 
-    pub fn get_struct(&self, size : StructSize, default_value : *Word) -> StructBuilder<'a> {
+    pub fn get_arena(&self) -> *mut BuilderArena {
+        unsafe { (*self.segment).get_arena() }
+    }
+
+    pub fn get_struct(&self, size : StructSize, default_value : *Word) -> Result<StructBuilder<'a>> {
         unsafe {
             WireHelpers::get_writable_struct_pointer(
                 self.pointer,
+                (*self.pointer).mut_target(),
                 self.segment,
                 size,
                 default_value)
         }
     }
 
-    pub fn get_list(&self, element_size : FieldSize, default_value : *Word) -> ListBuilder<'a> {
+    pub fn get_list(&self, element_size : FieldSize, default_value : *Word) -> Result<ListBuilder<'a>> {
         unsafe {
             WireHelpers::get_writable_list_pointer(
-                self.pointer, self.segment, element_size, default_value)
+                self.pointer, (*self.pointer).mut_target(), self.segment, element_size, default_value)
         }
     }
 
-    pub fn get_struct_list(&self, element_size : StructSize, default_value : *Word) -> ListBuilder<'a> {
+    pub fn get_struct_list(&self, element_size : StructSize,
+                           default_value : *Word) -> Result<ListBuilder<'a>> {
         unsafe {
             WireHelpers::get_writable_struct_list_pointer(
-                self.pointer, self.segment, element_size, default_value)
+                self.pointer, (*self.pointer).mut_target(), self.segment, element_size, default_value)
         }
     }
 
-    pub fn get_text(&self, default_value : *Word, default_size : ByteCount) -> Text::Builder<'a> {
+    pub fn get_text(&self, default_value : *Word, default_size : ByteCount) -> Result<Text::Builder<'a>> {
         unsafe {
             WireHelpers::get_writable_text_pointer(
-                self.pointer, self.segment, default_value, default_size)
+                self.pointer, (*self.pointer).mut_target(), self.segment, default_value, default_size)
         }
     }
 
-    pub fn get_data(&self, default_value : *Word, default_size : ByteCount) -> Data::Builder<'a> {
+    pub fn get_data(&self, default_value : *Word, default_size : ByteCount) -> Result<Data::Builder<'a>> {
         unsafe {
             WireHelpers::get_writable_data_pointer(
-                self.pointer, self.segment, default_value, default_size)
+                self.pointer, (*self.pointer).mut_target(), self.segment, default_value, default_size)
         }
     }
 
-    pub fn get_capability(&self) -> Box<ClientHook:Send> {
+    pub fn get_capability(&self) -> Result<Box<ClientHook:Send>> {
         unsafe {
             WireHelpers::read_capability_pointer(
                 &(*self.segment).reader, self.pointer as *WirePointer, std::int::MAX)
@@ -1990,15 +2626,17 @@ impl <'a> PointerBuilder<'a> {
         }
     }
 
-    pub fn set_struct(&self, value : &StructReader) {
+    pub fn set_struct(&self, value : &StructReader) -> Result<()> {
         unsafe {
-            WireHelpers::set_struct_pointer(self.segment, self.pointer, *value);
+            try!(WireHelpers::set_struct_pointer(self.segment, self.pointer, *value, false));
+            Ok(())
         }
     }
 
-    pub fn set_list(&self, value : &ListReader) {
+    pub fn set_list(&self, value : &ListReader) -> Result<()> {
         unsafe {
-            WireHelpers::set_list_pointer(self.segment, self.pointer, *value);
+            try!(WireHelpers::set_list_pointer(self.segment, self.pointer, *value, false));
+            Ok(())
         }
     }
 
@@ -2020,6 +2658,21 @@ impl <'a> PointerBuilder<'a> {
         }
     }
 
+    pub fn copy_from(&self, other : PointerReader) -> Result<()> {
+        unsafe {
+            if !(*self.pointer).is_null() {
+                WireHelpers::zero_object(self.segment, self.pointer);
+                std::ptr::zero_memory(self.pointer, 1);
+            }
+            if !other.pointer.is_null() {
+                try!(WireHelpers::copy_pointer(self.segment, self.pointer,
+                                               other.segment, other.pointer, other.nesting_limit,
+                                               false));
+            }
+            Ok(())
+        }
+    }
+
     pub fn clear(&self) {
         unsafe {
             WireHelpers::zero_object(self.segment, self.pointer);
@@ -2027,6 +2680,58 @@ impl <'a> PointerBuilder<'a> {
         }
     }
 
+    pub fn disown(&self) -> OrphanBuilder<'a> {
+        unsafe {
+            let mut segment = self.segment;
+            let location = if (*self.pointer).is_null() {
+                std::ptr::mut_null()
+            } else if (*self.pointer).kind() == WirePointerKind::Other {
+                //# Capabilities have no location, but we need a non-null
+                //# value to mark the orphan as non-null.
+                self.pointer as *mut Word
+            } else {
+                let mut reff = self.pointer;
+                WireHelpers::follow_builder_fars(&mut reff, (*self.pointer).mut_target(), &mut segment)
+            };
+
+            let mut result = OrphanBuilder::new_default();
+            std::ptr::copy_nonoverlapping_memory(result.tag_as_ptr(), self.pointer as *WirePointer, 1);
+            result.set_location(segment, location);
+
+            //# Zero out the pointer that was disowned.
+            std::ptr::zero_memory(self.pointer, 1);
+            result
+        }
+    }
+
+    pub fn adopt(&self, mut value : OrphanBuilder<'a>) {
+        unsafe {
+            if !value.location.is_null() {
+                assert!((*value.segment).get_arena() == (*self.segment).get_arena(),
+                        "Adopted object must live in the same message.");
+            }
+
+            if !(*self.pointer).is_null() {
+                WireHelpers::zero_object(self.segment, self.pointer);
+            }
+            std::ptr::zero_memory(self.pointer, 1);
+
+            let tag = value.tag_as_ptr();
+            if value.location.is_null() {
+                //# Adopting a null orphan leaves the pointer null.
+            } else if (*tag).kind() == WirePointerKind::Struct || (*tag).kind() == WirePointerKind::List {
+                WireHelpers::transfer_pointer_split(self.segment, self.pointer,
+                                                    value.segment, tag, value.location);
+            } else {
+                //# Far pointers and capabilities can be copied as-is.
+                std::ptr::copy_nonoverlapping_memory(self.pointer, tag as *WirePointer, 1);
+            }
+
+            //# The object now belongs to the pointer.
+            value.forget();
+        }
+    }
+
     pub fn as_reader(&self) -> PointerReader<'a> {
         unsafe {
             let segment_reader = &(*self.segment).reader;
@@ -2037,6 +2742,184 @@ impl <'a> PointerBuilder<'a> {
     }
 }
 
+pub struct OrphanBuilder<'a> {
+    //# Contains an encoded WirePointer representing this object. The
+    //# offset is meaningless; `location` says where the object lives.
+    tag : u64,
+    segment : *mut SegmentBuilder,
+    location : *mut Word,
+}
+
+#[unsafe_destructor]
+impl <'a> Drop for OrphanBuilder<'a> {
+    fn drop(&mut self) {
+        if !self.location.is_null() {
+            self.euthanize();
+        }
+    }
+}
+
+impl <'a> OrphanBuilder<'a> {
+    pub fn new_default() -> OrphanBuilder<'a> {
+        OrphanBuilder { tag : 0, segment : std::ptr::mut_null(), location : std::ptr::mut_null() }
+    }
+
+    pub fn init_struct(arena : *mut BuilderArena, size : StructSize) -> OrphanBuilder<'a> {
+        let mut result = OrphanBuilder::new_default();
+        unsafe {
+            let builder = WireHelpers::init_struct_pointer(result.tag_as_ptr(),
+                                                           &mut (*arena).segment0, size);
+            result.set_location(builder.segment, builder.data as *mut Word);
+        }
+        result
+    }
+
+    pub fn init_list(arena : *mut BuilderArena, element_size : FieldSize,
+                     element_count : ElementCount) -> OrphanBuilder<'a> {
+        let mut result = OrphanBuilder::new_default();
+        unsafe {
+            let builder = WireHelpers::init_list_pointer(result.tag_as_ptr(), &mut (*arena).segment0,
+                                                         element_count, element_size);
+            result.set_list_location(&builder);
+        }
+        result
+    }
+
+    pub fn init_struct_list(arena : *mut BuilderArena, element_count : ElementCount,
+                            element_size : StructSize) -> OrphanBuilder<'a> {
+        let mut result = OrphanBuilder::new_default();
+        unsafe {
+            let builder = WireHelpers::init_struct_list_pointer(result.tag_as_ptr(),
+                                                                &mut (*arena).segment0,
+                                                                element_count, element_size);
+            result.set_list_location(&builder);
+        }
+        result
+    }
+
+    pub fn init_text(arena : *mut BuilderArena, size : ByteCount) -> OrphanBuilder<'a> {
+        let mut result = OrphanBuilder::new_default();
+        unsafe {
+            let allocation = WireHelpers::init_text_pointer(result.tag_as_ptr(),
+                                                            &mut (*arena).segment0, size);
+            result.set_location(allocation.segment, allocation.value.as_ptr() as *mut Word);
+        }
+        result
+    }
+
+    pub fn init_data(arena : *mut BuilderArena, size : ByteCount) -> OrphanBuilder<'a> {
+        let mut result = OrphanBuilder::new_default();
+        unsafe {
+            let allocation = WireHelpers::init_data_pointer(result.tag_as_ptr(),
+                                                            &mut (*arena).segment0, size);
+            result.set_location(allocation.segment, allocation.value.as_mut_ptr() as *mut Word);
+        }
+        result
+    }
+
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.location.is_null()
+    }
+
+    pub fn as_struct(&mut self, size : StructSize) -> Result<StructBuilder<'a>> {
+        unsafe {
+            let result = try!(WireHelpers::get_writable_struct_pointer(
+                self.tag_as_ptr(), self.location, self.segment, size, std::ptr::null()));
+
+            //# Watch out, the pointer could have been updated if the
+            //# object had to be relocated.
+            self.set_location(result.segment, result.data as *mut Word);
+            Ok(result)
+        }
+    }
+
+    pub fn as_list(&mut self, element_size : FieldSize) -> Result<ListBuilder<'a>> {
+        unsafe {
+            let result = try!(WireHelpers::get_writable_list_pointer(
+                self.tag_as_ptr(), self.location, self.segment, element_size, std::ptr::null()));
+            self.set_list_location(&result);
+            Ok(result)
+        }
+    }
+
+    pub fn as_struct_list(&mut self, element_size : StructSize) -> Result<ListBuilder<'a>> {
+        unsafe {
+            let result = try!(WireHelpers::get_writable_struct_list_pointer(
+                self.tag_as_ptr(), self.location, self.segment, element_size, std::ptr::null()));
+            self.set_list_location(&result);
+            Ok(result)
+        }
+    }
+
+    pub fn as_text(&mut self) -> Result<Text::Builder<'a>> {
+        unsafe {
+            WireHelpers::get_writable_text_pointer(
+                self.tag_as_ptr(), self.location, self.segment, std::ptr::null(), 0)
+        }
+    }
+
+    pub fn as_data(&mut self) -> Result<Data::Builder<'a>> {
+        unsafe {
+            WireHelpers::get_writable_data_pointer(
+                self.tag_as_ptr(), self.location, self.segment, std::ptr::null(), 0)
+        }
+    }
+
+    #[inline]
+    fn tag_as_ptr(&mut self) -> *mut WirePointer {
+        unsafe { std::mem::transmute(&mut self.tag) }
+    }
+
+    unsafe fn set_location(&mut self, segment : *mut SegmentBuilder, location : *mut Word) {
+        self.segment = segment;
+        self.location = location;
+
+        // Positional tags get a zero offset, except for empty structs, whose
+        // tag would otherwise look like a null pointer.
+        let tag = self.tag_as_ptr();
+        match (*tag).kind() {
+            WirePointerKind::Struct if (*tag).struct_ref().word_size() == 0 => {}
+            WirePointerKind::Struct | WirePointerKind::List => {
+                (*tag).set_kind_with_zero_offset((*tag).kind());
+            }
+            WirePointerKind::Far | WirePointerKind::Other => {}
+        }
+    }
+
+    unsafe fn set_list_location(&mut self, list : &ListBuilder<'a>) {
+        let mut location : *mut Word = std::mem::transmute(list.ptr);
+        let tag = self.tag_as_ptr();
+        if (*tag).kind() == WirePointerKind::List &&
+            (*tag).list_ref().element_size() == InlineComposite {
+            //# The list's location is that of its element tag.
+            location = location.offset(-(POINTER_SIZE_IN_WORDS as int));
+        }
+        self.set_location(list.segment, location);
+    }
+
+    fn forget(&mut self) {
+        self.tag = 0;
+        self.segment = std::ptr::mut_null();
+        self.location = std::ptr::mut_null();
+    }
+
+    fn euthanize(&mut self) {
+        unsafe {
+            let tag = self.tag_as_ptr();
+            match (*tag).kind() {
+                WirePointerKind::Far | WirePointerKind::Other => {
+                    WireHelpers::zero_object(self.segment, tag);
+                }
+                WirePointerKind::Struct | WirePointerKind::List => {
+                    WireHelpers::zero_object_helper(self.segment, tag, self.location);
+                }
+            }
+        }
+        self.forget();
+    }
+}
+
 pub trait FromStructReader<'a> {
     fn new(reader : StructReader<'a>) -> Self;
 }
@@ -2068,6 +2951,14 @@ impl <'a> StructReader<'a>  {
 
     pub fn get_pointer_section_size(&self) -> WirePointerCount16 { self.pointer_count }
 
+    // Whether both readers point at the very same struct, as opposed to
+    // structs that are equal by value.
+    pub fn is_same(&self, other : &StructReader) -> bool {
+        self.data == other.data && self.pointers == other.pointers &&
+            self.data_size == other.data_size && self.pointer_count == other.pointer_count &&
+            self.bit0offset == other.bit0offset
+    }
+
     pub fn get_data_section_as_blob(&self) -> uint { fail!("unimplemented") }
 
     #[inline]
@@ -2129,7 +3020,7 @@ impl <'a> StructReader<'a>  {
         }
     }
 
-    pub fn total_size(&self) -> MessageSize {
+    pub fn total_size(&self) -> Result<MessageSize> {
         let mut result = MessageSize {
             word_count : WireHelpers::round_bits_up_to_words(self.data_size as u64) as u64 +
                 self.pointer_count as u64 * WORDS_PER_POINTER as u64,
@@ -2137,14 +3028,131 @@ impl <'a> StructReader<'a>  {
 
         for i in range(0, self.pointer_count as int) {
             unsafe {
-                result.plus_eq(WireHelpers::total_size(self.segment, self.pointers.offset(i),
-                                                       self.nesting_limit));
+                result.plus_eq(try!(WireHelpers::total_size(self.segment, self.pointers.offset(i),
+                                                            self.nesting_limit)));
             }
         }
 
-        // TODO when we have read limiting: segment->unread()
+        //# Traversing the whole message to measure it shouldn't use up
+        //# the caller's read limit.
+        if !self.segment.is_null() {
+            unsafe { (*self.segment).unread(result.word_count); }
+        }
 
-        result
+        Ok(result)
+    }
+
+    // Copies this struct into a new single-segment message in canonical
+    // form and returns the words of that message.
+    pub fn canonicalize(&self) -> Result<Vec<Word>> {
+        let size = try!(self.total_size());
+        canonicalize(size.word_count, |segment, root| {
+            unsafe {
+                try!(WireHelpers::set_struct_pointer(segment, root, *self, true));
+            }
+            Ok(())
+        })
+    }
+
+    // Checks that this struct starts at `read_head` and that the objects
+    // its pointers point to are laid out in order starting at `ptr_head`.
+    // `data_trunc` and `ptr_trunc` are set to whether the last data word
+    // and the last pointer are non-zero, i.e. whether the sections are
+    // properly truncated.
+    pub fn is_canonical(&self, read_head : &mut *Word, ptr_head : &mut *Word,
+                        data_trunc : &mut bool, ptr_trunc : &mut bool) -> Result<bool> {
+        if unsafe { std::mem::transmute::<*u8,*Word>(self.data) } != *read_head {
+            //# Our target area is not at the read head, so preorder fails.
+            return Ok(false);
+        }
+
+        if self.data_size as uint % BITS_PER_WORD != 0 {
+            //# Using legacy non-word-size structs, reject.
+            return Ok(false);
+        }
+        let data_size = self.data_size as uint / BITS_PER_WORD;
+
+        //# Mark whether the struct is properly truncated.
+        *data_trunc = data_size == 0 || self.get_data_field::<u64>(data_size - 1) != 0;
+        *ptr_trunc = self.pointer_count == 0 ||
+            !self.get_pointer_field(self.pointer_count as uint - 1).is_null();
+
+        //# Advance the read head.
+        *read_head = unsafe {
+            read_head.offset((data_size + self.pointer_count as uint * WORDS_PER_POINTER) as int)
+        };
+
+        for i in range(0, self.pointer_count as uint) {
+            if !try!(self.get_pointer_field(i).is_canonical(ptr_head)) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    // Returns byte `index` of the data section, or zero past its end.
+    fn get_data_byte(&self, index : uint) -> u8 {
+        if self.data_size == 1 {
+            //# This struct is an element of a list of bits.
+            if index == 0 { self.get_bool_field(0) as u8 } else { 0 }
+        } else if index < self.data_size as uint / BITS_PER_BYTE {
+            unsafe { *self.data.offset(index as int) }
+        } else {
+            0
+        }
+    }
+
+    // Compares two structs by value, reading missing data as zero and
+    // missing pointers as null, so that structs written by different
+    // versions of a schema or with different layouts can be equal.
+    pub fn equals(&self, other : &StructReader) -> Result<bool> {
+        let data_bytes = std::cmp::max(WireHelpers::round_bits_up_to_bytes(self.data_size as u64),
+                                       WireHelpers::round_bits_up_to_bytes(other.data_size as u64));
+        for i in range(0, data_bytes) {
+            if self.get_data_byte(i) != other.get_data_byte(i) {
+                return Ok(false);
+            }
+        }
+
+        let pointer_count = std::cmp::max(self.pointer_count, other.pointer_count) as uint;
+        for i in range(0, pointer_count) {
+            if !try!(self.get_pointer_field(i).equals(&other.get_pointer_field(i))) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    // A hash of this struct that agrees with `equals()`.
+    pub fn stable_hash(&self) -> Result<u64> {
+        let mut hasher = StableHasher::new();
+        try!(self.hash_into(&mut hasher));
+        Ok(hasher.state)
+    }
+
+    fn hash_into(&self, hasher : &mut StableHasher) -> Result<()> {
+        //# Trailing zero bytes and null pointers are left out, so that
+        //# the hash does not depend on the size of the struct.
+        let mut data_bytes = WireHelpers::round_bits_up_to_bytes(self.data_size as u64);
+        while data_bytes > 0 && self.get_data_byte(data_bytes - 1) == 0 {
+            data_bytes -= 1;
+        }
+        hasher.write_u64(data_bytes as u64);
+        for i in range(0, data_bytes) {
+            hasher.write_u8(self.get_data_byte(i));
+        }
+
+        let mut pointer_count = self.pointer_count as uint;
+        while pointer_count > 0 && self.get_pointer_field(pointer_count - 1).is_null() {
+            pointer_count -= 1;
+        }
+        hasher.write_u64(pointer_count as u64);
+        for i in range(0, pointer_count) {
+            try!(self.get_pointer_field(i).hash_into(hasher));
+        }
+        Ok(())
     }
 }
 
@@ -2261,6 +3269,7 @@ pub struct ListReader<'a> {
     segment : *SegmentReader,
     ptr : *u8,
     element_count : ElementCount,
+    element_size : FieldSize,
     step : BitCount0,
     struct_data_size : BitCount32,
     struct_pointer_count : WirePointerCount16,
@@ -2271,18 +3280,23 @@ impl <'a> ListReader<'a> {
 
     pub fn new_default() -> ListReader {
         ListReader { segment : std::ptr::null(),
-                    ptr : std::ptr::null(), element_count : 0, step: 0, struct_data_size : 0,
+                    ptr : std::ptr::null(), element_count : 0, element_size : Void,
+                    step: 0, struct_data_size : 0,
                     struct_pointer_count : 0, nesting_limit : 0x7fffffff}
     }
 
     #[inline]
     pub fn size(&self) -> ElementCount { self.element_count }
 
-    pub fn get_struct_element(&self, index : ElementCount) -> StructReader<'a> {
-        require!(self.nesting_limit > 0, unsafe {*self.segment},
-                 "Message is too deeply-nested or contains cycles",
-                 return StructReader::new_default());
+    pub fn get_struct_element(&self, index : ElementCount) -> Result<StructReader<'a>> {
+        require!(self.nesting_limit > 0, unsafe {*self.segment}, NestingLimitExceeded,
+                 return Ok(StructReader::new_default()));
+        Ok(self.get_struct_element_unchecked(index))
+    }
 
+    // Like get_struct_element(), but without the nesting limit check, for
+    // when only the element's location is needed.
+    fn get_struct_element_unchecked(&self, index : ElementCount) -> StructReader<'a> {
         let indexBit : BitCount64 = index as ElementCount64 * (self.step as BitCount64);
 
         let structData : *u8 = unsafe {
@@ -2320,6 +3334,131 @@ impl <'a> ListReader<'a> {
             nesting_limit : self.nesting_limit
         }
     }
+
+    // Checks that this list, whose pointer is `reff`, is in canonical form
+    // and starts at `read_head`, and advances `read_head` past it.
+    pub fn is_canonical(&self, read_head : &mut *Word, reff : *WirePointer) -> Result<bool> {
+        let start : *Word = unsafe { std::mem::transmute(self.ptr) };
+        match self.element_size {
+            InlineComposite => {
+                *read_head = unsafe { read_head.offset(POINTER_SIZE_IN_WORDS as int) };
+                if start != *read_head {
+                    //# The next word to read is the tag word, but the pointer is in
+                    //# front of it, so our check is slightly different.
+                    return Ok(false);
+                }
+                if self.struct_data_size as uint % BITS_PER_WORD != 0 {
+                    return Ok(false);
+                }
+                let element_words = self.struct_data_size as uint / BITS_PER_WORD +
+                    self.struct_pointer_count as uint * WORDS_PER_POINTER;
+                let total_size = self.element_count * element_words;
+                if total_size != unsafe { (*reff).list_ref().inline_composite_word_count() } {
+                    return Ok(false);
+                }
+                if element_words == 0 {
+                    return Ok(true);
+                }
+
+                let list_end = unsafe { read_head.offset(total_size as int) };
+                let mut pointer_head = list_end;
+                let mut list_data_trunc = false;
+                let mut list_ptr_trunc = false;
+                for i in range(0, self.element_count) {
+                    let mut data_trunc = false;
+                    let mut ptr_trunc = false;
+                    if !try!(try!(self.get_struct_element(i)).is_canonical(read_head, &mut pointer_head,
+                                                                           &mut data_trunc, &mut ptr_trunc)) {
+                        return Ok(false);
+                    }
+                    list_data_trunc |= data_trunc;
+                    list_ptr_trunc |= ptr_trunc;
+                }
+                assert!(*read_head == list_end);
+                *read_head = pointer_head;
+                Ok(list_data_trunc && list_ptr_trunc)
+            }
+            Pointer => {
+                if start != *read_head {
+                    return Ok(false);
+                }
+                *read_head = unsafe {
+                    read_head.offset((self.element_count * WORDS_PER_POINTER) as int)
+                };
+                for i in range(0, self.element_count) {
+                    if !try!(self.get_pointer_element(i).is_canonical(read_head)) {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            _ => {
+                if start != *read_head {
+                    return Ok(false);
+                }
+
+                //# Any padding after the last element must be zero.
+                let bit_size = self.element_count as u64 * data_bits_per_element(self.element_size) as u64;
+                unsafe {
+                    let mut byte_read_head : *u8 = self.ptr.offset((bit_size / BITS_PER_BYTE as u64) as int);
+                    let read_head_end = read_head.offset(
+                        WireHelpers::round_bits_up_to_words(bit_size) as int);
+
+                    let leftover_bits = bit_size % BITS_PER_BYTE as u64;
+                    if leftover_bits > 0 {
+                        let mask : u8 = !((1u8 << leftover_bits as uint) - 1);
+                        if mask & *byte_read_head != 0 {
+                            return Ok(false);
+                        }
+                        byte_read_head = byte_read_head.offset(1);
+                    }
+
+                    while byte_read_head != std::mem::transmute(read_head_end) {
+                        if *byte_read_head != 0 {
+                            return Ok(false);
+                        }
+                        byte_read_head = byte_read_head.offset(1);
+                    }
+
+                    *read_head = read_head_end;
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    // Compares two lists by value. Whatever their encoding, the elements
+    // are compared as structs, the same way that hash_into() hashes them.
+    // A struct list can therefore equal a list that was written with a
+    // smaller encoding, and so can two such smaller lists.
+    pub fn equals(&self, other : &ListReader) -> Result<bool> {
+        if self.element_count != other.element_count {
+            return Ok(false);
+        }
+        for i in range(0, self.element_count) {
+            if !try!(try!(self.get_struct_element(i)).equals(&try!(other.get_struct_element(i)))) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // A hash of this list that agrees with `equals()`.
+    pub fn stable_hash(&self) -> Result<u64> {
+        let mut hasher = StableHasher::new();
+        try!(self.hash_into(&mut hasher));
+        Ok(hasher.state)
+    }
+
+    fn hash_into(&self, hasher : &mut StableHasher) -> Result<()> {
+        //# Every kind of list is hashed element by element as structs,
+        //# since that is the most lenient way in which lists compare equal.
+        hasher.write_u64(self.element_count as u64);
+        for i in range(0, self.element_count) {
+            try!(try!(self.get_struct_element(i)).hash_into(hasher));
+        }
+        Ok(())
+    }
 }
 
 
@@ -5,7 +5,11 @@
  */
 
 use std;
+use arena;
+use common::Word;
+use error;
 use layout;
+use message;
 
 #[test]
 fn simple_raw_data_struct() {
@@ -16,7 +20,7 @@ fn simple_raw_data_struct() {
     };
 
     let reader = unsafe { layout::PointerReader::get_root_unchecked(
-        std::mem::transmute(data.words.unsafe_ref(0))).get_struct(std::ptr::null()) };
+        std::mem::transmute(data.words.unsafe_ref(0))).get_struct(std::ptr::null()).unwrap() };
 
     assert_eq!(0xefcdab8967452301u64, reader.get_data_field::<u64>(0));
     assert_eq!(0, reader.get_data_field::<u64>(1));
@@ -48,3 +52,360 @@ fn simple_raw_data_struct() {
     assert_eq!(reader.get_bool_field(63), true);
     assert_eq!(reader.get_bool_field(64), false);
 }
+
+fn new_arena(words : &[u8], traversal_limit_in_words : u64) -> Box<arena::ReaderArena> {
+    let segment : &[Word] = unsafe {
+        std::mem::transmute(std::raw::Slice { data : words.as_ptr(), len : words.len() / 8 })
+    };
+    let mut options = message::ReaderOptions::new();
+    options.traversal_limit_in_words(traversal_limit_in_words);
+    arena::ReaderArena::new([segment], options)
+}
+
+#[test]
+fn read_limit() {
+    // A root pointer to a struct with one data word.
+    let data : layout::AlignedData<[u8, .. 16]> = layout::AlignedData {
+        _dummy: 0,
+        words : [0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]
+    };
+
+    // The root pointer and the struct each cost one word.
+    let arena = new_arena(data.words.as_slice(), 2);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 64).unwrap();
+    assert!(root.get_struct(std::ptr::null()).is_ok());
+    assert!(root.get_struct(std::ptr::null()).is_err());
+
+    let arena = new_arena(data.words.as_slice(), 1);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 64).unwrap();
+    assert_eq!(root.get_struct(std::ptr::null()).err(), Some(error::ReadLimitExceeded));
+}
+
+#[test]
+fn amplified_void_list() {
+    // A root pointer to a list of 2^29 - 1 voids, which takes up no space.
+    let data : layout::AlignedData<[u8, .. 8]> = layout::AlignedData {
+        _dummy: 0,
+        words : [0x01, 0x00, 0x00, 0x00, 0xf8, 0xff, 0xff, 0xff]
+    };
+
+    let arena = new_arena(data.words.as_slice(), 8 * 1024 * 1024);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 64).unwrap();
+    assert_eq!(root.get_list(layout::Void, std::ptr::null()).err(),
+               Some(error::AmplifiedListPointer));
+}
+
+#[test]
+fn text_not_utf8() {
+    // A root pointer to a two-byte list holding 0xff and a NUL terminator.
+    let data : layout::AlignedData<[u8, .. 16]> = layout::AlignedData {
+        _dummy: 0,
+        words : [0x01, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x00,
+                 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    };
+    let segment : &[Word] = unsafe {
+        std::mem::transmute(std::raw::Slice { data : data.words.as_ptr(), len : 2 })
+    };
+
+    let mut options = message::ReaderOptions::new();
+    options.fail_fast(true);
+    let arena = arena::ReaderArena::new([segment], options);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 64).unwrap();
+    assert_eq!(root.get_text(std::ptr::null(), 0).err(), Some(error::TextNotUtf8));
+
+    options.fail_fast(false);
+    let arena = arena::ReaderArena::new([segment], options);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 64).unwrap();
+    assert_eq!(root.get_text(std::ptr::null(), 0).unwrap(), "");
+}
+
+#[test]
+fn struct_element_nesting_limit() {
+    // A root pointer to a struct list with one element whose data is 42.
+    let data : layout::AlignedData<[u8, .. 24]> = layout::AlignedData {
+        _dummy: 0,
+        words : [0x01, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00,
+                 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    };
+    let segment : &[Word] = unsafe {
+        std::mem::transmute(std::raw::Slice { data : data.words.as_ptr(), len : 3 })
+    };
+
+    let mut options = message::ReaderOptions::new();
+    options.fail_fast(true);
+    let arena = arena::ReaderArena::new([segment], options);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 2).unwrap();
+    let list = root.get_list(layout::InlineComposite, std::ptr::null()).unwrap();
+    assert_eq!(list.get_struct_element(0).unwrap().get_data_field::<u64>(0), 42);
+
+    // The list itself can still be read, but its elements are too deep.
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 1).unwrap();
+    let list = root.get_list(layout::InlineComposite, std::ptr::null()).unwrap();
+    assert_eq!(list.get_struct_element(0).err(), Some(error::NestingLimitExceeded));
+
+    options.fail_fast(false);
+    let arena = arena::ReaderArena::new([segment], options);
+    let root = layout::PointerReader::get_root(&arena.segment0, arena.segment0.ptr, 1).unwrap();
+    let list = root.get_list(layout::InlineComposite, std::ptr::null()).unwrap();
+    assert_eq!(list.get_struct_element(0).unwrap().get_data_field::<u64>(0), 0);
+}
+
+#[test]
+fn upgrade_struct_list() {
+    use layout::PrimitiveElement;
+
+    let mut arena = arena::BuilderArena::new(message::GrowHeuristically, arena::NumWords(64), true);
+    let location = arena.segment0.allocate(1).unwrap();
+    let root = layout::PointerBuilder::get_root(&mut arena.segment0, location);
+
+    let old_size = layout::StructSize { data : 1, pointers : 0,
+                                        preferred_list_encoding : layout::InlineComposite };
+    let new_size = layout::StructSize { data : 2, pointers : 1,
+                                        preferred_list_encoding : layout::InlineComposite };
+
+    // A list of u32s, as written by a schema in which the element type was primitive.
+    let list = root.init_list(layout::FourBytes, 3);
+    for i in range(0u, 3) {
+        PrimitiveElement::set(&list, i, (i * 100) as u32);
+    }
+
+    let upgraded = root.get_struct_list(old_size, std::ptr::null()).unwrap();
+    assert_eq!(upgraded.size(), 3);
+    for i in range(0u, 3) {
+        assert_eq!(upgraded.get_struct_element(i).get_data_field::<u32>(0), (i * 100) as u32);
+        assert_eq!(upgraded.get_struct_element(i).get_data_field::<u32>(1), 0);
+        upgraded.get_struct_element(i).set_data_field::<u64>(0, i as u64 + 7);
+    }
+
+    // Now grow the structs themselves.
+    let upgraded = root.get_struct_list(new_size, std::ptr::null()).unwrap();
+    assert_eq!(upgraded.size(), 3);
+    for i in range(0u, 3) {
+        let element = upgraded.get_struct_element(i);
+        assert_eq!(element.get_data_field::<u64>(0), i as u64 + 7);
+        assert_eq!(element.get_data_field::<u64>(1), 0);
+        assert!(element.get_pointer_field(0).is_null());
+    }
+}
+
+#[test]
+fn struct_default_value() {
+    // A pointer to a struct with one data word.
+    let default_value : layout::AlignedData<[u8, .. 16]> = layout::AlignedData {
+        _dummy: 0,
+        words : [0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    };
+    let default_ptr : *Word = unsafe { std::mem::transmute(default_value.words.as_ptr()) };
+    let size = layout::StructSize { data : 1, pointers : 0,
+                                    preferred_list_encoding : layout::EightBytes };
+
+    let mut arena = arena::BuilderArena::new(message::GrowHeuristically, arena::NumWords(64), true);
+    let location = arena.segment0.allocate(1).unwrap();
+    let root = layout::PointerBuilder::get_root(&mut arena.segment0, location);
+
+    assert_eq!(root.as_reader().get_struct(default_ptr).unwrap().get_data_field::<u64>(0), 42);
+    assert!(root.is_null());
+
+    let builder = root.get_struct(size, default_ptr).unwrap();
+    assert!(!root.is_null());
+    assert_eq!(builder.get_data_field::<u64>(0), 42);
+    builder.set_data_field::<u64>(0, 43);
+    assert_eq!(root.as_reader().get_struct(default_ptr).unwrap().get_data_field::<u64>(0), 43);
+    assert_eq!(default_value.words[8], 0x2a);
+}
+
+#[test]
+fn double_far_transfer() {
+    // Fixed-size segments of four words: the root pointer, a struct with one data
+    // word and one pointer, and the text that pointer refers to fill segment 0.
+    let mut arena = arena::BuilderArena::new(message::FixedSize, arena::NumWords(4), true);
+    let location = arena.segment0.allocate(1).unwrap();
+    let root = layout::PointerBuilder::get_root(&mut arena.segment0, location);
+
+    let old_size = layout::StructSize { data : 1, pointers : 1,
+                                        preferred_list_encoding : layout::InlineComposite };
+    let new_size = layout::StructSize { data : 2, pointers : 1,
+                                        preferred_list_encoding : layout::InlineComposite };
+
+    let old_struct = root.init_struct(old_size);
+    old_struct.set_data_field::<u64>(0, 17);
+    old_struct.get_pointer_field(0).set_text("hi");
+    assert!(arena.more_segments.len() == 0);
+
+    // Growing the struct moves it to a new segment. Segment 0 has no room left for a
+    // landing pad, so the text has to be reached through a double-far pointer.
+    let new_struct = root.get_struct(new_size, std::ptr::null()).unwrap();
+    assert_eq!(new_struct.get_data_field::<u64>(0), 17);
+    assert!(new_struct.get_pointer_field(0).get_text(std::ptr::null(), 0).unwrap()
+            .as_mut_bytes() == "hi".as_bytes());
+
+    let reader = root.as_reader().get_struct(std::ptr::null()).unwrap();
+    assert_eq!(reader.get_data_field::<u64>(0), 17);
+    assert!(reader.get_pointer_field(0).get_text(std::ptr::null(), 0).unwrap() == "hi");
+    assert_eq!(reader.total_size().unwrap().word_count, 4);
+}
+
+#[test]
+fn canonicalize() {
+    use message::MessageReader;
+
+    let mut arena = arena::BuilderArena::new(message::GrowHeuristically, arena::NumWords(64), true);
+    let location = arena.segment0.allocate(1).unwrap();
+    let root = layout::PointerBuilder::get_root(&mut arena.segment0, location);
+
+    let size = layout::StructSize { data : 3, pointers : 3,
+                                    preferred_list_encoding : layout::InlineComposite };
+    let element_size = layout::StructSize { data : 2, pointers : 1,
+                                            preferred_list_encoding : layout::InlineComposite };
+
+    // The data section ends in two zero words and the last pointer is null.
+    let builder = root.init_struct(size);
+    builder.set_data_field::<u64>(0, 5);
+    builder.get_pointer_field(0).set_text("hi");
+    let list = builder.get_pointer_field(1).init_struct_list(2, element_size);
+    list.get_struct_element(0).set_data_field::<u64>(0, 1);
+    list.get_struct_element(1).set_data_field::<u64>(0, 2);
+
+    arena.get_segments_for_output(|segments| {
+        let reader = message::SegmentArrayMessageReader::new(segments, message::ReaderOptions::new());
+        assert!(!reader.is_canonical().unwrap());
+    });
+
+    let words = root.as_reader().get_struct(std::ptr::null()).unwrap().canonicalize().unwrap();
+
+    // Root pointer, a struct with one data word and two pointers, the text,
+    // and a list tag followed by two one-word elements.
+    assert_eq!(words.len(), 8);
+
+    let segments = [words.as_slice()];
+    let reader = message::SegmentArrayMessageReader::new(segments, message::ReaderOptions::new());
+    assert!(reader.is_canonical().unwrap());
+
+    let root_reader = layout::PointerReader::get_root(&reader.arena().segment0,
+                                                      words.as_ptr(), 64).unwrap();
+    let struct_reader = root_reader.get_struct(std::ptr::null()).unwrap();
+    assert_eq!(struct_reader.get_data_section_size(), 64);
+    assert_eq!(struct_reader.get_pointer_section_size(), 2);
+    assert_eq!(struct_reader.get_data_field::<u64>(0), 5);
+    assert!(struct_reader.get_pointer_field(0).get_text(std::ptr::null(), 0).unwrap() == "hi");
+    let list_reader = struct_reader.get_pointer_field(1).get_list(layout::InlineComposite,
+                                                                  std::ptr::null()).unwrap();
+    assert_eq!(list_reader.size(), 2);
+    assert_eq!(list_reader.get_struct_element(0).unwrap().get_data_field::<u64>(0), 1);
+    assert_eq!(list_reader.get_struct_element(1).unwrap().get_data_field::<u64>(0), 2);
+
+    // Canonicalizing a canonical message gives back the same words.
+    let again = reader.get_root_internal().unwrap().canonicalize().unwrap();
+    assert_eq!(again.len(), words.len());
+    for i in range(0, words.len()) {
+        let a : u64 = unsafe { std::mem::transmute(words.as_slice()[i]) };
+        let b : u64 = unsafe { std::mem::transmute(again.as_slice()[i]) };
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn struct_equality() {
+    let mut arena = arena::BuilderArena::new(message::GrowHeuristically, arena::NumWords(64), true);
+    let location1 = arena.segment0.allocate(1).unwrap();
+    let location2 = arena.segment0.allocate(1).unwrap();
+    let root1 = layout::PointerBuilder::get_root(&mut arena.segment0, location1);
+    let root2 = layout::PointerBuilder::get_root(&mut arena.segment0, location2);
+
+    // A struct with trailing zero data and a null pointer equals a smaller one.
+    let small = root1.init_struct(layout::StructSize { data : 1, pointers : 0,
+                                                       preferred_list_encoding : layout::EightBytes });
+    let large = root2.init_struct(layout::StructSize { data : 2, pointers : 1,
+                                                       preferred_list_encoding : layout::InlineComposite });
+    small.set_data_field::<u32>(1, 99);
+    large.set_data_field::<u32>(1, 99);
+
+    let small_reader = small.as_reader();
+    let large_reader = large.as_reader();
+    assert!(small_reader.equals(&large_reader).unwrap());
+    assert_eq!(small_reader.stable_hash().unwrap(), large_reader.stable_hash().unwrap());
+    assert!(root1.as_reader().equals(&root2.as_reader()).unwrap());
+
+    large.get_pointer_field(0).set_text("x");
+    assert!(!small_reader.equals(&large.as_reader()).unwrap());
+    assert!(!root1.as_reader().equals(&root2.as_reader()).unwrap());
+}
+
+#[test]
+fn list_equality_is_transitive() {
+    let mut arena = arena::BuilderArena::new(message::GrowHeuristically, arena::NumWords(64), true);
+    let location1 = arena.segment0.allocate(1).unwrap();
+    let location2 = arena.segment0.allocate(1).unwrap();
+    let location3 = arena.segment0.allocate(1).unwrap();
+    let bytes_root = layout::PointerBuilder::get_root(&mut arena.segment0, location1);
+    let shorts_root = layout::PointerBuilder::get_root(&mut arena.segment0, location2);
+    let structs_root = layout::PointerBuilder::get_root(&mut arena.segment0, location3);
+
+    let bytes = bytes_root.init_list(layout::Byte, 2);
+    let shorts = shorts_root.init_list(layout::TwoBytes, 2);
+    let structs = structs_root.init_struct_list(
+        2, layout::StructSize { data : 1, pointers : 0, preferred_list_encoding : layout::EightBytes });
+    for i in range(0u, 2) {
+        layout::PrimitiveElement::set(&bytes, i, i as u8 + 1);
+        layout::PrimitiveElement::set(&shorts, i, i as u16 + 1);
+        structs.get_struct_element(i).set_data_field::<u64>(0, i as u64 + 1);
+    }
+
+    let bytes = bytes_root.as_reader();
+    let shorts = shorts_root.as_reader();
+    let structs = structs_root.as_reader();
+    assert!(bytes.equals(&structs).unwrap());
+    assert!(shorts.equals(&structs).unwrap());
+    assert!(bytes.equals(&shorts).unwrap());
+    assert_eq!(bytes.stable_hash().unwrap(), shorts.stable_hash().unwrap());
+    assert_eq!(shorts.stable_hash().unwrap(), structs.stable_hash().unwrap());
+
+    layout::PrimitiveElement::set(&shorts_root.get_list(layout::TwoBytes, std::ptr::null()).unwrap(),
+                                  1, 0x102u16);
+    assert!(!bytes.equals(&shorts).unwrap());
+    assert!(!shorts.equals(&structs).unwrap());
+}
+
+struct CountingAllocator {
+    inner : message::HeapAllocator,
+    allocated : *mut uint,
+    deallocated : *mut uint,
+}
+
+impl message::SegmentAllocator for CountingAllocator {
+    fn allocate_segment(&mut self, minimum_size : uint) -> (*mut Word, uint) {
+        unsafe { *self.allocated += 1; }
+        self.inner.allocate_segment(minimum_size)
+    }
+
+    fn deallocate_segment(&mut self, ptr : *mut Word, size : uint) {
+        unsafe { *self.deallocated += 1; }
+        self.inner.deallocate_segment(ptr, size)
+    }
+}
+
+#[test]
+fn custom_segment_allocator() {
+    let mut allocated = 0u;
+    let mut deallocated = 0u;
+    {
+        let allocator = box CountingAllocator {
+            inner : message::HeapAllocator::new(2, message::FixedSize),
+            allocated : &mut allocated,
+            deallocated : &mut deallocated,
+        };
+        let mut arena = arena::BuilderArena::new_with_allocator(allocator, arena::NumWords(2), true);
+        let location = arena.segment0.allocate(1).unwrap();
+        let root = layout::PointerBuilder::get_root(&mut arena.segment0, location);
+
+        // The text doesn't fit next to the root, so it goes in a second segment.
+        root.set_text("hello, world");
+        assert_eq!(arena.more_segments.len(), 1);
+        assert!(root.as_reader().get_text(std::ptr::null(), 0).unwrap() == "hello, world");
+    }
+    assert_eq!(allocated, 2);
+    assert_eq!(deallocated, 2);
+}
@@ -22,9 +22,12 @@ extern crate debug;
 pub use any::AnyPointer;
 pub use blob::{Text, Data};
 pub use common::{MessageSize};
-pub use list::{PrimitiveList, EnumList, StructList, TextList, DataList, ListList};
+pub use error::{Error, Result};
+pub use list::{PrimitiveList, EnumList, StructList, TextList, DataList, ListList,
+               AnyPointerList, CapabilityList};
 pub use message::{MessageBuilder, BuilderOptions, MessageReader, ReaderOptions};
 pub use message::MallocMessageBuilder;
+pub use orphan::{Orphan, Orphanage};
 pub use serialize::OwnedSpaceMessageReader;
 
 pub mod any;
@@ -32,20 +35,30 @@ pub mod arena;
 pub mod blob;
 pub mod capability;
 pub mod common;
+pub mod dynamic;
 pub mod endian;
+pub mod error;
 pub mod io;
 pub mod layout;
 pub mod list;
 pub mod mask;
 pub mod message;
+pub mod orphan;
+pub mod schema_capnp;
 pub mod serialize;
 pub mod serialize_packed;
+pub mod shared_memory;
+pub mod visitor;
 
 
+#[cfg(test)]
+pub mod dynamic_test;
 #[cfg(test)]
 pub mod layout_test;
 #[cfg(test)]
 pub mod serialize_packed_test;
+#[cfg(test)]
+pub mod serialize_test;
 
 
 
@@ -6,25 +6,33 @@
 
 use layout::{PointerBuilder, PointerReader};
 use common::Word;
+use error::Result;
 
 
 // TODO maybe we can simplify these traits. It seems that the only
 // difference in the implementations is the FieldSize.
 
 pub trait FromPointerReader<'a> {
-    fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Self;
+    fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Self>;
 }
 
 pub trait FromPointerBuilder<'a> {
+    fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Self>;
+}
+
+// Capabilities cannot be initialized in place, so clients implement
+// FromPointerBuilder but not this.
+pub trait InitPointerBuilder<'a> : FromPointerBuilder<'a> {
     fn init_pointer(PointerBuilder<'a>, uint) -> Self;
-    fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Self;
 }
 
 pub mod PrimitiveList {
-    use super::{FromPointerReader, FromPointerBuilder};
-    use layout::{ListReader, ListBuilder, PointerReader, PointerBuilder,
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
+    use layout::{ListReader, ListBuilder, PointerReader, PointerBuilder, OrphanBuilder,
                  PrimitiveElement, element_size_for_type};
     use common::Word;
+    use error::Result;
 
     pub struct Reader<'a, T> {
         // I want this field to be private, but then I can't access it in set_list()
@@ -40,8 +48,8 @@ pub mod PrimitiveList {
     }
 
     impl <'a, T : PrimitiveElement> FromPointerReader<'a> for Reader<'a, T> {
-        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Reader<'a, T> {
-            Reader { reader : reader.get_list(element_size_for_type::<T>(), default_value) }
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a, T>> {
+            Ok(Reader { reader : try!(reader.get_list(element_size_for_type::<T>(), default_value)) })
         }
     }
 
@@ -69,11 +77,23 @@ pub mod PrimitiveList {
     }
 
     impl <'a, T : PrimitiveElement> FromPointerBuilder<'a> for Builder<'a, T> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.get_list(element_size_for_type::<T>(), default_value)) })
+        }
+    }
+
+    impl <'a, T : PrimitiveElement> InitPointerBuilder<'a> for Builder<'a, T> {
         fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a, T> {
             Builder { builder : builder.init_list(element_size_for_type::<T>(), size) }
         }
-        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Builder<'a, T> {
-            Builder { builder : builder.get_list(element_size_for_type::<T>(), default_value) }
+    }
+
+    impl <'a, T : PrimitiveElement> FromOrphanBuilder<'a> for Builder<'a, T> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), element_size_for_type::<T>(), size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.as_list(element_size_for_type::<T>())) })
         }
     }
 
@@ -93,7 +113,9 @@ pub trait ToU16 {
 pub mod EnumList {
     use layout::*;
     use list::*;
+    use orphan::{FromOrphanBuilder, Orphanage};
     use common::Word;
+    use error::Result;
 
     pub struct Reader<'a, T> {
         pub reader : ListReader<'a>
@@ -109,8 +131,8 @@ pub mod EnumList {
     }
 
     impl <'a, T : FromPrimitive> FromPointerReader<'a> for Reader<'a, T> {
-        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Reader<'a, T> {
-            Reader { reader : reader.get_list(TwoBytes, default_value) }
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a, T>> {
+            Ok(Reader { reader : try!(reader.get_list(TwoBytes, default_value)) })
         }
     }
 
@@ -140,11 +162,23 @@ pub mod EnumList {
     }
 
     impl <'a, T : FromPrimitive> FromPointerBuilder<'a> for Builder<'a, T> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.get_list(TwoBytes, default_value)) })
+        }
+    }
+
+    impl <'a, T : FromPrimitive> InitPointerBuilder<'a> for Builder<'a, T> {
         fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a, T> {
             Builder { builder : builder.init_list(TwoBytes, size) }
         }
-        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Builder<'a, T> {
-            Builder { builder : builder.get_list(TwoBytes, default_value) }
+    }
+
+    impl <'a, T : FromPrimitive> FromOrphanBuilder<'a> for Builder<'a, T> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), TwoBytes, size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.as_list(TwoBytes)) })
         }
     }
 
@@ -159,9 +193,11 @@ pub mod EnumList {
 }
 
 pub mod StructList {
-    use super::{FromPointerReader, FromPointerBuilder};
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
     use common::Word;
     use layout::*;
+    use error::Result;
 
     pub struct Reader<'a, T> {
         pub reader : ListReader<'a>
@@ -173,18 +209,27 @@ pub mod StructList {
         }
 
         pub fn size(&self) -> uint { self.reader.size() }
+
+        pub fn get(&self, index : uint) -> Result<T> {
+            assert!(index < self.size());
+            Ok(FromStructReader::new(try!(self.reader.get_struct_element(index))))
+        }
     }
 
     impl <'a, T : FromStructReader<'a>> FromPointerReader<'a> for Reader<'a, T> {
-        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Reader<'a, T> {
-            Reader { reader : reader.get_list(InlineComposite, default_value) }
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a, T>> {
+            Ok(Reader { reader : try!(reader.get_list(InlineComposite, default_value)) })
         }
     }
 
+    // Gives a default struct for an element that is nested too deeply,
+    // even when the message is read with fail_fast. Use get() to see the
+    // error.
     impl <'a, T : FromStructReader<'a>> Index<uint, T> for Reader<'a, T> {
         fn index(&self, index : &uint) -> T {
             assert!(*index < self.size());
-            let result : T = FromStructReader::new(self.reader.get_struct_element(*index));
+            let result : T = FromStructReader::new(
+                self.reader.get_struct_element(*index).unwrap_or(StructReader::new_default()));
             result
         }
     }
@@ -205,15 +250,27 @@ pub mod StructList {
     }
 
     impl <'a, T : FromStructBuilder<'a> + HasStructSize> FromPointerBuilder<'a> for Builder<'a, T> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a, T>> {
+            Ok(Builder {
+                builder : try!(builder.get_struct_list(HasStructSize::struct_size(None::<T>), default_value))
+            })
+        }
+    }
+
+    impl <'a, T : FromStructBuilder<'a> + HasStructSize> InitPointerBuilder<'a> for Builder<'a, T> {
         fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a, T> {
             Builder {
                 builder : builder.init_struct_list(size, HasStructSize::struct_size(None::<T>))
             }
         }
-        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Builder<'a, T> {
-            Builder {
-                builder : builder.get_struct_list(HasStructSize::struct_size(None::<T>), default_value)
-            }
+    }
+
+    impl <'a, T : FromStructBuilder<'a> + HasStructSize> FromOrphanBuilder<'a> for Builder<'a, T> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_struct_list(orphanage.arena(), size, HasStructSize::struct_size(None::<T>))
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.as_struct_list(HasStructSize::struct_size(None::<T>))) })
         }
     }
 
@@ -228,10 +285,12 @@ pub mod StructList {
 }
 
 pub mod ListList {
-    use super::{FromPointerReader, FromPointerBuilder};
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
     use std;
     use common::Word;
     use layout::*;
+    use error::Result;
 
     pub struct Reader<'a, T> {
         pub reader : ListReader<'a>
@@ -246,16 +305,16 @@ pub mod ListList {
     }
 
     impl <'a, T : FromPointerReader<'a>> FromPointerReader<'a> for Reader<'a, T> {
-        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Reader<'a, T> {
-            Reader { reader : reader.get_list(Pointer, default_value) }
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a, T>> {
+            Ok(Reader { reader : try!(reader.get_list(Pointer, default_value)) })
         }
     }
 
-    impl <'a, T : FromPointerReader<'a>> Index<uint, T> for Reader<'a, T> {
-        fn index(&self, index : &uint) -> T {
-            assert!(*index <  self.size());
+    impl <'a, T : FromPointerReader<'a>> Reader<'a, T> {
+        pub fn get(&self, index : uint) -> Result<T> {
+            assert!(index <  self.size());
             FromPointerReader::get_from_pointer(
-                &self.reader.get_pointer_element(*index), std::ptr::null())
+                &self.reader.get_pointer_element(index), std::ptr::null())
         }
     }
 
@@ -269,47 +328,61 @@ pub mod ListList {
         }
 
         pub fn size(&self) -> uint { self.builder.size() }
+    }
 
+    impl <'a, T : InitPointerBuilder<'a>> Builder<'a, T> {
         pub fn init(&self, index : uint, size : uint) -> T {
             let result : T =
-                FromPointerBuilder::init_pointer(self.builder.get_pointer_element(index), size);
+                InitPointerBuilder::init_pointer(self.builder.get_pointer_element(index), size);
             result
         }
     }
 
 
     impl <'a, T : FromPointerBuilder<'a>> FromPointerBuilder<'a> for Builder<'a, T> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a, T>> {
+            Ok(Builder {
+                builder : try!(builder.get_list(Pointer, default_value))
+            })
+        }
+    }
+
+    impl <'a, T : FromPointerBuilder<'a>> InitPointerBuilder<'a> for Builder<'a, T> {
         fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a, T> {
             Builder {
                 builder : builder.init_list(Pointer, size)
             }
         }
-        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Builder<'a, T> {
-            Builder {
-                builder : builder.get_list(Pointer, default_value)
-            }
+    }
+
+    impl <'a, T : FromPointerBuilder<'a>> FromOrphanBuilder<'a> for Builder<'a, T> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), Pointer, size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.as_list(Pointer)) })
         }
     }
 
-    impl <'a, T : FromPointerBuilder<'a>> Index<uint, T> for Builder<'a, T> {
-        fn index(&self, index : &uint) -> T {
-            assert!(*index < self.size());
-            let result : T =
-                FromPointerBuilder::get_from_pointer(
-                self.builder.get_pointer_element(*index),
-                std::ptr::null());
-            result
+    impl <'a, T : FromPointerBuilder<'a>> Builder<'a, T> {
+        pub fn get(&self, index : uint) -> Result<T> {
+            assert!(index < self.size());
+            FromPointerBuilder::get_from_pointer(
+                self.builder.get_pointer_element(index),
+                std::ptr::null())
         }
     }
 
 }
 
 pub mod TextList {
-    use super::{FromPointerReader, FromPointerBuilder};
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
     use std;
     use common::Word;
     use blob::Text;
     use layout::*;
+    use error::Result;
 
     pub struct Reader<'a> {
         pub reader : ListReader<'a>
@@ -324,15 +397,15 @@ pub mod TextList {
     }
 
     impl <'a> FromPointerReader<'a> for Reader<'a> {
-        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Reader<'a> {
-            Reader { reader : reader.get_list(Pointer, default_value) }
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a>> {
+            Ok(Reader { reader : try!(reader.get_list(Pointer, default_value)) })
         }
     }
 
-    impl <'a> Index<uint, Text::Reader<'a>> for Reader<'a> {
-        fn index(&self, index : &uint) -> Text::Reader<'a> {
-            assert!(*index <  self.size());
-            self.reader.get_pointer_element(*index).get_text(std::ptr::null(), 0)
+    impl <'a> Reader<'a> {
+        pub fn get(&self, index : uint) -> Result<Text::Reader<'a>> {
+            assert!(index <  self.size());
+            self.reader.get_pointer_element(index).get_text(std::ptr::null(), 0)
         }
     }
 
@@ -355,32 +428,47 @@ pub mod TextList {
 
 
     impl <'a> FromPointerBuilder<'a> for Builder<'a> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a>> {
+            Ok(Builder {
+                builder : try!(builder.get_list(Pointer, default_value))
+            })
+        }
+    }
+
+    impl <'a> InitPointerBuilder<'a> for Builder<'a> {
         fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a> {
             Builder {
                 builder : builder.init_list(Pointer, size)
             }
         }
-        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Builder<'a> {
-            Builder {
-                builder : builder.get_list(Pointer, default_value)
-            }
+    }
+
+    impl <'a> FromOrphanBuilder<'a> for Builder<'a> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), Pointer, size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a>> {
+            Ok(Builder { builder : try!(builder.as_list(Pointer)) })
         }
     }
 
-    impl <'a> Index<uint, Text::Builder<'a>> for Builder<'a> {
-        fn index(&self, index : &uint) -> Text::Builder<'a> {
-            self.builder.get_pointer_element(*index).get_text(std::ptr::null(), 0)
+    impl <'a> Builder<'a> {
+        pub fn get(&self, index : uint) -> Result<Text::Builder<'a>> {
+            assert!(index < self.size());
+            self.builder.get_pointer_element(index).get_text(std::ptr::null(), 0)
         }
     }
 
 }
 
 pub mod DataList {
-    use super::{FromPointerReader, FromPointerBuilder};
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
     use std;
     use common::Word;
     use blob::Data;
     use layout::*;
+    use error::Result;
 
     pub struct Reader<'a> {
         pub reader : ListReader<'a>
@@ -395,15 +483,15 @@ pub mod DataList {
     }
 
     impl <'a> FromPointerReader<'a> for Reader<'a> {
-        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Reader<'a> {
-            Reader { reader : reader.get_list(Pointer, default_value) }
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a>> {
+            Ok(Reader { reader : try!(reader.get_list(Pointer, default_value)) })
         }
     }
 
-    impl <'a> Index<uint, Data::Reader<'a>> for Reader<'a> {
-        fn index(&self, index : &uint) -> Data::Reader<'a> {
-            assert!(*index <  self.size());
-            self.reader.get_pointer_element(*index).get_data(std::ptr::null(), 0)
+    impl <'a> Reader<'a> {
+        pub fn get(&self, index : uint) -> Result<Data::Reader<'a>> {
+            assert!(index <  self.size());
+            self.reader.get_pointer_element(index).get_data(std::ptr::null(), 0)
         }
     }
 
@@ -426,23 +514,196 @@ pub mod DataList {
 
 
     impl <'a> FromPointerBuilder<'a> for Builder<'a> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a>> {
+            Ok(Builder {
+                builder : try!(builder.get_list(Pointer, default_value))
+            })
+        }
+    }
+
+    impl <'a> InitPointerBuilder<'a> for Builder<'a> {
         fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a> {
             Builder {
                 builder : builder.init_list(Pointer, size)
             }
         }
-        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Builder<'a> {
+    }
+
+    impl <'a> FromOrphanBuilder<'a> for Builder<'a> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), Pointer, size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a>> {
+            Ok(Builder { builder : try!(builder.as_list(Pointer)) })
+        }
+    }
+
+    impl <'a> Builder<'a> {
+        pub fn get(&self, index : uint) -> Result<Data::Builder<'a>> {
+            assert!(index < self.size());
+            self.builder.get_pointer_element(index).get_data(std::ptr::null(), 0)
+        }
+    }
+
+}
+
+pub mod AnyPointerList {
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
+    use any::AnyPointer;
+    use common::Word;
+    use layout::*;
+    use error::Result;
+
+    pub struct Reader<'a> {
+        pub reader : ListReader<'a>
+    }
+
+    impl <'a> Reader<'a> {
+        pub fn new<'b>(reader : ListReader<'b>) -> Reader<'b> {
+            Reader::<'b> { reader : reader }
+        }
+
+        pub fn size(&self) -> uint { self.reader.size() }
+    }
+
+    impl <'a> FromPointerReader<'a> for Reader<'a> {
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a>> {
+            Ok(Reader { reader : try!(reader.get_list(Pointer, default_value)) })
+        }
+    }
+
+    impl <'a> Reader<'a> {
+        pub fn get(&self, index : uint) -> AnyPointer::Reader<'a> {
+            assert!(index <  self.size());
+            AnyPointer::Reader::new(self.reader.get_pointer_element(index))
+        }
+    }
+
+    pub struct Builder<'a> {
+        pub builder : ListBuilder<'a>
+    }
+
+    impl <'a> Builder<'a> {
+        pub fn new(builder : ListBuilder<'a>) -> Builder<'a> {
+            Builder { builder : builder }
+        }
+
+        pub fn size(&self) -> uint { self.builder.size() }
+    }
+
+    impl <'a> FromPointerBuilder<'a> for Builder<'a> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a>> {
+            Ok(Builder {
+                builder : try!(builder.get_list(Pointer, default_value))
+            })
+        }
+    }
+
+    impl <'a> InitPointerBuilder<'a> for Builder<'a> {
+        fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a> {
             Builder {
-                builder : builder.get_list(Pointer, default_value)
+                builder : builder.init_list(Pointer, size)
             }
         }
     }
 
-    impl <'a> Index<uint, Data::Builder<'a>> for Builder<'a> {
-        fn index(&self, index : &uint) -> Data::Builder<'a> {
-            assert!(*index < self.size());
-            self.builder.get_pointer_element(*index).get_data(std::ptr::null(), 0)
+    impl <'a> FromOrphanBuilder<'a> for Builder<'a> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), Pointer, size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a>> {
+            Ok(Builder { builder : try!(builder.as_list(Pointer)) })
+        }
+    }
+
+    impl <'a> Builder<'a> {
+        pub fn get(&self, index : uint) -> AnyPointer::Builder<'a> {
+            assert!(index < self.size());
+            AnyPointer::Builder::new(self.builder.get_pointer_element(index))
+        }
+    }
+}
+
+pub mod CapabilityList {
+    use super::{FromPointerReader, FromPointerBuilder, InitPointerBuilder};
+    use orphan::{FromOrphanBuilder, Orphanage};
+    use capability::{ClientHook, FromClientHook};
+    use common::Word;
+    use layout::*;
+    use error::Result;
+
+    pub struct Reader<'a, T> {
+        pub reader : ListReader<'a>
+    }
+
+    impl <'a, T : FromClientHook> Reader<'a, T> {
+        pub fn new<'b>(reader : ListReader<'b>) -> Reader<'b, T> {
+            Reader::<'b, T> { reader : reader }
+        }
+
+        pub fn size(&self) -> uint { self.reader.size() }
+    }
+
+    impl <'a, T : FromClientHook> FromPointerReader<'a> for Reader<'a, T> {
+        fn get_from_pointer(reader : &PointerReader<'a>, default_value : *Word) -> Result<Reader<'a, T>> {
+            Ok(Reader { reader : try!(reader.get_list(Pointer, default_value)) })
+        }
+    }
+
+    impl <'a, T : FromClientHook> Reader<'a, T> {
+        pub fn get(&self, index : uint) -> Result<T> {
+            assert!(index <  self.size());
+            self.reader.get_pointer_element(index).get_capability().map(|c| FromClientHook::new(c))
+        }
+    }
+
+    pub struct Builder<'a, T> {
+        pub builder : ListBuilder<'a>
+    }
+
+    impl <'a, T : FromClientHook> Builder<'a, T> {
+        pub fn new(builder : ListBuilder<'a>) -> Builder<'a, T> {
+            Builder { builder : builder }
+        }
+
+        pub fn size(&self) -> uint { self.builder.size() }
+
+        pub fn set(&self, index : uint, value : Box<ClientHook:Send>) {
+            assert!(index < self.size());
+            self.builder.get_pointer_element(index).set_capability(value);
         }
     }
 
+    impl <'a, T : FromClientHook> FromPointerBuilder<'a> for Builder<'a, T> {
+        fn get_from_pointer(builder : PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a, T>> {
+            Ok(Builder {
+                builder : try!(builder.get_list(Pointer, default_value))
+            })
+        }
+    }
+
+    impl <'a, T : FromClientHook> InitPointerBuilder<'a> for Builder<'a, T> {
+        fn init_pointer(builder : PointerBuilder<'a>, size : uint) -> Builder<'a, T> {
+            Builder {
+                builder : builder.init_list(Pointer, size)
+            }
+        }
+    }
+
+    impl <'a, T : FromClientHook> FromOrphanBuilder<'a> for Builder<'a, T> {
+        fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a> {
+            OrphanBuilder::init_list(orphanage.arena(), Pointer, size)
+        }
+        fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Builder<'a, T>> {
+            Ok(Builder { builder : try!(builder.as_list(Pointer)) })
+        }
+    }
+
+    impl <'a, T : FromClientHook> Builder<'a, T> {
+        pub fn get(&self, index : uint) -> Result<T> {
+            assert!(index < self.size());
+            self.builder.get_pointer_element(index).get_capability().map(|c| FromClientHook::new(c))
+        }
+    }
 }
@@ -6,18 +6,21 @@
 
 use std;
 use std::vec::Vec;
+use libc;
 use any::AnyPointer;
 use capability::ClientHook;
 use common::*;
 use arena::*;
+use error::Result;
 use layout;
 use layout::{FromStructBuilder, HasStructSize};
+use orphan::Orphanage;
 
 pub struct ReaderOptions {
     pub traversal_limit_in_words : u64,
     pub nesting_limit : int,
 
-    // If true, malformed messages trigger task failure.
+    // If true, malformed messages are reported as errors.
     // If false, malformed messages fall back to default values.
     pub fail_fast : bool,
 }
@@ -54,19 +57,41 @@ pub trait MessageReader {
     fn mut_arena<'a>(&'a mut self) -> &'a mut ReaderArena;
     fn get_options<'a>(&'a self) -> &'a ReaderOptions;
 
-    fn get_root_internal<'a> (&'a self) -> AnyPointer::Reader<'a> {
+    fn get_root_internal<'a> (&'a self) -> Result<AnyPointer::Reader<'a>> {
         unsafe {
             let segment : *SegmentReader = &self.arena().segment0;
 
-            let pointer_reader = layout::PointerReader::get_root(
-                segment, (*segment).get_start_ptr(), self.get_options().nesting_limit as int);
+            let pointer_reader = try!(layout::PointerReader::get_root(
+                segment, (*segment).get_start_ptr(), self.get_options().nesting_limit as int));
 
-            AnyPointer::Reader::new(pointer_reader)
+            Ok(AnyPointer::Reader::new(pointer_reader))
         }
     }
 
-    fn get_root<'a, T : layout::FromStructReader<'a>>(&'a self) -> T {
-        self.get_root_internal().get_as_struct()
+    fn get_root<'a, T : layout::FromStructReader<'a>>(&'a self) -> Result<T> {
+        try!(self.get_root_internal()).get_as_struct()
+    }
+
+    // Checks whether this message is in canonical form: a single segment
+    // holding the root object and everything it points to, laid out in
+    // preorder with no padding, no far pointers, and truncated structs.
+    fn is_canonical(&self) -> Result<bool> {
+        if self.arena().more_segments.len() > 0 {
+            //# Multi-segment messages are not canonical.
+            return Ok(false);
+        }
+
+        unsafe {
+            let segment : *SegmentReader = &self.arena().segment0;
+            let start = (*segment).get_start_ptr();
+            let pointer_reader = try!(layout::PointerReader::get_root(
+                segment, start, self.get_options().nesting_limit as int));
+
+            let mut read_head = start.offset(POINTER_SIZE_IN_WORDS as int);
+            let root_is_canonical = try!(pointer_reader.is_canonical(&mut read_head));
+            let all_words_consumed = read_head == start.offset((*segment).size as int);
+            Ok(root_is_canonical && all_words_consumed)
+        }
     }
 
     fn init_cap_table(&mut self, cap_table : Vec<Option<Box<ClientHook:Send>>>) {
@@ -75,7 +100,7 @@ pub trait MessageReader {
 }
 
 pub struct SegmentArrayMessageReader<'a> {
-    segments : &'a [ &'a [Word]],
+    segments : Vec<&'a [Word]>,
     options : ReaderOptions,
     arena : Box<ReaderArena>
 }
@@ -83,7 +108,7 @@ pub struct SegmentArrayMessageReader<'a> {
 
 impl <'a> MessageReader for SegmentArrayMessageReader<'a> {
     fn get_segment<'b>(&'b self, id : uint) -> &'b [Word] {
-        self.segments[id]
+        self.segments.as_slice()[id]
     }
 
     fn arena<'b>(&'b self) -> &'b ReaderArena { &*self.arena }
@@ -97,10 +122,16 @@ impl <'a> MessageReader for SegmentArrayMessageReader<'a> {
 impl <'a> SegmentArrayMessageReader<'a> {
 
     pub fn new<'b>(segments : &'b [&'b [Word]], options : ReaderOptions) -> SegmentArrayMessageReader<'b> {
+        SegmentArrayMessageReader::from_segments(Vec::from_slice(segments), options)
+    }
+
+    pub fn from_segments<'b>(segments : Vec<&'b [Word]>,
+                             options : ReaderOptions) -> SegmentArrayMessageReader<'b> {
         assert!(segments.len() > 0);
+        let arena = ReaderArena::new(segments.as_slice(), options);
         SegmentArrayMessageReader {
             segments : segments,
-            arena : ReaderArena::new(segments, options),
+            arena : arena,
             options : options
         }
     }
@@ -118,7 +149,7 @@ pub struct BuilderOptions {
     pub first_segment_words : uint,
     pub allocation_strategy : AllocationStrategy,
 
-    // If true, malformed messages trigger task failure.
+    // If true, malformed messages are reported as errors.
     // If false, malformed messages fall back to default values.
     pub fail_fast : bool,
 }
@@ -146,6 +177,47 @@ impl BuilderOptions {
     }
 }
 
+// Provides the memory for the segments of messages being built.
+pub trait SegmentAllocator {
+    // Returns a zeroed block of at least `minimum_size` words, along with
+    // the number of words actually available in it.
+    fn allocate_segment(&mut self, minimum_size : WordCount) -> (*mut Word, WordCount);
+
+    // Called for each segment returned by `allocate_segment()` when the
+    // message that owns it is dropped.
+    fn deallocate_segment(&mut self, ptr : *mut Word, size : WordCount);
+}
+
+// Allocates segments on the heap, sizing them according to an AllocationStrategy.
+pub struct HeapAllocator {
+    next_size : WordCount,
+    allocation_strategy : AllocationStrategy,
+}
+
+impl HeapAllocator {
+    pub fn new(first_segment_words : WordCount,
+               allocation_strategy : AllocationStrategy) -> HeapAllocator {
+        HeapAllocator { next_size : first_segment_words, allocation_strategy : allocation_strategy }
+    }
+}
+
+impl SegmentAllocator for HeapAllocator {
+    fn allocate_segment(&mut self, minimum_size : WordCount) -> (*mut Word, WordCount) {
+        let size = std::cmp::max(minimum_size, self.next_size);
+        let ptr : *mut Word = unsafe {
+            std::mem::transmute(libc::calloc(size as libc::size_t, BYTES_PER_WORD as libc::size_t))
+        };
+        match self.allocation_strategy {
+            GrowHeuristically => { self.next_size += size; }
+            FixedSize => { }
+        }
+        (ptr, size)
+    }
+
+    fn deallocate_segment(&mut self, ptr : *mut Word, _size : WordCount) {
+        unsafe { libc::free(std::mem::transmute(ptr)); }
+    }
+}
 
 pub trait MessageBuilder {
     fn mut_arena<'a>(&'a mut self) -> &'a mut BuilderArena;
@@ -179,12 +251,46 @@ pub trait MessageBuilder {
         self.get_root_internal().init_as_struct()
     }
 
-    fn get_root<'a, T : FromStructBuilder<'a> + HasStructSize>(&'a mut self) -> T {
+    fn get_root<'a, T : FromStructBuilder<'a> + HasStructSize>(&'a mut self) -> Result<T> {
         self.get_root_internal().get_as_struct()
     }
 
-    fn set_root<'a, T : layout::ToStructReader<'a> + layout::ToStructReader<'a>>(&'a mut self, value : &T) {
-        self.get_root_internal().set_as_struct(value);
+    fn set_root<'a, T : layout::ToStructReader<'a> + layout::ToStructReader<'a>>(&'a mut self,
+                                                                                  value : &T) -> Result<()> {
+        self.get_root_internal().set_as_struct(value)
+    }
+
+    // Orphans are zeroed when dropped, so the orphanage borrows the
+    // message for as long as it and its orphans are alive, and a message
+    // cannot be dropped while one of its orphans still exists:
+    //
+    //     let orphan = {
+    //         let mut message = MallocMessageBuilder::new_default();
+    //         message.get_orphanage().new_orphan::<Text::Builder>(5)
+    //     }; // error: `message` does not live long enough
+    //
+    // To build the root alongside orphans, get both from the root pointer
+    // with `get_root_internal()` and AnyPointer::Builder::get_orphanage().
+    fn get_orphanage<'a>(&'a mut self) -> Orphanage<'a> {
+        //# The root pointer must be allocated before any orphans, so
+        //# that it ends up at the start of the first segment.
+        self.get_root_internal().get_orphanage()
+    }
+
+    // Copies the message into a new builder with a single segment that holds
+    // only the objects reachable from the root, leaving behind the space of
+    // objects that were overwritten or cleared. The new message's cap table
+    // holds just the capabilities that are still referenced.
+    fn compact(&mut self) -> Result<MallocMessageBuilder> {
+        let root = self.get_root_internal().as_reader();
+        let size = try!(root.total_size());
+
+        let mut options = BuilderOptions::new();
+        options.first_segment_words(size.word_count as uint + POINTER_SIZE_IN_WORDS)
+               .fail_fast(self.arena().fail_fast);
+        let mut result = MallocMessageBuilder::new(options);
+        try!(result.get_root_internal().set_as(root));
+        Ok(result)
     }
 
     fn get_segments_for_output<T>(&self, cont : |&[&[Word]]| -> T) -> T {
@@ -214,10 +320,27 @@ impl MallocMessageBuilder {
         MallocMessageBuilder { arena : arena }
     }
 
+    // Builds a message whose segments come from `allocator`. The first
+    // segment is requested with a minimum size of `options.first_segment_words`;
+    // `options.allocation_strategy` is ignored, as sizing is up to the allocator.
+    pub fn new_with_allocator(allocator : Box<SegmentAllocator>,
+                              options : BuilderOptions) -> MallocMessageBuilder {
+        let arena = BuilderArena::new_with_allocator(allocator,
+                                                     NumWords(options.first_segment_words),
+                                                     options.fail_fast);
+
+        MallocMessageBuilder { arena : arena }
+    }
+
     pub fn new_default() -> MallocMessageBuilder {
         MallocMessageBuilder::new(BuilderOptions::new())
     }
 
+    // Discards the message, keeping its segments for the next one.
+    pub fn clear(&mut self) {
+        self.arena.reset();
+    }
+
 }
 
 impl MessageBuilder for MallocMessageBuilder {
@@ -229,6 +352,44 @@ impl MessageBuilder for MallocMessageBuilder {
     }
 }
 
+// Hands out MallocMessageBuilders and takes them back once they are no
+// longer needed, so that their segments get reused instead of being
+// allocated afresh for every message.
+pub struct MallocMessageBuilderPool {
+    options : BuilderOptions,
+    max_pooled : uint,
+    builders : Vec<MallocMessageBuilder>,
+}
+
+impl MallocMessageBuilderPool {
+    // `max_pooled` bounds how many idle builders are kept.
+    pub fn new(options : BuilderOptions, max_pooled : uint) -> MallocMessageBuilderPool {
+        MallocMessageBuilderPool { options : options, max_pooled : max_pooled, builders : Vec::new() }
+    }
+
+    // Returns an empty builder, reusing a recycled one if there is any.
+    pub fn get(&mut self) -> MallocMessageBuilder {
+        match self.builders.pop() {
+            Some(builder) => builder,
+            None => MallocMessageBuilder::new(self.options)
+        }
+    }
+
+    // Clears `builder` and keeps it for a later `get()`. If the pool is
+    // already full, the builder is dropped instead.
+    pub fn recycle(&mut self, mut builder : MallocMessageBuilder) {
+        if self.builders.len() < self.max_pooled {
+            builder.clear();
+            self.builders.push(builder);
+        }
+    }
+
+    // The number of idle builders in the pool.
+    pub fn len(&self) -> uint {
+        self.builders.len()
+    }
+}
+
 
 pub struct ScratchSpaceMallocMessageBuilder<'a> {
     arena : Box<BuilderArena>,
@@ -0,0 +1,64 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+// Orphans are objects that live in a message but are not reachable from
+// the root. They can be created through an Orphanage, or by disowning a
+// field, and are later adopted into some other pointer field of the same
+// message. An orphan that is never adopted is zeroed out when dropped.
+
+use arena::BuilderArena;
+use error::Result;
+use layout::{OrphanBuilder, HasStructSize};
+
+pub trait FromOrphanBuilder<'a> {
+    fn init_orphan(orphanage : &Orphanage<'a>, size : uint) -> OrphanBuilder<'a>;
+    fn get_from_orphan(builder : &mut OrphanBuilder<'a>) -> Result<Self>;
+}
+
+pub struct Orphan<'a, T> {
+    builder : OrphanBuilder<'a>
+}
+
+impl <'a, T : FromOrphanBuilder<'a>> Orphan<'a, T> {
+    pub fn new(builder : OrphanBuilder<'a>) -> Orphan<'a, T> {
+        Orphan { builder : builder }
+    }
+
+    pub fn get(&mut self) -> Result<T> {
+        FromOrphanBuilder::get_from_orphan(&mut self.builder)
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.builder.is_null()
+    }
+
+    pub fn into_builder(self) -> OrphanBuilder<'a> {
+        self.builder
+    }
+}
+
+pub struct Orphanage<'a> {
+    arena : *mut BuilderArena
+}
+
+impl <'a> Orphanage<'a> {
+    pub fn new(arena : *mut BuilderArena) -> Orphanage<'a> {
+        Orphanage { arena : arena }
+    }
+
+    pub fn arena(&self) -> *mut BuilderArena {
+        self.arena
+    }
+
+    pub fn new_struct_orphan<T : FromOrphanBuilder<'a> + HasStructSize>(&self) -> Orphan<'a, T> {
+        Orphan::new(OrphanBuilder::init_struct(self.arena, HasStructSize::struct_size(None::<T>)))
+    }
+
+    // `size` is the element count for lists and the byte count for text and data.
+    pub fn new_orphan<T : FromOrphanBuilder<'a>>(&self, size : uint) -> Orphan<'a, T> {
+        Orphan::new(FromOrphanBuilder::init_orphan(self, size))
+    }
+}
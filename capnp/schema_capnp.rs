@@ -1,18 +1,32 @@
+// This file is maintained by hand. It started out as capnpc-rust output
+// for schema.capnp, adapted to live inside the capnp crate, and nothing
+// regenerates it. It lacks the items that capnpc-rust now emits: schema()
+// and ENCODED_NODE, the orphan accessors, equals() and stable_hash(), and
+// the InitPointerBuilder impls.
+//
+// To update it, run capnpc-rust over the schema.capnp of the matching
+// Cap'n Proto release, and in the output replace each module's
+// `use capnp::...` lines with the crate-relative imports that the modules
+// below use (`use layout;`, `use error::Result;` and so on). The generated
+// code refers to the capnp crate only through those imports. Then check
+// that capnpc-rust and capnp/dynamic.rs still build against it.
+
 #![allow(unused_imports)]
 #![allow(dead_code)]
 
 pub mod Node {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
-    layout::StructSize { data : 5, pointers : 5, preferred_list_encoding : layout::InlineComposite};
+    layout::StructSize { data : 5, pointers : 6, preferred_list_encoding : layout::InlineComposite};
 
 
   pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -33,7 +47,7 @@ pub mod Node {
       self.reader.get_data_field::<u64>(0)
     }
     #[inline]
-    pub fn get_display_name(&self) -> Text::Reader<'a> {
+    pub fn get_display_name(&self) -> Result<Text::Reader<'a>> {
       self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     pub fn has_display_name(&self) -> bool {
@@ -48,15 +62,26 @@ pub mod Node {
       self.reader.get_data_field::<u64>(2)
     }
     #[inline]
-    pub fn get_nested_nodes(&self) -> StructList::Reader<'a,schema_capnp::Node::NestedNode::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(1).get_list(schema_capnp::Node::NestedNode::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_parameters(&self) -> Result<StructList::Reader<'a,schema_capnp::Node::Parameter::Reader<'a>>> {
+      self.reader.get_pointer_field(5).get_list(schema_capnp::Node::Parameter::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
+    }
+    pub fn has_parameters(&self) -> bool {
+      !self.reader.get_pointer_field(5).is_null()
+    }
+    #[inline]
+    pub fn get_is_generic(&self) -> bool {
+      self.reader.get_bool_field(288)
+    }
+    #[inline]
+    pub fn get_nested_nodes(&self) -> Result<StructList::Reader<'a,schema_capnp::Node::NestedNode::Reader<'a>>> {
+      self.reader.get_pointer_field(1).get_list(schema_capnp::Node::NestedNode::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_nested_nodes(&self) -> bool {
       !self.reader.get_pointer_field(1).is_null()
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(2).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_annotations(&self) -> Result<StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>> {
+      self.reader.get_pointer_field(2).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_annotations(&self) -> bool {
       !self.reader.get_pointer_field(2).is_null()
@@ -122,7 +147,7 @@ pub mod Node {
       self.builder.set_data_field::<u64>(0, value);
     }
     #[inline]
-    pub fn get_display_name(&self) -> Text::Builder<'a> {
+    pub fn get_display_name(&self) -> Result<Text::Builder<'a>> {
       self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     #[inline]
@@ -153,11 +178,35 @@ pub mod Node {
       self.builder.set_data_field::<u64>(2, value);
     }
     #[inline]
-    pub fn get_nested_nodes(&self) -> StructList::Builder<'a,schema_capnp::Node::NestedNode::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Node::NestedNode::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_parameters(&self) -> Result<StructList::Builder<'a,schema_capnp::Node::Parameter::Builder<'a>>> {
+      self.builder.get_pointer_field(5).get_struct_list(schema_capnp::Node::Parameter::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
+    }
+    #[inline]
+    pub fn set_parameters(&self, value : StructList::Reader<'a,schema_capnp::Node::Parameter::Reader<'a>>) -> Result<()> {
+      self.builder.get_pointer_field(5).set_list(&value.reader)
+    }
+    #[inline]
+    pub fn init_parameters(&self, size : uint) -> StructList::Builder<'a,schema_capnp::Node::Parameter::Builder<'a>> {
+      StructList::Builder::<'a, schema_capnp::Node::Parameter::Builder<'a>>::new(
+        self.builder.get_pointer_field(5).init_struct_list(size, schema_capnp::Node::Parameter::STRUCT_SIZE))
+    }
+    pub fn has_parameters(&self) -> bool {
+      !self.builder.get_pointer_field(5).is_null()
+    }
+    #[inline]
+    pub fn get_is_generic(&self) -> bool {
+      self.builder.get_bool_field(288)
+    }
+    #[inline]
+    pub fn set_is_generic(&self, value : bool) {
+      self.builder.set_bool_field(288, value);
+    }
+    #[inline]
+    pub fn get_nested_nodes(&self) -> Result<StructList::Builder<'a,schema_capnp::Node::NestedNode::Builder<'a>>> {
+      self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Node::NestedNode::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_nested_nodes(&self, value : StructList::Reader<'a,schema_capnp::Node::NestedNode::Reader<'a>>) {
+    pub fn set_nested_nodes(&self, value : StructList::Reader<'a,schema_capnp::Node::NestedNode::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(1).set_list(&value.reader)
     }
     #[inline]
@@ -169,11 +218,11 @@ pub mod Node {
       !self.builder.get_pointer_field(1).is_null()
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(2).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_annotations(&self) -> Result<StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>>> {
+      self.builder.get_pointer_field(2).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) {
+    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(2).set_list(&value.reader)
     }
     #[inline]
@@ -295,14 +344,93 @@ pub mod Node {
   pub type WhichReader<'a> = Which<'a,schema_capnp::Node::Struct::Reader<'a>,schema_capnp::Node::Enum::Reader<'a>,schema_capnp::Node::Interface::Reader<'a>,schema_capnp::Node::Const::Reader<'a>,schema_capnp::Node::Annotation::Reader<'a>>;
   pub type WhichBuilder<'a> = Which<'a,schema_capnp::Node::Struct::Builder<'a>,schema_capnp::Node::Enum::Builder<'a>,schema_capnp::Node::Interface::Builder<'a>,schema_capnp::Node::Const::Builder<'a>,schema_capnp::Node::Annotation::Builder<'a>>;
 
+  pub mod Parameter {
+    use std;
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
+    use schema_capnp;
+
+    pub static STRUCT_SIZE : layout::StructSize =
+      layout::StructSize { data : 0, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+
+
+    pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+    impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+      fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+        Reader { reader : reader }
+      }
+    }
+
+    impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+      fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+    }
+
+    impl <'a> Reader<'a> {
+      #[inline]
+      pub fn get_name(&self) -> Result<Text::Reader<'a>> {
+        self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
+      }
+      pub fn has_name(&self) -> bool {
+        !self.reader.get_pointer_field(0).is_null()
+      }
+    }
+
+    pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+    impl <'a> layout::HasStructSize for Builder<'a> {
+      #[inline]
+      fn struct_size(_unused_self : Option<Builder>) -> layout::StructSize { STRUCT_SIZE }
+    }
+    impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+      fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+        Builder { builder : builder }
+      }
+    }
+    impl <'a> Builder<'a> {
+      pub fn as_reader(&self) -> Reader<'a> {
+        FromStructReader::new(self.builder.as_reader())
+      }
+      #[inline]
+      pub fn get_name(&self) -> Result<Text::Builder<'a>> {
+        self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
+      }
+      #[inline]
+      pub fn set_name(&self, value : Text::Reader) {
+        self.builder.get_pointer_field(0).set_text(value);
+      }
+      #[inline]
+      pub fn init_name(&self, size : uint) -> Text::Builder<'a> {
+        self.builder.get_pointer_field(0).init_text(size)
+      }
+      pub fn has_name(&self) -> bool {
+        !self.builder.get_pointer_field(0).is_null()
+      }
+    }
+
+    pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+    impl FromTypelessPipeline for Pipeline {
+      fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+        Pipeline { _typeless : typeless }
+      }
+    }
+    impl Pipeline {
+    }
+  }
+
   pub mod NestedNode {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub static STRUCT_SIZE : layout::StructSize =
@@ -323,7 +451,7 @@ pub mod Node {
 
     impl <'a> Reader<'a> {
       #[inline]
-      pub fn get_name(&self) -> Text::Reader<'a> {
+      pub fn get_name(&self) -> Result<Text::Reader<'a>> {
         self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
       }
       pub fn has_name(&self) -> bool {
@@ -350,7 +478,7 @@ pub mod Node {
         FromStructReader::new(self.builder.as_reader())
       }
       #[inline]
-      pub fn get_name(&self) -> Text::Builder<'a> {
+      pub fn get_name(&self) -> Result<Text::Builder<'a>> {
         self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
       }
       #[inline]
@@ -386,12 +514,13 @@ pub mod Node {
 
   pub mod Struct {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -432,8 +561,8 @@ pub mod Node {
         self.reader.get_data_field::<u32>(8)
       }
       #[inline]
-      pub fn get_fields(&self) -> StructList::Reader<'a,schema_capnp::Field::Reader<'a>> {
-        StructList::Reader::new(self.reader.get_pointer_field(3).get_list(schema_capnp::Field::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+      pub fn get_fields(&self) -> Result<StructList::Reader<'a,schema_capnp::Field::Reader<'a>>> {
+        self.reader.get_pointer_field(3).get_list(schema_capnp::Field::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
       }
       pub fn has_fields(&self) -> bool {
         !self.reader.get_pointer_field(3).is_null()
@@ -499,11 +628,11 @@ pub mod Node {
         self.builder.set_data_field::<u32>(8, value);
       }
       #[inline]
-      pub fn get_fields(&self) -> StructList::Builder<'a,schema_capnp::Field::Builder<'a>> {
-        StructList::Builder::new(self.builder.get_pointer_field(3).get_struct_list(schema_capnp::Field::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_fields(&self) -> Result<StructList::Builder<'a,schema_capnp::Field::Builder<'a>>> {
+        self.builder.get_pointer_field(3).get_struct_list(schema_capnp::Field::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
       }
       #[inline]
-      pub fn set_fields(&self, value : StructList::Reader<'a,schema_capnp::Field::Reader<'a>>) {
+      pub fn set_fields(&self, value : StructList::Reader<'a,schema_capnp::Field::Reader<'a>>) -> Result<()> {
         self.builder.get_pointer_field(3).set_list(&value.reader)
       }
       #[inline]
@@ -528,12 +657,13 @@ pub mod Node {
 
   pub mod Enum {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -550,8 +680,8 @@ pub mod Node {
 
     impl <'a> Reader<'a> {
       #[inline]
-      pub fn get_enumerants(&self) -> StructList::Reader<'a,schema_capnp::Enumerant::Reader<'a>> {
-        StructList::Reader::new(self.reader.get_pointer_field(3).get_list(schema_capnp::Enumerant::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+      pub fn get_enumerants(&self) -> Result<StructList::Reader<'a,schema_capnp::Enumerant::Reader<'a>>> {
+        self.reader.get_pointer_field(3).get_list(schema_capnp::Enumerant::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
       }
       pub fn has_enumerants(&self) -> bool {
         !self.reader.get_pointer_field(3).is_null()
@@ -569,11 +699,11 @@ pub mod Node {
         FromStructReader::new(self.builder.as_reader())
       }
       #[inline]
-      pub fn get_enumerants(&self) -> StructList::Builder<'a,schema_capnp::Enumerant::Builder<'a>> {
-        StructList::Builder::new(self.builder.get_pointer_field(3).get_struct_list(schema_capnp::Enumerant::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_enumerants(&self) -> Result<StructList::Builder<'a,schema_capnp::Enumerant::Builder<'a>>> {
+        self.builder.get_pointer_field(3).get_struct_list(schema_capnp::Enumerant::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
       }
       #[inline]
-      pub fn set_enumerants(&self, value : StructList::Reader<'a,schema_capnp::Enumerant::Reader<'a>>) {
+      pub fn set_enumerants(&self, value : StructList::Reader<'a,schema_capnp::Enumerant::Reader<'a>>) -> Result<()> {
         self.builder.get_pointer_field(3).set_list(&value.reader)
       }
       #[inline]
@@ -598,12 +728,13 @@ pub mod Node {
 
   pub mod Interface {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -620,17 +751,17 @@ pub mod Node {
 
     impl <'a> Reader<'a> {
       #[inline]
-      pub fn get_methods(&self) -> StructList::Reader<'a,schema_capnp::Method::Reader<'a>> {
-        StructList::Reader::new(self.reader.get_pointer_field(3).get_list(schema_capnp::Method::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+      pub fn get_methods(&self) -> Result<StructList::Reader<'a,schema_capnp::Method::Reader<'a>>> {
+        self.reader.get_pointer_field(3).get_list(schema_capnp::Method::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
       }
       pub fn has_methods(&self) -> bool {
         !self.reader.get_pointer_field(3).is_null()
       }
       #[inline]
-      pub fn get_extends(&self) -> PrimitiveList::Reader<'a,u64> {
-        PrimitiveList::Reader::new(self.reader.get_pointer_field(4).get_list(layout::EightBytes, std::ptr::null()))
+      pub fn get_superclasses(&self) -> Result<StructList::Reader<'a,schema_capnp::Superclass::Reader<'a>>> {
+        self.reader.get_pointer_field(4).get_list(schema_capnp::Superclass::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
       }
-      pub fn has_extends(&self) -> bool {
+      pub fn has_superclasses(&self) -> bool {
         !self.reader.get_pointer_field(4).is_null()
       }
     }
@@ -646,11 +777,11 @@ pub mod Node {
         FromStructReader::new(self.builder.as_reader())
       }
       #[inline]
-      pub fn get_methods(&self) -> StructList::Builder<'a,schema_capnp::Method::Builder<'a>> {
-        StructList::Builder::new(self.builder.get_pointer_field(3).get_struct_list(schema_capnp::Method::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_methods(&self) -> Result<StructList::Builder<'a,schema_capnp::Method::Builder<'a>>> {
+        self.builder.get_pointer_field(3).get_struct_list(schema_capnp::Method::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
       }
       #[inline]
-      pub fn set_methods(&self, value : StructList::Reader<'a,schema_capnp::Method::Reader<'a>>) {
+      pub fn set_methods(&self, value : StructList::Reader<'a,schema_capnp::Method::Reader<'a>>) -> Result<()> {
         self.builder.get_pointer_field(3).set_list(&value.reader)
       }
       #[inline]
@@ -662,20 +793,19 @@ pub mod Node {
         !self.builder.get_pointer_field(3).is_null()
       }
       #[inline]
-      pub fn get_extends(&self) -> PrimitiveList::Builder<'a,u64> {
-        PrimitiveList::Builder::new(self.builder.get_pointer_field(4).get_list(layout::EightBytes, std::ptr::null()))
+      pub fn get_superclasses(&self) -> Result<StructList::Builder<'a,schema_capnp::Superclass::Builder<'a>>> {
+        self.builder.get_pointer_field(4).get_struct_list(schema_capnp::Superclass::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
       }
       #[inline]
-      pub fn set_extends(&self, value : PrimitiveList::Reader<'a,u64>) {
+      pub fn set_superclasses(&self, value : StructList::Reader<'a,schema_capnp::Superclass::Reader<'a>>) -> Result<()> {
         self.builder.get_pointer_field(4).set_list(&value.reader)
       }
       #[inline]
-      pub fn init_extends(&self, size : uint) -> PrimitiveList::Builder<'a,u64> {
-        PrimitiveList::Builder::<'a,u64>::new(
-          self.builder.get_pointer_field(4).init_list(layout::EightBytes,size)
-        )
+      pub fn init_superclasses(&self, size : uint) -> StructList::Builder<'a,schema_capnp::Superclass::Builder<'a>> {
+        StructList::Builder::<'a, schema_capnp::Superclass::Builder<'a>>::new(
+          self.builder.get_pointer_field(4).init_struct_list(size, schema_capnp::Superclass::STRUCT_SIZE))
       }
-      pub fn has_extends(&self) -> bool {
+      pub fn has_superclasses(&self) -> bool {
         !self.builder.get_pointer_field(4).is_null()
       }
     }
@@ -692,12 +822,13 @@ pub mod Node {
 
   pub mod Const {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -714,15 +845,15 @@ pub mod Node {
 
     impl <'a> Reader<'a> {
       #[inline]
-      pub fn get_type(&self) -> schema_capnp::Type::Reader<'a> {
-        FromStructReader::new(self.reader.get_pointer_field(3).get_struct( std::ptr::null()))
+      pub fn get_type(&self) -> Result<schema_capnp::Type::Reader<'a>> {
+        self.reader.get_pointer_field(3).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
       }
       pub fn has_type(&self) -> bool {
         !self.reader.get_pointer_field(3).is_null()
       }
       #[inline]
-      pub fn get_value(&self) -> schema_capnp::Value::Reader<'a> {
-        FromStructReader::new(self.reader.get_pointer_field(4).get_struct( std::ptr::null()))
+      pub fn get_value(&self) -> Result<schema_capnp::Value::Reader<'a>> {
+        self.reader.get_pointer_field(4).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
       }
       pub fn has_value(&self) -> bool {
         !self.reader.get_pointer_field(4).is_null()
@@ -740,11 +871,11 @@ pub mod Node {
         FromStructReader::new(self.builder.as_reader())
       }
       #[inline]
-      pub fn get_type(&self) -> schema_capnp::Type::Builder<'a> {
-        FromStructBuilder::new(self.builder.get_pointer_field(3).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_type(&self) -> Result<schema_capnp::Type::Builder<'a>> {
+        self.builder.get_pointer_field(3).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
       }
       #[inline]
-      pub fn set_type(&self, value : schema_capnp::Type::Reader) {
+      pub fn set_type(&self, value : schema_capnp::Type::Reader) -> Result<()> {
         self.builder.get_pointer_field(3).set_struct(&value.struct_reader())
       }
       #[inline]
@@ -755,11 +886,11 @@ pub mod Node {
         !self.builder.get_pointer_field(3).is_null()
       }
       #[inline]
-      pub fn get_value(&self) -> schema_capnp::Value::Builder<'a> {
-        FromStructBuilder::new(self.builder.get_pointer_field(4).get_struct(schema_capnp::Value::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_value(&self) -> Result<schema_capnp::Value::Builder<'a>> {
+        self.builder.get_pointer_field(4).get_struct(schema_capnp::Value::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
       }
       #[inline]
-      pub fn set_value(&self, value : schema_capnp::Value::Reader) {
+      pub fn set_value(&self, value : schema_capnp::Value::Reader) -> Result<()> {
         self.builder.get_pointer_field(4).set_struct(&value.struct_reader())
       }
       #[inline]
@@ -789,12 +920,13 @@ pub mod Node {
 
   pub mod Annotation {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -811,8 +943,8 @@ pub mod Node {
 
     impl <'a> Reader<'a> {
       #[inline]
-      pub fn get_type(&self) -> schema_capnp::Type::Reader<'a> {
-        FromStructReader::new(self.reader.get_pointer_field(3).get_struct( std::ptr::null()))
+      pub fn get_type(&self) -> Result<schema_capnp::Type::Reader<'a>> {
+        self.reader.get_pointer_field(3).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
       }
       pub fn has_type(&self) -> bool {
         !self.reader.get_pointer_field(3).is_null()
@@ -878,11 +1010,11 @@ pub mod Node {
         FromStructReader::new(self.builder.as_reader())
       }
       #[inline]
-      pub fn get_type(&self) -> schema_capnp::Type::Builder<'a> {
-        FromStructBuilder::new(self.builder.get_pointer_field(3).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_type(&self) -> Result<schema_capnp::Type::Builder<'a>> {
+        self.builder.get_pointer_field(3).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
       }
       #[inline]
-      pub fn set_type(&self, value : schema_capnp::Type::Reader) {
+      pub fn set_type(&self, value : schema_capnp::Type::Reader) -> Result<()> {
         self.builder.get_pointer_field(3).set_struct(&value.struct_reader())
       }
       #[inline]
@@ -1006,12 +1138,13 @@ pub mod Node {
 
 pub mod Field {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
@@ -1032,7 +1165,7 @@ pub mod Field {
 
   impl <'a> Reader<'a> {
     #[inline]
-    pub fn get_name(&self) -> Text::Reader<'a> {
+    pub fn get_name(&self) -> Result<Text::Reader<'a>> {
       self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     pub fn has_name(&self) -> bool {
@@ -1043,8 +1176,8 @@ pub mod Field {
       self.reader.get_data_field::<u16>(0)
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(1).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_annotations(&self) -> Result<StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>> {
+      self.reader.get_pointer_field(1).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_annotations(&self) -> bool {
       !self.reader.get_pointer_field(1).is_null()
@@ -1090,7 +1223,7 @@ pub mod Field {
       FromStructReader::new(self.builder.as_reader())
     }
     #[inline]
-    pub fn get_name(&self) -> Text::Builder<'a> {
+    pub fn get_name(&self) -> Result<Text::Builder<'a>> {
       self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     #[inline]
@@ -1113,11 +1246,11 @@ pub mod Field {
       self.builder.set_data_field::<u16>(0, value);
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_annotations(&self) -> Result<StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>>> {
+      self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) {
+    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(1).set_list(&value.reader)
     }
     #[inline]
@@ -1200,12 +1333,13 @@ pub mod Field {
 
   pub mod Slot {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -1226,15 +1360,15 @@ pub mod Field {
         self.reader.get_data_field::<u32>(1)
       }
       #[inline]
-      pub fn get_type(&self) -> schema_capnp::Type::Reader<'a> {
-        FromStructReader::new(self.reader.get_pointer_field(2).get_struct( std::ptr::null()))
+      pub fn get_type(&self) -> Result<schema_capnp::Type::Reader<'a>> {
+        self.reader.get_pointer_field(2).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
       }
       pub fn has_type(&self) -> bool {
         !self.reader.get_pointer_field(2).is_null()
       }
       #[inline]
-      pub fn get_default_value(&self) -> schema_capnp::Value::Reader<'a> {
-        FromStructReader::new(self.reader.get_pointer_field(3).get_struct( std::ptr::null()))
+      pub fn get_default_value(&self) -> Result<schema_capnp::Value::Reader<'a>> {
+        self.reader.get_pointer_field(3).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
       }
       pub fn has_default_value(&self) -> bool {
         !self.reader.get_pointer_field(3).is_null()
@@ -1264,11 +1398,11 @@ pub mod Field {
         self.builder.set_data_field::<u32>(1, value);
       }
       #[inline]
-      pub fn get_type(&self) -> schema_capnp::Type::Builder<'a> {
-        FromStructBuilder::new(self.builder.get_pointer_field(2).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_type(&self) -> Result<schema_capnp::Type::Builder<'a>> {
+        self.builder.get_pointer_field(2).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
       }
       #[inline]
-      pub fn set_type(&self, value : schema_capnp::Type::Reader) {
+      pub fn set_type(&self, value : schema_capnp::Type::Reader) -> Result<()> {
         self.builder.get_pointer_field(2).set_struct(&value.struct_reader())
       }
       #[inline]
@@ -1279,11 +1413,11 @@ pub mod Field {
         !self.builder.get_pointer_field(2).is_null()
       }
       #[inline]
-      pub fn get_default_value(&self) -> schema_capnp::Value::Builder<'a> {
-        FromStructBuilder::new(self.builder.get_pointer_field(3).get_struct(schema_capnp::Value::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_default_value(&self) -> Result<schema_capnp::Value::Builder<'a>> {
+        self.builder.get_pointer_field(3).get_struct(schema_capnp::Value::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
       }
       #[inline]
-      pub fn set_default_value(&self, value : schema_capnp::Value::Reader) {
+      pub fn set_default_value(&self, value : schema_capnp::Value::Reader) -> Result<()> {
         self.builder.get_pointer_field(3).set_struct(&value.struct_reader())
       }
       #[inline]
@@ -1321,12 +1455,13 @@ pub mod Field {
 
   pub mod Group {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -1380,12 +1515,13 @@ pub mod Field {
 
   pub mod Ordinal {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -1475,12 +1611,13 @@ pub mod Field {
 
 pub mod Enumerant {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
@@ -1501,7 +1638,7 @@ pub mod Enumerant {
 
   impl <'a> Reader<'a> {
     #[inline]
-    pub fn get_name(&self) -> Text::Reader<'a> {
+    pub fn get_name(&self) -> Result<Text::Reader<'a>> {
       self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     pub fn has_name(&self) -> bool {
@@ -1512,8 +1649,8 @@ pub mod Enumerant {
       self.reader.get_data_field::<u16>(0)
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(1).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_annotations(&self) -> Result<StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>> {
+      self.reader.get_pointer_field(1).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_annotations(&self) -> bool {
       !self.reader.get_pointer_field(1).is_null()
@@ -1535,7 +1672,7 @@ pub mod Enumerant {
       FromStructReader::new(self.builder.as_reader())
     }
     #[inline]
-    pub fn get_name(&self) -> Text::Builder<'a> {
+    pub fn get_name(&self) -> Result<Text::Builder<'a>> {
       self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     #[inline]
@@ -1558,11 +1695,11 @@ pub mod Enumerant {
       self.builder.set_data_field::<u16>(0, value);
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_annotations(&self) -> Result<StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>>> {
+      self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) {
+    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(1).set_list(&value.reader)
     }
     #[inline]
@@ -1587,16 +1724,17 @@ pub mod Enumerant {
 
 pub mod Method {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
-    layout::StructSize { data : 3, pointers : 2, preferred_list_encoding : layout::InlineComposite};
+    layout::StructSize { data : 3, pointers : 5, preferred_list_encoding : layout::InlineComposite};
 
 
   pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -1613,7 +1751,7 @@ pub mod Method {
 
   impl <'a> Reader<'a> {
     #[inline]
-    pub fn get_name(&self) -> Text::Reader<'a> {
+    pub fn get_name(&self) -> Result<Text::Reader<'a>> {
       self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     pub fn has_name(&self) -> bool {
@@ -1624,16 +1762,37 @@ pub mod Method {
       self.reader.get_data_field::<u16>(0)
     }
     #[inline]
+    pub fn get_implicit_parameters(&self) -> Result<StructList::Reader<'a,schema_capnp::Node::Parameter::Reader<'a>>> {
+      self.reader.get_pointer_field(4).get_list(schema_capnp::Node::Parameter::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
+    }
+    pub fn has_implicit_parameters(&self) -> bool {
+      !self.reader.get_pointer_field(4).is_null()
+    }
+    #[inline]
     pub fn get_param_struct_type(&self) -> u64 {
       self.reader.get_data_field::<u64>(1)
     }
     #[inline]
+    pub fn get_param_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+      self.reader.get_pointer_field(2).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+    }
+    pub fn has_param_brand(&self) -> bool {
+      !self.reader.get_pointer_field(2).is_null()
+    }
+    #[inline]
     pub fn get_result_struct_type(&self) -> u64 {
       self.reader.get_data_field::<u64>(2)
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(1).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_result_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+      self.reader.get_pointer_field(3).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+    }
+    pub fn has_result_brand(&self) -> bool {
+      !self.reader.get_pointer_field(3).is_null()
+    }
+    #[inline]
+    pub fn get_annotations(&self) -> Result<StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>> {
+      self.reader.get_pointer_field(1).get_list(schema_capnp::Annotation::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_annotations(&self) -> bool {
       !self.reader.get_pointer_field(1).is_null()
@@ -1655,7 +1814,7 @@ pub mod Method {
       FromStructReader::new(self.builder.as_reader())
     }
     #[inline]
-    pub fn get_name(&self) -> Text::Builder<'a> {
+    pub fn get_name(&self) -> Result<Text::Builder<'a>> {
       self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
     }
     #[inline]
@@ -1678,6 +1837,22 @@ pub mod Method {
       self.builder.set_data_field::<u16>(0, value);
     }
     #[inline]
+    pub fn get_implicit_parameters(&self) -> Result<StructList::Builder<'a,schema_capnp::Node::Parameter::Builder<'a>>> {
+      self.builder.get_pointer_field(4).get_struct_list(schema_capnp::Node::Parameter::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
+    }
+    #[inline]
+    pub fn set_implicit_parameters(&self, value : StructList::Reader<'a,schema_capnp::Node::Parameter::Reader<'a>>) -> Result<()> {
+      self.builder.get_pointer_field(4).set_list(&value.reader)
+    }
+    #[inline]
+    pub fn init_implicit_parameters(&self, size : uint) -> StructList::Builder<'a,schema_capnp::Node::Parameter::Builder<'a>> {
+      StructList::Builder::<'a, schema_capnp::Node::Parameter::Builder<'a>>::new(
+        self.builder.get_pointer_field(4).init_struct_list(size, schema_capnp::Node::Parameter::STRUCT_SIZE))
+    }
+    pub fn has_implicit_parameters(&self) -> bool {
+      !self.builder.get_pointer_field(4).is_null()
+    }
+    #[inline]
     pub fn get_param_struct_type(&self) -> u64 {
       self.builder.get_data_field::<u64>(1)
     }
@@ -1686,6 +1861,21 @@ pub mod Method {
       self.builder.set_data_field::<u64>(1, value);
     }
     #[inline]
+    pub fn get_param_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+      self.builder.get_pointer_field(2).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+    }
+    #[inline]
+    pub fn set_param_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+      self.builder.get_pointer_field(2).set_struct(&value.struct_reader())
+    }
+    #[inline]
+    pub fn init_param_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+      FromStructBuilder::new(self.builder.get_pointer_field(2).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+    }
+    pub fn has_param_brand(&self) -> bool {
+      !self.builder.get_pointer_field(2).is_null()
+    }
+    #[inline]
     pub fn get_result_struct_type(&self) -> u64 {
       self.builder.get_data_field::<u64>(2)
     }
@@ -1694,11 +1884,26 @@ pub mod Method {
       self.builder.set_data_field::<u64>(2, value);
     }
     #[inline]
-    pub fn get_annotations(&self) -> StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_result_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+      self.builder.get_pointer_field(3).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+    }
+    #[inline]
+    pub fn set_result_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+      self.builder.get_pointer_field(3).set_struct(&value.struct_reader())
+    }
+    #[inline]
+    pub fn init_result_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+      FromStructBuilder::new(self.builder.get_pointer_field(3).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+    }
+    pub fn has_result_brand(&self) -> bool {
+      !self.builder.get_pointer_field(3).is_null()
+    }
+    #[inline]
+    pub fn get_annotations(&self) -> Result<StructList::Builder<'a,schema_capnp::Annotation::Builder<'a>>> {
+      self.builder.get_pointer_field(1).get_struct_list(schema_capnp::Annotation::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) {
+    pub fn set_annotations(&self, value : StructList::Reader<'a,schema_capnp::Annotation::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(1).set_list(&value.reader)
     }
     #[inline]
@@ -1718,21 +1923,27 @@ pub mod Method {
     }
   }
   impl Pipeline {
+    pub fn get_param_brand(&self) -> schema_capnp::Brand::Pipeline {
+      FromTypelessPipeline::new(self._typeless.get_pointer_field(2))
+    }
+    pub fn get_result_brand(&self) -> schema_capnp::Brand::Pipeline {
+      FromTypelessPipeline::new(self._typeless.get_pointer_field(3))
+    }
   }
 }
 
 pub mod Type {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
-    layout::StructSize { data : 2, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+    layout::StructSize { data : 3, pointers : 1, preferred_list_encoding : layout::InlineComposite};
 
 
   pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -1843,7 +2054,7 @@ pub mod Type {
         }
         18 => {
           return std::option::Some(AnyPointer(
-            ()
+            FromStructReader::new(self.reader)
           ));
         }
         _ => return std::option::None
@@ -1931,23 +2142,30 @@ pub mod Type {
     pub fn init_enum(&self, ) -> schema_capnp::Type::Enum::Builder<'a> {
       self.builder.set_data_field::<u16>(0, 15);
       self.builder.set_data_field::<u64>(1, 0);
+      self.builder.get_pointer_field(0).clear();
       FromStructBuilder::new(self.builder)
     }
     #[inline]
     pub fn init_struct(&self, ) -> schema_capnp::Type::Struct::Builder<'a> {
       self.builder.set_data_field::<u16>(0, 16);
       self.builder.set_data_field::<u64>(1, 0);
+      self.builder.get_pointer_field(0).clear();
       FromStructBuilder::new(self.builder)
     }
     #[inline]
     pub fn init_interface(&self, ) -> schema_capnp::Type::Interface::Builder<'a> {
       self.builder.set_data_field::<u16>(0, 17);
       self.builder.set_data_field::<u64>(1, 0);
+      self.builder.get_pointer_field(0).clear();
       FromStructBuilder::new(self.builder)
     }
     #[inline]
-    pub fn set_any_pointer(&self, _value : ()) {
+    pub fn init_any_pointer(&self, ) -> schema_capnp::Type::AnyPointer::Builder<'a> {
       self.builder.set_data_field::<u16>(0, 18);
+      self.builder.set_data_field::<u16>(4, 0);
+      self.builder.set_data_field::<u64>(2, 0);
+      self.builder.set_data_field::<u16>(5, 0);
+      FromStructBuilder::new(self.builder)
     }
     #[inline]
     pub fn which(&self) -> std::option::Option<WhichBuilder<'a>> {
@@ -2044,7 +2262,7 @@ pub mod Type {
         }
         18 => {
           return std::option::Some(AnyPointer(
-            ()
+            FromStructBuilder::new(self.builder)
           ));
         }
         _ => return std::option::None
@@ -2052,15 +2270,15 @@ pub mod Type {
     }
   }
 
-  pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+  pub struct Pipeline { _typeless : ::any::AnyPointer::Pipeline }
   impl FromTypelessPipeline for Pipeline {
-    fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+    fn new(typeless : ::any::AnyPointer::Pipeline) -> Pipeline {
       Pipeline { _typeless : typeless }
     }
   }
   impl Pipeline {
   }
-  pub enum Which<'a,A0,A1,A2,A3> {
+  pub enum Which<'a,A0,A1,A2,A3,A4> {
     Void(()),
     Bool(()),
     Int8(()),
@@ -2079,19 +2297,20 @@ pub mod Type {
     Enum(A1),
     Struct(A2),
     Interface(A3),
-    AnyPointer(()),
+    AnyPointer(A4),
   }
-  pub type WhichReader<'a> = Which<'a,schema_capnp::Type::List::Reader<'a>,schema_capnp::Type::Enum::Reader<'a>,schema_capnp::Type::Struct::Reader<'a>,schema_capnp::Type::Interface::Reader<'a>>;
-  pub type WhichBuilder<'a> = Which<'a,schema_capnp::Type::List::Builder<'a>,schema_capnp::Type::Enum::Builder<'a>,schema_capnp::Type::Struct::Builder<'a>,schema_capnp::Type::Interface::Builder<'a>>;
+  pub type WhichReader<'a> = Which<'a,schema_capnp::Type::List::Reader<'a>,schema_capnp::Type::Enum::Reader<'a>,schema_capnp::Type::Struct::Reader<'a>,schema_capnp::Type::Interface::Reader<'a>,schema_capnp::Type::AnyPointer::Reader<'a>>;
+  pub type WhichBuilder<'a> = Which<'a,schema_capnp::Type::List::Builder<'a>,schema_capnp::Type::Enum::Builder<'a>,schema_capnp::Type::Struct::Builder<'a>,schema_capnp::Type::Interface::Builder<'a>,schema_capnp::Type::AnyPointer::Builder<'a>>;
 
   pub mod List {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -2108,8 +2327,8 @@ pub mod Type {
 
     impl <'a> Reader<'a> {
       #[inline]
-      pub fn get_element_type(&self) -> schema_capnp::Type::Reader<'a> {
-        FromStructReader::new(self.reader.get_pointer_field(0).get_struct( std::ptr::null()))
+      pub fn get_element_type(&self) -> Result<schema_capnp::Type::Reader<'a>> {
+        self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
       }
       pub fn has_element_type(&self) -> bool {
         !self.reader.get_pointer_field(0).is_null()
@@ -2127,11 +2346,11 @@ pub mod Type {
         FromStructReader::new(self.builder.as_reader())
       }
       #[inline]
-      pub fn get_element_type(&self) -> schema_capnp::Type::Builder<'a> {
-        FromStructBuilder::new(self.builder.get_pointer_field(0).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_element_type(&self) -> Result<schema_capnp::Type::Builder<'a>> {
+        self.builder.get_pointer_field(0).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
       }
       #[inline]
-      pub fn set_element_type(&self, value : schema_capnp::Type::Reader) {
+      pub fn set_element_type(&self, value : schema_capnp::Type::Reader) -> Result<()> {
         self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
       }
       #[inline]
@@ -2158,12 +2377,13 @@ pub mod Type {
 
   pub mod Enum {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -2183,6 +2403,13 @@ pub mod Type {
       pub fn get_type_id(&self) -> u64 {
         self.reader.get_data_field::<u64>(1)
       }
+      #[inline]
+      pub fn get_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+        self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+      }
+      pub fn has_brand(&self) -> bool {
+        !self.reader.get_pointer_field(0).is_null()
+      }
     }
 
     pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
@@ -2203,6 +2430,21 @@ pub mod Type {
       pub fn set_type_id(&self, value : u64) {
         self.builder.set_data_field::<u64>(1, value);
       }
+      #[inline]
+      pub fn get_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+        self.builder.get_pointer_field(0).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+      }
+      #[inline]
+      pub fn set_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+        self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
+      }
+      #[inline]
+      pub fn init_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+        FromStructBuilder::new(self.builder.get_pointer_field(0).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+      }
+      pub fn has_brand(&self) -> bool {
+        !self.builder.get_pointer_field(0).is_null()
+      }
     }
 
     pub struct Pipeline { _typeless : AnyPointer::Pipeline }
@@ -2212,17 +2454,21 @@ pub mod Type {
       }
     }
     impl Pipeline {
+      pub fn get_brand(&self) -> schema_capnp::Brand::Pipeline {
+        FromTypelessPipeline::new(self._typeless.get_pointer_field(0))
+      }
     }
   }
 
   pub mod Struct {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -2242,6 +2488,13 @@ pub mod Type {
       pub fn get_type_id(&self) -> u64 {
         self.reader.get_data_field::<u64>(1)
       }
+      #[inline]
+      pub fn get_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+        self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+      }
+      pub fn has_brand(&self) -> bool {
+        !self.reader.get_pointer_field(0).is_null()
+      }
     }
 
     pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
@@ -2262,6 +2515,21 @@ pub mod Type {
       pub fn set_type_id(&self, value : u64) {
         self.builder.set_data_field::<u64>(1, value);
       }
+      #[inline]
+      pub fn get_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+        self.builder.get_pointer_field(0).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+      }
+      #[inline]
+      pub fn set_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+        self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
+      }
+      #[inline]
+      pub fn init_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+        FromStructBuilder::new(self.builder.get_pointer_field(0).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+      }
+      pub fn has_brand(&self) -> bool {
+        !self.builder.get_pointer_field(0).is_null()
+      }
     }
 
     pub struct Pipeline { _typeless : AnyPointer::Pipeline }
@@ -2271,17 +2539,21 @@ pub mod Type {
       }
     }
     impl Pipeline {
+      pub fn get_brand(&self) -> schema_capnp::Brand::Pipeline {
+        FromTypelessPipeline::new(self._typeless.get_pointer_field(0))
+      }
     }
   }
 
   pub mod Interface {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -2301,6 +2573,13 @@ pub mod Type {
       pub fn get_type_id(&self) -> u64 {
         self.reader.get_data_field::<u64>(1)
       }
+      #[inline]
+      pub fn get_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+        self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+      }
+      pub fn has_brand(&self) -> bool {
+        !self.reader.get_pointer_field(0).is_null()
+      }
     }
 
     pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
@@ -2321,6 +2600,21 @@ pub mod Type {
       pub fn set_type_id(&self, value : u64) {
         self.builder.set_data_field::<u64>(1, value);
       }
+      #[inline]
+      pub fn get_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+        self.builder.get_pointer_field(0).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+      }
+      #[inline]
+      pub fn set_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+        self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
+      }
+      #[inline]
+      pub fn init_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+        FromStructBuilder::new(self.builder.get_pointer_field(0).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+      }
+      pub fn has_brand(&self) -> bool {
+        !self.builder.get_pointer_field(0).is_null()
+      }
     }
 
     pub struct Pipeline { _typeless : AnyPointer::Pipeline }
@@ -2330,46 +2624,839 @@ pub mod Type {
       }
     }
     impl Pipeline {
+      pub fn get_brand(&self) -> schema_capnp::Brand::Pipeline {
+        FromTypelessPipeline::new(self._typeless.get_pointer_field(0))
+      }
     }
   }
-}
-
-pub mod Value {
-  use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
-  use schema_capnp;
 
-  pub static STRUCT_SIZE : layout::StructSize =
-    layout::StructSize { data : 2, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+  pub mod AnyPointer {
+    use std;
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
+    use schema_capnp;
 
+    pub struct Reader<'a> { reader : layout::StructReader<'a> }
 
-  pub struct Reader<'a> { reader : layout::StructReader<'a> }
+    impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+      fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+        Reader { reader : reader }
+      }
+    }
 
-  impl <'a> layout::FromStructReader<'a> for Reader<'a> {
-    fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
-      Reader { reader : reader }
+    impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+      fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
     }
-  }
 
-  impl <'a> layout::ToStructReader<'a> for Reader<'a> {
-    fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
-  }
+    impl <'a> Reader<'a> {
+      #[inline]
+      pub fn which(&self) -> std::option::Option<WhichReader<'a>> {
+        match self.reader.get_data_field::<u16>(4) {
+          0 => {
+            return std::option::Some(Unconstrained(
+              FromStructReader::new(self.reader)
+            ));
+          }
+          1 => {
+            return std::option::Some(Parameter(
+              FromStructReader::new(self.reader)
+            ));
+          }
+          2 => {
+            return std::option::Some(ImplicitMethodParameter(
+              FromStructReader::new(self.reader)
+            ));
+          }
+          _ => return std::option::None
+        }
+      }
+    }
 
-  impl <'a> Reader<'a> {
-    pub fn has_text(&self) -> bool {
-      if self.reader.get_data_field::<u16>(0) != 12 { return false; }
-      !self.reader.get_pointer_field(0).is_null()
+    pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+    impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+      fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+        Builder { builder : builder }
+      }
     }
-    pub fn has_data(&self) -> bool {
-      if self.reader.get_data_field::<u16>(0) != 13 { return false; }
-      !self.reader.get_pointer_field(0).is_null()
+    impl <'a> Builder<'a> {
+      pub fn as_reader(&self) -> Reader<'a> {
+        FromStructReader::new(self.builder.as_reader())
+      }
+      #[inline]
+      pub fn init_unconstrained(&self, ) -> schema_capnp::Type::AnyPointer::Unconstrained::Builder<'a> {
+        self.builder.set_data_field::<u16>(4, 0);
+        self.builder.set_data_field::<u16>(5, 0);
+        FromStructBuilder::new(self.builder)
+      }
+      #[inline]
+      pub fn init_parameter(&self, ) -> schema_capnp::Type::AnyPointer::Parameter::Builder<'a> {
+        self.builder.set_data_field::<u16>(4, 1);
+        self.builder.set_data_field::<u64>(2, 0);
+        self.builder.set_data_field::<u16>(5, 0);
+        FromStructBuilder::new(self.builder)
+      }
+      #[inline]
+      pub fn init_implicit_method_parameter(&self, ) -> schema_capnp::Type::AnyPointer::ImplicitMethodParameter::Builder<'a> {
+        self.builder.set_data_field::<u16>(4, 2);
+        self.builder.set_data_field::<u16>(5, 0);
+        FromStructBuilder::new(self.builder)
+      }
+      #[inline]
+      pub fn which(&self) -> std::option::Option<WhichBuilder<'a>> {
+        match self.builder.get_data_field::<u16>(4) {
+          0 => {
+            return std::option::Some(Unconstrained(
+              FromStructBuilder::new(self.builder)
+            ));
+          }
+          1 => {
+            return std::option::Some(Parameter(
+              FromStructBuilder::new(self.builder)
+            ));
+          }
+          2 => {
+            return std::option::Some(ImplicitMethodParameter(
+              FromStructBuilder::new(self.builder)
+            ));
+          }
+          _ => return std::option::None
+        }
+      }
     }
-    pub fn has_list(&self) -> bool {
+
+    pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+    impl FromTypelessPipeline for Pipeline {
+      fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+        Pipeline { _typeless : typeless }
+      }
+    }
+    impl Pipeline {
+    }
+    pub enum Which<'a,A0,A1,A2> {
+      Unconstrained(A0),
+      Parameter(A1),
+      ImplicitMethodParameter(A2),
+    }
+    pub type WhichReader<'a> = Which<'a,schema_capnp::Type::AnyPointer::Unconstrained::Reader<'a>,schema_capnp::Type::AnyPointer::Parameter::Reader<'a>,schema_capnp::Type::AnyPointer::ImplicitMethodParameter::Reader<'a>>;
+    pub type WhichBuilder<'a> = Which<'a,schema_capnp::Type::AnyPointer::Unconstrained::Builder<'a>,schema_capnp::Type::AnyPointer::Parameter::Builder<'a>,schema_capnp::Type::AnyPointer::ImplicitMethodParameter::Builder<'a>>;
+
+    pub mod Unconstrained {
+      use std;
+      use any::AnyPointer;
+      use capability::{FromClientHook, FromTypelessPipeline};
+      use blob::{Text, Data};
+      use layout;
+      use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+      use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+      use error::Result;
+      use schema_capnp;
+
+      pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+      impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+        fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+          Reader { reader : reader }
+        }
+      }
+
+      impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+        fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+      }
+
+      impl <'a> Reader<'a> {
+        #[inline]
+        pub fn which(&self) -> std::option::Option<WhichReader> {
+          match self.reader.get_data_field::<u16>(5) {
+            0 => {
+              return std::option::Some(AnyKind(
+                ()
+              ));
+            }
+            1 => {
+              return std::option::Some(Struct(
+                ()
+              ));
+            }
+            2 => {
+              return std::option::Some(List(
+                ()
+              ));
+            }
+            3 => {
+              return std::option::Some(Capability(
+                ()
+              ));
+            }
+            _ => return std::option::None
+          }
+        }
+      }
+
+      pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+      impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+        fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+          Builder { builder : builder }
+        }
+      }
+      impl <'a> Builder<'a> {
+        pub fn as_reader(&self) -> Reader<'a> {
+          FromStructReader::new(self.builder.as_reader())
+        }
+        #[inline]
+        pub fn set_any_kind(&self, _value : ()) {
+          self.builder.set_data_field::<u16>(5, 0);
+        }
+        #[inline]
+        pub fn set_struct(&self, _value : ()) {
+          self.builder.set_data_field::<u16>(5, 1);
+        }
+        #[inline]
+        pub fn set_list(&self, _value : ()) {
+          self.builder.set_data_field::<u16>(5, 2);
+        }
+        #[inline]
+        pub fn set_capability(&self, _value : ()) {
+          self.builder.set_data_field::<u16>(5, 3);
+        }
+        #[inline]
+        pub fn which(&self) -> std::option::Option<WhichBuilder> {
+          match self.builder.get_data_field::<u16>(5) {
+            0 => {
+              return std::option::Some(AnyKind(
+                ()
+              ));
+            }
+            1 => {
+              return std::option::Some(Struct(
+                ()
+              ));
+            }
+            2 => {
+              return std::option::Some(List(
+                ()
+              ));
+            }
+            3 => {
+              return std::option::Some(Capability(
+                ()
+              ));
+            }
+            _ => return std::option::None
+          }
+        }
+      }
+
+      pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+      impl FromTypelessPipeline for Pipeline {
+        fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+          Pipeline { _typeless : typeless }
+        }
+      }
+      impl Pipeline {
+      }
+      pub enum Which {
+        AnyKind(()),
+        Struct(()),
+        List(()),
+        Capability(()),
+      }
+      pub type WhichReader = Which;
+      pub type WhichBuilder = Which;
+    }
+
+    pub mod Parameter {
+      use std;
+      use any::AnyPointer;
+      use capability::{FromClientHook, FromTypelessPipeline};
+      use blob::{Text, Data};
+      use layout;
+      use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+      use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+      use error::Result;
+      use schema_capnp;
+
+      pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+      impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+        fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+          Reader { reader : reader }
+        }
+      }
+
+      impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+        fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+      }
+
+      impl <'a> Reader<'a> {
+        #[inline]
+        pub fn get_scope_id(&self) -> u64 {
+          self.reader.get_data_field::<u64>(2)
+        }
+        #[inline]
+        pub fn get_parameter_index(&self) -> u16 {
+          self.reader.get_data_field::<u16>(5)
+        }
+      }
+
+      pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+      impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+        fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+          Builder { builder : builder }
+        }
+      }
+      impl <'a> Builder<'a> {
+        pub fn as_reader(&self) -> Reader<'a> {
+          FromStructReader::new(self.builder.as_reader())
+        }
+        #[inline]
+        pub fn get_scope_id(&self) -> u64 {
+          self.builder.get_data_field::<u64>(2)
+        }
+        #[inline]
+        pub fn set_scope_id(&self, value : u64) {
+          self.builder.set_data_field::<u64>(2, value);
+        }
+        #[inline]
+        pub fn get_parameter_index(&self) -> u16 {
+          self.builder.get_data_field::<u16>(5)
+        }
+        #[inline]
+        pub fn set_parameter_index(&self, value : u16) {
+          self.builder.set_data_field::<u16>(5, value);
+        }
+      }
+
+      pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+      impl FromTypelessPipeline for Pipeline {
+        fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+          Pipeline { _typeless : typeless }
+        }
+      }
+      impl Pipeline {
+      }
+    }
+
+    pub mod ImplicitMethodParameter {
+      use std;
+      use any::AnyPointer;
+      use capability::{FromClientHook, FromTypelessPipeline};
+      use blob::{Text, Data};
+      use layout;
+      use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+      use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+      use error::Result;
+      use schema_capnp;
+
+      pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+      impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+        fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+          Reader { reader : reader }
+        }
+      }
+
+      impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+        fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+      }
+
+      impl <'a> Reader<'a> {
+        #[inline]
+        pub fn get_parameter_index(&self) -> u16 {
+          self.reader.get_data_field::<u16>(5)
+        }
+      }
+
+      pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+      impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+        fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+          Builder { builder : builder }
+        }
+      }
+      impl <'a> Builder<'a> {
+        pub fn as_reader(&self) -> Reader<'a> {
+          FromStructReader::new(self.builder.as_reader())
+        }
+        #[inline]
+        pub fn get_parameter_index(&self) -> u16 {
+          self.builder.get_data_field::<u16>(5)
+        }
+        #[inline]
+        pub fn set_parameter_index(&self, value : u16) {
+          self.builder.set_data_field::<u16>(5, value);
+        }
+      }
+
+      pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+      impl FromTypelessPipeline for Pipeline {
+        fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+          Pipeline { _typeless : typeless }
+        }
+      }
+      impl Pipeline {
+      }
+    }
+  }
+}
+
+pub mod Brand {
+  use std;
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
+  use schema_capnp;
+
+  pub static STRUCT_SIZE : layout::StructSize =
+    layout::StructSize { data : 0, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+
+
+  pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+  impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+    fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+      Reader { reader : reader }
+    }
+  }
+
+  impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+    fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+  }
+
+  impl <'a> Reader<'a> {
+    #[inline]
+    pub fn get_scopes(&self) -> Result<StructList::Reader<'a,schema_capnp::Brand::Scope::Reader<'a>>> {
+      self.reader.get_pointer_field(0).get_list(schema_capnp::Brand::Scope::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
+    }
+    pub fn has_scopes(&self) -> bool {
+      !self.reader.get_pointer_field(0).is_null()
+    }
+  }
+
+  pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+  impl <'a> layout::HasStructSize for Builder<'a> {
+    #[inline]
+    fn struct_size(_unused_self : Option<Builder>) -> layout::StructSize { STRUCT_SIZE }
+  }
+  impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+    fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+      Builder { builder : builder }
+    }
+  }
+  impl <'a> Builder<'a> {
+    pub fn as_reader(&self) -> Reader<'a> {
+      FromStructReader::new(self.builder.as_reader())
+    }
+    #[inline]
+    pub fn get_scopes(&self) -> Result<StructList::Builder<'a,schema_capnp::Brand::Scope::Builder<'a>>> {
+      self.builder.get_pointer_field(0).get_struct_list(schema_capnp::Brand::Scope::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
+    }
+    #[inline]
+    pub fn set_scopes(&self, value : StructList::Reader<'a,schema_capnp::Brand::Scope::Reader<'a>>) -> Result<()> {
+      self.builder.get_pointer_field(0).set_list(&value.reader)
+    }
+    #[inline]
+    pub fn init_scopes(&self, size : uint) -> StructList::Builder<'a,schema_capnp::Brand::Scope::Builder<'a>> {
+      StructList::Builder::<'a, schema_capnp::Brand::Scope::Builder<'a>>::new(
+        self.builder.get_pointer_field(0).init_struct_list(size, schema_capnp::Brand::Scope::STRUCT_SIZE))
+    }
+    pub fn has_scopes(&self) -> bool {
+      !self.builder.get_pointer_field(0).is_null()
+    }
+  }
+
+  pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+  impl FromTypelessPipeline for Pipeline {
+    fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+      Pipeline { _typeless : typeless }
+    }
+  }
+  impl Pipeline {
+  }
+
+  pub mod Scope {
+    use std;
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
+    use schema_capnp;
+
+    pub static STRUCT_SIZE : layout::StructSize =
+      layout::StructSize { data : 2, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+
+
+    pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+    impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+      fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+        Reader { reader : reader }
+      }
+    }
+
+    impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+      fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+    }
+
+    impl <'a> Reader<'a> {
+      #[inline]
+      pub fn get_scope_id(&self) -> u64 {
+        self.reader.get_data_field::<u64>(0)
+      }
+      pub fn has_bind(&self) -> bool {
+        if self.reader.get_data_field::<u16>(4) != 0 { return false; }
+        !self.reader.get_pointer_field(0).is_null()
+      }
+      #[inline]
+      pub fn which(&self) -> std::option::Option<WhichReader<'a>> {
+        match self.reader.get_data_field::<u16>(4) {
+          0 => {
+            return std::option::Some(Bind(
+              self.reader.get_pointer_field(0).get_list(schema_capnp::Brand::Binding::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
+            ));
+          }
+          1 => {
+            return std::option::Some(Inherit(
+              ()
+            ));
+          }
+          _ => return std::option::None
+        }
+      }
+    }
+
+    pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+    impl <'a> layout::HasStructSize for Builder<'a> {
+      #[inline]
+      fn struct_size(_unused_self : Option<Builder>) -> layout::StructSize { STRUCT_SIZE }
+    }
+    impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+      fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+        Builder { builder : builder }
+      }
+    }
+    impl <'a> Builder<'a> {
+      pub fn as_reader(&self) -> Reader<'a> {
+        FromStructReader::new(self.builder.as_reader())
+      }
+      #[inline]
+      pub fn get_scope_id(&self) -> u64 {
+        self.builder.get_data_field::<u64>(0)
+      }
+      #[inline]
+      pub fn set_scope_id(&self, value : u64) {
+        self.builder.set_data_field::<u64>(0, value);
+      }
+      #[inline]
+      pub fn set_bind(&self, value : StructList::Reader<'a,schema_capnp::Brand::Binding::Reader<'a>>) -> Result<()> {
+        self.builder.set_data_field::<u16>(4, 0);
+        self.builder.get_pointer_field(0).set_list(&value.reader)
+      }
+      #[inline]
+      pub fn init_bind(&self, size : uint) -> StructList::Builder<'a,schema_capnp::Brand::Binding::Builder<'a>> {
+        self.builder.set_data_field::<u16>(4, 0);
+        StructList::Builder::<'a, schema_capnp::Brand::Binding::Builder<'a>>::new(
+          self.builder.get_pointer_field(0).init_struct_list(size, schema_capnp::Brand::Binding::STRUCT_SIZE))
+      }
+      pub fn has_bind(&self) -> bool {
+        if self.builder.get_data_field::<u16>(4) != 0 { return false; }
+        !self.builder.get_pointer_field(0).is_null()
+      }
+      #[inline]
+      pub fn set_inherit(&self, _value : ()) {
+        self.builder.set_data_field::<u16>(4, 1);
+      }
+      #[inline]
+      pub fn which(&self) -> std::option::Option<WhichBuilder<'a>> {
+        match self.builder.get_data_field::<u16>(4) {
+          0 => {
+            return std::option::Some(Bind(
+              self.builder.get_pointer_field(0).get_struct_list(schema_capnp::Brand::Binding::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
+            ));
+          }
+          1 => {
+            return std::option::Some(Inherit(
+              ()
+            ));
+          }
+          _ => return std::option::None
+        }
+      }
+    }
+
+    pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+    impl FromTypelessPipeline for Pipeline {
+      fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+        Pipeline { _typeless : typeless }
+      }
+    }
+    impl Pipeline {
+    }
+    pub enum Which<'a,A0> {
+      Bind(A0),
+      Inherit(()),
+    }
+    pub type WhichReader<'a> = Which<'a,Result<StructList::Reader<'a,schema_capnp::Brand::Binding::Reader<'a>>>>;
+    pub type WhichBuilder<'a> = Which<'a,Result<StructList::Builder<'a,schema_capnp::Brand::Binding::Builder<'a>>>>;
+  }
+
+  pub mod Binding {
+    use std;
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
+    use schema_capnp;
+
+    pub static STRUCT_SIZE : layout::StructSize =
+      layout::StructSize { data : 1, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+
+
+    pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+    impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+      fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+        Reader { reader : reader }
+      }
+    }
+
+    impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+      fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+    }
+
+    impl <'a> Reader<'a> {
+      pub fn has_type(&self) -> bool {
+        if self.reader.get_data_field::<u16>(0) != 1 { return false; }
+        !self.reader.get_pointer_field(0).is_null()
+      }
+      #[inline]
+      pub fn which(&self) -> std::option::Option<WhichReader<'a>> {
+        match self.reader.get_data_field::<u16>(0) {
+          0 => {
+            return std::option::Some(Unbound(
+              ()
+            ));
+          }
+          1 => {
+            return std::option::Some(Type(
+              self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+            ));
+          }
+          _ => return std::option::None
+        }
+      }
+    }
+
+    pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+    impl <'a> layout::HasStructSize for Builder<'a> {
+      #[inline]
+      fn struct_size(_unused_self : Option<Builder>) -> layout::StructSize { STRUCT_SIZE }
+    }
+    impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+      fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+        Builder { builder : builder }
+      }
+    }
+    impl <'a> Builder<'a> {
+      pub fn as_reader(&self) -> Reader<'a> {
+        FromStructReader::new(self.builder.as_reader())
+      }
+      #[inline]
+      pub fn set_unbound(&self, _value : ()) {
+        self.builder.set_data_field::<u16>(0, 0);
+      }
+      #[inline]
+      pub fn set_type(&self, value : schema_capnp::Type::Reader) -> Result<()> {
+        self.builder.set_data_field::<u16>(0, 1);
+        self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
+      }
+      #[inline]
+      pub fn init_type(&self, ) -> schema_capnp::Type::Builder<'a> {
+        self.builder.set_data_field::<u16>(0, 1);
+        FromStructBuilder::new(self.builder.get_pointer_field(0).init_struct(schema_capnp::Type::STRUCT_SIZE))
+      }
+      pub fn has_type(&self) -> bool {
+        if self.builder.get_data_field::<u16>(0) != 1 { return false; }
+        !self.builder.get_pointer_field(0).is_null()
+      }
+      #[inline]
+      pub fn which(&self) -> std::option::Option<WhichBuilder<'a>> {
+        match self.builder.get_data_field::<u16>(0) {
+          0 => {
+            return std::option::Some(Unbound(
+              ()
+            ));
+          }
+          1 => {
+            return std::option::Some(Type(
+              self.builder.get_pointer_field(0).get_struct(schema_capnp::Type::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+            ));
+          }
+          _ => return std::option::None
+        }
+      }
+    }
+
+    pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+    impl FromTypelessPipeline for Pipeline {
+      fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+        Pipeline { _typeless : typeless }
+      }
+    }
+    impl Pipeline {
+    }
+    pub enum Which<'a,A0> {
+      Unbound(()),
+      Type(A0),
+    }
+    pub type WhichReader<'a> = Which<'a,Result<schema_capnp::Type::Reader<'a>>>;
+    pub type WhichBuilder<'a> = Which<'a,Result<schema_capnp::Type::Builder<'a>>>;
+  }
+}
+
+pub mod Superclass {
+  use std;
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
+  use schema_capnp;
+
+  pub static STRUCT_SIZE : layout::StructSize =
+    layout::StructSize { data : 1, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+
+
+  pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+  impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+    fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+      Reader { reader : reader }
+    }
+  }
+
+  impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+    fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+  }
+
+  impl <'a> Reader<'a> {
+    #[inline]
+    pub fn get_id(&self) -> u64 {
+      self.reader.get_data_field::<u64>(0)
+    }
+    #[inline]
+    pub fn get_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+      self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+    }
+    pub fn has_brand(&self) -> bool {
+      !self.reader.get_pointer_field(0).is_null()
+    }
+  }
+
+  pub struct Builder<'a> { builder : layout::StructBuilder<'a> }
+  impl <'a> layout::HasStructSize for Builder<'a> {
+    #[inline]
+    fn struct_size(_unused_self : Option<Builder>) -> layout::StructSize { STRUCT_SIZE }
+  }
+  impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {
+    fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {
+      Builder { builder : builder }
+    }
+  }
+  impl <'a> Builder<'a> {
+    pub fn as_reader(&self) -> Reader<'a> {
+      FromStructReader::new(self.builder.as_reader())
+    }
+    #[inline]
+    pub fn get_id(&self) -> u64 {
+      self.builder.get_data_field::<u64>(0)
+    }
+    #[inline]
+    pub fn set_id(&self, value : u64) {
+      self.builder.set_data_field::<u64>(0, value);
+    }
+    #[inline]
+    pub fn get_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+      self.builder.get_pointer_field(0).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+    }
+    #[inline]
+    pub fn set_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+      self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
+    }
+    #[inline]
+    pub fn init_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+      FromStructBuilder::new(self.builder.get_pointer_field(0).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+    }
+    pub fn has_brand(&self) -> bool {
+      !self.builder.get_pointer_field(0).is_null()
+    }
+  }
+
+  pub struct Pipeline { _typeless : AnyPointer::Pipeline }
+  impl FromTypelessPipeline for Pipeline {
+    fn new(typeless : AnyPointer::Pipeline) -> Pipeline {
+      Pipeline { _typeless : typeless }
+    }
+  }
+  impl Pipeline {
+    pub fn get_brand(&self) -> schema_capnp::Brand::Pipeline {
+      FromTypelessPipeline::new(self._typeless.get_pointer_field(0))
+    }
+  }
+}
+
+pub mod Value {
+  use std;
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
+  use schema_capnp;
+
+  pub static STRUCT_SIZE : layout::StructSize =
+    layout::StructSize { data : 2, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+
+
+  pub struct Reader<'a> { reader : layout::StructReader<'a> }
+
+  impl <'a> layout::FromStructReader<'a> for Reader<'a> {
+    fn new(reader: layout::StructReader<'a>) -> Reader<'a> {
+      Reader { reader : reader }
+    }
+  }
+
+  impl <'a> layout::ToStructReader<'a> for Reader<'a> {
+    fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }
+  }
+
+  impl <'a> Reader<'a> {
+    pub fn has_text(&self) -> bool {
+      if self.reader.get_data_field::<u16>(0) != 12 { return false; }
+      !self.reader.get_pointer_field(0).is_null()
+    }
+    pub fn has_data(&self) -> bool {
+      if self.reader.get_data_field::<u16>(0) != 13 { return false; }
+      !self.reader.get_pointer_field(0).is_null()
+    }
+    pub fn has_list(&self) -> bool {
       if self.reader.get_data_field::<u16>(0) != 14 { return false; }
       !self.reader.get_pointer_field(0).is_null()
     }
@@ -2759,22 +3846,23 @@ pub mod Value {
     Interface(()),
     AnyPointer(A4),
   }
-  pub type WhichReader<'a> = Which<'a,Text::Reader<'a>,Data::Reader<'a>,AnyPointer::Reader<'a>,AnyPointer::Reader<'a>,AnyPointer::Reader<'a>>;
-  pub type WhichBuilder<'a> = Which<'a,Text::Builder<'a>,Data::Builder<'a>,AnyPointer::Builder<'a>,AnyPointer::Builder<'a>,AnyPointer::Builder<'a>>;
+  pub type WhichReader<'a> = Which<'a,Result<Text::Reader<'a>>,Result<Data::Reader<'a>>,AnyPointer::Reader<'a>,AnyPointer::Reader<'a>,AnyPointer::Reader<'a>>;
+  pub type WhichBuilder<'a> = Which<'a,Result<Text::Builder<'a>>,Result<Data::Builder<'a>>,AnyPointer::Builder<'a>,AnyPointer::Builder<'a>,AnyPointer::Builder<'a>>;
 }
 
 pub mod Annotation {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
-    layout::StructSize { data : 1, pointers : 1, preferred_list_encoding : layout::InlineComposite};
+    layout::StructSize { data : 1, pointers : 2, preferred_list_encoding : layout::InlineComposite};
 
 
   pub struct Reader<'a> { reader : layout::StructReader<'a> }
@@ -2795,8 +3883,15 @@ pub mod Annotation {
       self.reader.get_data_field::<u64>(0)
     }
     #[inline]
-    pub fn get_value(&self) -> schema_capnp::Value::Reader<'a> {
-      FromStructReader::new(self.reader.get_pointer_field(0).get_struct( std::ptr::null()))
+    pub fn get_brand(&self) -> Result<schema_capnp::Brand::Reader<'a>> {
+      self.reader.get_pointer_field(1).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
+    }
+    pub fn has_brand(&self) -> bool {
+      !self.reader.get_pointer_field(1).is_null()
+    }
+    #[inline]
+    pub fn get_value(&self) -> Result<schema_capnp::Value::Reader<'a>> {
+      self.reader.get_pointer_field(0).get_struct( std::ptr::null()).map(|s| FromStructReader::new(s))
     }
     pub fn has_value(&self) -> bool {
       !self.reader.get_pointer_field(0).is_null()
@@ -2826,11 +3921,26 @@ pub mod Annotation {
       self.builder.set_data_field::<u64>(0, value);
     }
     #[inline]
-    pub fn get_value(&self) -> schema_capnp::Value::Builder<'a> {
-      FromStructBuilder::new(self.builder.get_pointer_field(0).get_struct(schema_capnp::Value::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_brand(&self) -> Result<schema_capnp::Brand::Builder<'a>> {
+      self.builder.get_pointer_field(1).get_struct(schema_capnp::Brand::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
+    }
+    #[inline]
+    pub fn set_brand(&self, value : schema_capnp::Brand::Reader) -> Result<()> {
+      self.builder.get_pointer_field(1).set_struct(&value.struct_reader())
+    }
+    #[inline]
+    pub fn init_brand(&self, ) -> schema_capnp::Brand::Builder<'a> {
+      FromStructBuilder::new(self.builder.get_pointer_field(1).init_struct(schema_capnp::Brand::STRUCT_SIZE))
+    }
+    pub fn has_brand(&self) -> bool {
+      !self.builder.get_pointer_field(1).is_null()
+    }
+    #[inline]
+    pub fn get_value(&self) -> Result<schema_capnp::Value::Builder<'a>> {
+      self.builder.get_pointer_field(0).get_struct(schema_capnp::Value::STRUCT_SIZE, std::ptr::null()).map(|s| FromStructBuilder::new(s))
     }
     #[inline]
-    pub fn set_value(&self, value : schema_capnp::Value::Reader) {
+    pub fn set_value(&self, value : schema_capnp::Value::Reader) -> Result<()> {
       self.builder.get_pointer_field(0).set_struct(&value.struct_reader())
     }
     #[inline]
@@ -2849,6 +3959,9 @@ pub mod Annotation {
     }
   }
   impl Pipeline {
+    pub fn get_brand(&self) -> schema_capnp::Brand::Pipeline {
+      FromTypelessPipeline::new(self._typeless.get_pointer_field(1))
+    }
     pub fn get_value(&self) -> schema_capnp::Value::Pipeline {
       FromTypelessPipeline::new(self._typeless.get_pointer_field(0))
     }
@@ -2856,7 +3969,7 @@ pub mod Annotation {
 }
 
 pub mod ElementSize {
-  use capnp::list::{ToU16};
+  use list::{ToU16};
 
   #[repr(u16)]
   #[deriving(FromPrimitive)]
@@ -2879,12 +3992,13 @@ pub mod ElementSize {
 
 pub mod CodeGeneratorRequest {
   use std;
-  use capnp::any::AnyPointer;
-  use capnp::capability::{FromClientHook, FromTypelessPipeline};
-  use capnp::blob::{Text, Data};
-  use capnp::layout;
-  use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-  use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use any::AnyPointer;
+  use capability::{FromClientHook, FromTypelessPipeline};
+  use blob::{Text, Data};
+  use layout;
+  use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+  use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+  use error::Result;
   use schema_capnp;
 
   pub static STRUCT_SIZE : layout::StructSize =
@@ -2905,15 +4019,15 @@ pub mod CodeGeneratorRequest {
 
   impl <'a> Reader<'a> {
     #[inline]
-    pub fn get_nodes(&self) -> StructList::Reader<'a,schema_capnp::Node::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(0).get_list(schema_capnp::Node::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_nodes(&self) -> Result<StructList::Reader<'a,schema_capnp::Node::Reader<'a>>> {
+      self.reader.get_pointer_field(0).get_list(schema_capnp::Node::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_nodes(&self) -> bool {
       !self.reader.get_pointer_field(0).is_null()
     }
     #[inline]
-    pub fn get_requested_files(&self) -> StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Reader<'a>> {
-      StructList::Reader::new(self.reader.get_pointer_field(1).get_list(schema_capnp::CodeGeneratorRequest::RequestedFile::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+    pub fn get_requested_files(&self) -> Result<StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Reader<'a>>> {
+      self.reader.get_pointer_field(1).get_list(schema_capnp::CodeGeneratorRequest::RequestedFile::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
     }
     pub fn has_requested_files(&self) -> bool {
       !self.reader.get_pointer_field(1).is_null()
@@ -2935,11 +4049,11 @@ pub mod CodeGeneratorRequest {
       FromStructReader::new(self.builder.as_reader())
     }
     #[inline]
-    pub fn get_nodes(&self) -> StructList::Builder<'a,schema_capnp::Node::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(0).get_struct_list(schema_capnp::Node::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_nodes(&self) -> Result<StructList::Builder<'a,schema_capnp::Node::Builder<'a>>> {
+      self.builder.get_pointer_field(0).get_struct_list(schema_capnp::Node::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_nodes(&self, value : StructList::Reader<'a,schema_capnp::Node::Reader<'a>>) {
+    pub fn set_nodes(&self, value : StructList::Reader<'a,schema_capnp::Node::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(0).set_list(&value.reader)
     }
     #[inline]
@@ -2951,11 +4065,11 @@ pub mod CodeGeneratorRequest {
       !self.builder.get_pointer_field(0).is_null()
     }
     #[inline]
-    pub fn get_requested_files(&self) -> StructList::Builder<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Builder<'a>> {
-      StructList::Builder::new(self.builder.get_pointer_field(1).get_struct_list(schema_capnp::CodeGeneratorRequest::RequestedFile::STRUCT_SIZE, std::ptr::null()))
+    pub fn get_requested_files(&self) -> Result<StructList::Builder<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Builder<'a>>> {
+      self.builder.get_pointer_field(1).get_struct_list(schema_capnp::CodeGeneratorRequest::RequestedFile::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
     }
     #[inline]
-    pub fn set_requested_files(&self, value : StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Reader<'a>>) {
+    pub fn set_requested_files(&self, value : StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Reader<'a>>) -> Result<()> {
       self.builder.get_pointer_field(1).set_list(&value.reader)
     }
     #[inline]
@@ -2979,12 +4093,13 @@ pub mod CodeGeneratorRequest {
 
   pub mod RequestedFile {
     use std;
-    use capnp::any::AnyPointer;
-    use capnp::capability::{FromClientHook, FromTypelessPipeline};
-    use capnp::blob::{Text, Data};
-    use capnp::layout;
-    use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-    use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use any::AnyPointer;
+    use capability::{FromClientHook, FromTypelessPipeline};
+    use blob::{Text, Data};
+    use layout;
+    use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+    use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+    use error::Result;
     use schema_capnp;
 
     pub static STRUCT_SIZE : layout::StructSize =
@@ -3009,15 +4124,15 @@ pub mod CodeGeneratorRequest {
         self.reader.get_data_field::<u64>(0)
       }
       #[inline]
-      pub fn get_filename(&self) -> Text::Reader<'a> {
+      pub fn get_filename(&self) -> Result<Text::Reader<'a>> {
         self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
       }
       pub fn has_filename(&self) -> bool {
         !self.reader.get_pointer_field(0).is_null()
       }
       #[inline]
-      pub fn get_imports(&self) -> StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Import::Reader<'a>> {
-        StructList::Reader::new(self.reader.get_pointer_field(1).get_list(schema_capnp::CodeGeneratorRequest::RequestedFile::Import::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))
+      pub fn get_imports(&self) -> Result<StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Import::Reader<'a>>> {
+        self.reader.get_pointer_field(1).get_list(schema_capnp::CodeGeneratorRequest::RequestedFile::Import::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()).map(|l| StructList::Reader::new(l))
       }
       pub fn has_imports(&self) -> bool {
         !self.reader.get_pointer_field(1).is_null()
@@ -3047,7 +4162,7 @@ pub mod CodeGeneratorRequest {
         self.builder.set_data_field::<u64>(0, value);
       }
       #[inline]
-      pub fn get_filename(&self) -> Text::Builder<'a> {
+      pub fn get_filename(&self) -> Result<Text::Builder<'a>> {
         self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
       }
       #[inline]
@@ -3062,11 +4177,11 @@ pub mod CodeGeneratorRequest {
         !self.builder.get_pointer_field(0).is_null()
       }
       #[inline]
-      pub fn get_imports(&self) -> StructList::Builder<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Import::Builder<'a>> {
-        StructList::Builder::new(self.builder.get_pointer_field(1).get_struct_list(schema_capnp::CodeGeneratorRequest::RequestedFile::Import::STRUCT_SIZE, std::ptr::null()))
+      pub fn get_imports(&self) -> Result<StructList::Builder<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Import::Builder<'a>>> {
+        self.builder.get_pointer_field(1).get_struct_list(schema_capnp::CodeGeneratorRequest::RequestedFile::Import::STRUCT_SIZE, std::ptr::null()).map(|l| StructList::Builder::new(l))
       }
       #[inline]
-      pub fn set_imports(&self, value : StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Import::Reader<'a>>) {
+      pub fn set_imports(&self, value : StructList::Reader<'a,schema_capnp::CodeGeneratorRequest::RequestedFile::Import::Reader<'a>>) -> Result<()> {
         self.builder.get_pointer_field(1).set_list(&value.reader)
       }
       #[inline]
@@ -3090,12 +4205,13 @@ pub mod CodeGeneratorRequest {
 
     pub mod Import {
       use std;
-      use capnp::any::AnyPointer;
-      use capnp::capability::{FromClientHook, FromTypelessPipeline};
-      use capnp::blob::{Text, Data};
-      use capnp::layout;
-      use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};
-      use capnp::list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+      use any::AnyPointer;
+      use capability::{FromClientHook, FromTypelessPipeline};
+      use blob::{Text, Data};
+      use layout;
+      use layout::{FromStructBuilder, FromStructReader, ToStructReader};
+      use list::{PrimitiveList, ToU16, EnumList, StructList, TextList, DataList, ListList};
+      use error::Result;
       use schema_capnp;
 
       pub static STRUCT_SIZE : layout::StructSize =
@@ -3120,7 +4236,7 @@ pub mod CodeGeneratorRequest {
           self.reader.get_data_field::<u64>(0)
         }
         #[inline]
-        pub fn get_name(&self) -> Text::Reader<'a> {
+        pub fn get_name(&self) -> Result<Text::Reader<'a>> {
           self.reader.get_pointer_field(0).get_text(std::ptr::null(), 0)
         }
         pub fn has_name(&self) -> bool {
@@ -3151,7 +4267,7 @@ pub mod CodeGeneratorRequest {
           self.builder.set_data_field::<u64>(0, value);
         }
         #[inline]
-        pub fn get_name(&self) -> Text::Builder<'a> {
+        pub fn get_name(&self) -> Result<Text::Builder<'a>> {
           self.builder.get_pointer_field(0).get_text(std::ptr::null(), 0)
         }
         #[inline]
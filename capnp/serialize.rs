@@ -6,6 +6,7 @@
 
 use std;
 use std::vec::Vec;
+use libc;
 use common::*;
 use endian::*;
 use message::*;
@@ -40,11 +41,12 @@ fn invalid_input<T>(desc : &'static str) -> std::io::IoResult<T> {
                                  detail : None});
 }
 
-pub fn new_reader<U : std::io::Reader>(inputStream : &mut U,
-                                       options : ReaderOptions)
-                                       -> std::io::IoResult<OwnedSpaceMessageReader> {
-
-    let firstWord = try!(inputStream.read_exact(8));
+// Reads the rest of the segment table from `inputStream`, given its first
+// word, and returns the size of each segment in words.
+fn read_segment_sizes<U : std::io::Reader>(inputStream : &mut U,
+                                           firstWord : &[u8],
+                                           options : ReaderOptions)
+                                           -> std::io::IoResult<Vec<u32>> {
 
     let segmentCount : u32 =
         unsafe {let p : *WireValue<u32> = std::mem::transmute(firstWord.as_ptr());
@@ -53,7 +55,7 @@ pub fn new_reader<U : std::io::Reader>(inputStream : &mut U,
 
     let segment0Size =
         if segmentCount == 0 { 0 } else {
-        unsafe {let p : *WireValue<u32> = std::mem::transmute(firstWord.as_slice().unsafe_ref(4));
+        unsafe {let p : *WireValue<u32> = std::mem::transmute(firstWord.unsafe_ref(4));
                 (*p).get()
         }
     };
@@ -64,7 +66,8 @@ pub fn new_reader<U : std::io::Reader>(inputStream : &mut U,
         return invalid_input("too many segments");
     }
 
-    let mut moreSizes : Vec<u32> = Vec::with_capacity((segmentCount & !1) as uint);
+    let mut sizes : Vec<u32> = Vec::with_capacity(segmentCount as uint);
+    sizes.push(segment0Size);
 
     if segmentCount > 1 {
         let moreSizesRaw = try!(inputStream.read_exact((4 * (segmentCount & !1)) as uint));
@@ -74,7 +77,7 @@ pub fn new_reader<U : std::io::Reader>(inputStream : &mut U,
                     std::mem::transmute(moreSizesRaw.as_slice().unsafe_ref(ii * 4));
                 (*p).get()
             };
-            moreSizes.push(size);
+            sizes.push(size);
             totalWords += size;
         }
     }
@@ -89,46 +92,495 @@ pub fn new_reader<U : std::io::Reader>(inputStream : &mut U,
                               receiving end, see capnp::ReaderOptions.");
     }
 
-    let mut ownedSpace : Vec<Word> = allocate_zeroed_words(totalWords as uint);
-    let bufLen = totalWords as uint * BYTES_PER_WORD;
+    Ok(sizes)
+}
+
+// Returns the bounds of each segment as word offsets into a buffer
+// holding all of the segments back to back.
+fn segment_slices(sizes : &[u32]) -> Vec<(uint, uint)> {
+    let mut result = Vec::with_capacity(sizes.len());
+    let mut offset = 0;
+    for &size in sizes.iter() {
+        result.push((offset, offset + size as uint));
+        offset += size as uint;
+    }
+    result
+}
+
+pub fn new_reader<U : std::io::Reader>(inputStream : &mut U,
+                                       options : ReaderOptions)
+                                       -> std::io::IoResult<OwnedSpaceMessageReader> {
+    let firstWord = try!(inputStream.read_exact(8));
+    read_message(inputStream, firstWord.as_slice(), options)
+}
+
+// Like `new_reader()`, but returns `None` if the stream ends cleanly
+// before the start of a message. A stream that ends partway through
+// a message is an error.
+pub fn try_new_reader<U : std::io::Reader>(inputStream : &mut U,
+                                           options : ReaderOptions)
+                                           -> std::io::IoResult<Option<OwnedSpaceMessageReader>> {
+    let firstByte = match inputStream.read_byte() {
+        Ok(b) => b,
+        Err(ref e) if e.kind == std::io::EndOfFile => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let result = match inputStream.read_exact(7) {
+        Ok(rest) => {
+            let mut firstWord = vec!(firstByte);
+            firstWord.push_all(rest.as_slice());
+            read_message(inputStream, firstWord.as_slice(), options)
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Err(ref e) if e.kind == std::io::EndOfFile => invalid_input("Message ends prematurely."),
+        result => result.map(|message| Some(message)),
+    }
+}
+
+// Reads messages from a stream, one after another, until the stream ends.
+pub struct MessageIterator<'a, U> {
+    input : &'a mut U,
+    options : ReaderOptions,
+    done : bool,
+}
+
+impl <'a, U : std::io::Reader> MessageIterator<'a, U> {
+    pub fn new<'b>(input : &'b mut U, options : ReaderOptions) -> MessageIterator<'b, U> {
+        MessageIterator { input : input, options : options, done : false }
+    }
+}
+
+impl <'a, U : std::io::Reader> Iterator<std::io::IoResult<OwnedSpaceMessageReader>>
+for MessageIterator<'a, U> {
+    fn next(&mut self) -> Option<std::io::IoResult<OwnedSpaceMessageReader>> {
+        if self.done { return None; }
+        match try_new_reader(self.input, self.options) {
+            Ok(Some(message)) => Some(Ok(message)),
+            Ok(None) => { self.done = true; None }
+            Err(e) => { self.done = true; Some(Err(e)) }
+        }
+    }
+}
+
+fn read_message<U : std::io::Reader>(inputStream : &mut U,
+                                     firstWord : &[u8],
+                                     options : ReaderOptions)
+                                     -> std::io::IoResult<OwnedSpaceMessageReader> {
+
+    let sizes = try!(read_segment_sizes(inputStream, firstWord, options));
+    let segment_slices = segment_slices(sizes.as_slice());
+    let totalWords = match segment_slices.last() { Some(&(_, end)) => end, None => 0 };
+
+    let mut ownedSpace : Vec<Word> = allocate_zeroed_words(totalWords);
+    try!(read_segments(inputStream, ownedSpace.as_mut_slice()));
+
+    let arena = {
+        let segments : Vec<&[Word]> =
+            segment_slices.iter().map(|&(a, b)| ownedSpace.slice(a, b)).collect();
+        arena::ReaderArena::new(segments.as_slice(), options)
+    };
+
+    Ok(OwnedSpaceMessageReader {
+        segment_slices : segment_slices,
+        owned_space : ownedSpace,
+        arena : arena,
+        options : options,
+    })
+}
+
+// Fills `words` from `inputStream`.
+fn read_segments<U : std::io::Reader>(inputStream : &mut U,
+                                      words : &mut [Word]) -> std::io::IoResult<()> {
+    let bufLen = words.len() * BYTES_PER_WORD;
 
     unsafe {
-        let ptr : *mut u8 = std::mem::transmute(ownedSpace.as_mut_slice().as_mut_ptr());
+        let ptr : *mut u8 = std::mem::transmute(words.as_mut_ptr());
         try!(std::slice::raw::mut_buf_as_slice::<u8,std::io::IoResult<uint>>(ptr, bufLen, |buf| {
                     io::read_at_least(inputStream, buf, bufLen)
                 }));
     }
+    Ok(())
+}
 
-    // TODO(maybe someday) lazy reading like in capnp-c++?
+// Like `OwnedSpaceMessageReader`, but the message is read into a buffer
+// supplied by the caller, which can then be reused for the next message.
+pub struct ScratchSpaceMessageReader<'a> {
+    options : ReaderOptions,
+    arena : Box<arena::ReaderArena>,
+    segment_slices : Vec<(uint, uint)>,
+    scratch_space : &'a mut Vec<Word>,
+}
 
-    let mut segment_slices : Vec<(uint, uint)> = vec!((0, segment0Size as uint));
+impl <'a> MessageReader for ScratchSpaceMessageReader<'a> {
+    fn get_segment<'b>(&'b self, id : uint) -> &'b [Word] {
+        let (a,b) = self.segment_slices.as_slice()[id];
+        self.scratch_space.slice(a, b)
+    }
+
+    fn arena<'b>(&'b self) -> &'b arena::ReaderArena { &*self.arena }
+    fn mut_arena<'b>(&'b mut self) -> &'b mut arena::ReaderArena { &mut *self.arena }
+
+    fn get_options<'b>(&'b self) -> &'b ReaderOptions {
+        return &self.options;
+    }
+}
+
+// Reads a message into `scratch_space`, which is only reallocated if the
+// message does not fit in it.
+pub fn new_scratch_space_reader<'a, U : std::io::Reader>(inputStream : &mut U,
+                                                         scratch_space : &'a mut Vec<Word>,
+                                                         options : ReaderOptions)
+                                                         -> std::io::IoResult<ScratchSpaceMessageReader<'a>> {
+    let firstWord = try!(inputStream.read_exact(8));
+    let sizes = try!(read_segment_sizes(inputStream, firstWord.as_slice(), options));
+    let segment_slices = segment_slices(sizes.as_slice());
+    let totalWords = match segment_slices.last() { Some(&(_, end)) => end, None => 0 };
+
+    if scratch_space.len() < totalWords {
+        *scratch_space = allocate_zeroed_words(totalWords);
+    }
+    try!(read_segments(inputStream, scratch_space.as_mut_slice().mut_slice_to(totalWords)));
 
     let arena = {
-        let segment0 : &[Word] = ownedSpace.slice(0, segment0Size as uint);
-        let mut segments : Vec<&[Word]> = vec!(segment0);
-
-        if segmentCount > 1 {
-            let mut offset = segment0Size;
-
-            for ii in range(0, segmentCount as uint - 1) {
-                segments.push(ownedSpace.slice(offset as uint,
-                                               (offset + moreSizes.as_slice()[ii]) as uint));
-                segment_slices.push((offset as uint,
-                                     (offset + moreSizes.as_slice()[ii]) as uint));
-                offset += moreSizes.as_slice()[ii];
-            }
+        let segments : Vec<&[Word]> =
+            segment_slices.iter().map(|&(a, b)| scratch_space.slice(a, b)).collect();
+        arena::ReaderArena::new(segments.as_slice(), options)
+    };
+
+    Ok(ScratchSpaceMessageReader {
+        options : options,
+        arena : arena,
+        segment_slices : segment_slices,
+        scratch_space : scratch_space,
+    })
+}
+
+enum LazyState {
+    Pending,
+    Loaded,
+    Failed(std::io::IoError),
+}
+
+// The part of an input stream that has yet to be read into an
+// `InputStreamMessageReader`.
+struct LazyInput<'a, U> {
+    input : &'a mut U,
+    state : LazyState,
+    ptr : *mut u8,
+    len : uint,
+}
+
+impl <'a, U : std::io::Reader> LazyInput<'a, U> {
+    fn load(&mut self) -> bool {
+        match self.state {
+            Loaded => return true,
+            Failed(_) => return false,
+            Pending => {}
         }
+
+        let len = self.len;
+        let input = &mut *self.input;
+        let result = unsafe {
+            std::slice::raw::mut_buf_as_slice::<u8,std::io::IoResult<uint>>(self.ptr, len, |buf| {
+                io::read_at_least(input, buf, len)
+            })
+        };
+
+        match result {
+            Ok(_) => { self.state = Loaded; true }
+            Err(e) => { self.state = Failed(e); false }
+        }
+    }
+
+    fn error(&self) -> Option<std::io::IoError> {
+        match self.state {
+            Failed(ref e) => Some(e.clone()),
+            _ => None,
+        }
+    }
+}
+
+unsafe fn load_lazy_segments<U : std::io::Reader>(context : *mut u8) -> bool {
+    let lazy : &mut LazyInput<U> = std::mem::transmute(context);
+    lazy.load()
+}
+
+// Like `OwnedSpaceMessageReader`, but only the first segment is read up
+// front. The remaining segments are read from the input stream the first
+// time anything outside the first segment is accessed, so the message
+// can be examined before all of it has arrived.
+pub struct InputStreamMessageReader<'a, U> {
+    options : ReaderOptions,
+    arena : Box<arena::ReaderArena>,
+    segment_slices : Vec<(uint, uint)>,
+    owned_space : Vec<Word>,
+    lazy : Box<LazyInput<'a, U>>,
+}
+
+impl <'a, U : std::io::Reader> MessageReader for InputStreamMessageReader<'a, U> {
+    // Reads in the rest of the message if `id` is not the first segment.
+    // If that fails, the segment comes back empty rather than as the
+    // zeroed space that was set aside for it, and load_error() gives the
+    // reason.
+    fn get_segment<'b>(&'b self, id : uint) -> &'b [Word] {
+        if id > 0 && self.arena.try_get_segment(id as u32).is_err() {
+            return self.owned_space.slice(0, 0);
+        }
+        let (a,b) = self.segment_slices.as_slice()[id];
+        self.owned_space.slice(a, b)
+    }
+
+    fn arena<'b>(&'b self) -> &'b arena::ReaderArena { &*self.arena }
+    fn mut_arena<'b>(&'b mut self) -> &'b mut arena::ReaderArena { &mut *self.arena }
+
+    fn get_options<'b>(&'b self) -> &'b ReaderOptions {
+        return &self.options;
+    }
+}
+
+impl <'a, U : std::io::Reader> InputStreamMessageReader<'a, U> {
+    // The error that stopped the rest of the message from being read, if
+    // any. Reads that needed those segments failed with SegmentLoadFailed,
+    // and get_segment() returns them empty.
+    pub fn load_error(&self) -> Option<std::io::IoError> {
+        self.lazy.error()
+    }
+}
+
+#[unsafe_destructor]
+impl <'a, U : std::io::Reader> Drop for InputStreamMessageReader<'a, U> {
+    fn drop(&mut self) {
+        //# Consume the rest of the message so that the input stream is
+        //# left positioned at the start of the next one.
+        self.lazy.load();
+    }
+}
+
+pub fn new_lazy_reader<'a, U : std::io::Reader>(inputStream : &'a mut U,
+                                                options : ReaderOptions)
+                                                -> std::io::IoResult<InputStreamMessageReader<'a, U>> {
+
+    let firstWord = try!(inputStream.read_exact(8));
+    let sizes = try!(read_segment_sizes(inputStream, firstWord.as_slice(), options));
+    let segment_slices = segment_slices(sizes.as_slice());
+    let totalWords = match segment_slices.last() { Some(&(_, end)) => end, None => 0 };
+    let segment0Size = sizes.as_slice()[0] as uint;
+
+    let mut ownedSpace : Vec<Word> = allocate_zeroed_words(totalWords);
+
+    unsafe {
+        let ptr : *mut u8 = std::mem::transmute(ownedSpace.as_mut_slice().as_mut_ptr());
+        let len = segment0Size * BYTES_PER_WORD;
+        try!(std::slice::raw::mut_buf_as_slice::<u8,std::io::IoResult<uint>>(ptr, len, |buf| {
+                    io::read_at_least(inputStream, buf, len)
+                }));
+    }
+
+    let mut lazy = box LazyInput {
+        input : inputStream,
+        state : if sizes.len() > 1 { Pending } else { Loaded },
+        ptr : unsafe {
+            std::mem::transmute(ownedSpace.as_mut_slice().as_mut_ptr().offset(segment0Size as int))
+        },
+        len : (totalWords - segment0Size) * BYTES_PER_WORD,
+    };
+
+    let mut arena = {
+        let segments : Vec<&[Word]> =
+            segment_slices.iter().map(|&(a, b)| ownedSpace.slice(a, b)).collect();
         arena::ReaderArena::new(segments.as_slice(), options)
     };
+    let context : *mut u8 = unsafe { std::mem::transmute(&mut *lazy) };
+    arena.segment_loader = Some((load_lazy_segments::<U>, context));
 
-    Ok(OwnedSpaceMessageReader {
+    Ok(InputStreamMessageReader {
+        options : options,
+        arena : arena,
         segment_slices : segment_slices,
         owned_space : ownedSpace,
-        arena : arena,
+        lazy : lazy,
+    })
+}
+
+// Parses the segment table at the start of `words`, returning the
+// bounds of each segment as word offsets into `words`.
+fn read_segment_table(words : &[Word]) -> std::io::IoResult<Vec<(uint, uint)>> {
+    if words.len() == 0 {
+        return invalid_input("Message ends prematurely in segment table.");
+    }
+
+    let table : *WireValue<u32> = unsafe { std::mem::transmute(words.as_ptr()) };
+
+    let segment_count_minus_one = unsafe { (*table).get() };
+    if segment_count_minus_one >= 512 {
+        return invalid_input("too many segments");
+    }
+    let segment_count = segment_count_minus_one as uint + 1;
+
+    //# The table holds the segment count followed by one size per
+    //# segment, padded out to a whole number of words.
+    let table_words = (segment_count + 2) / 2;
+    if words.len() < table_words {
+        return invalid_input("Message ends prematurely in segment table.");
+    }
+
+    let mut segment_slices : Vec<(uint, uint)> = Vec::with_capacity(segment_count);
+    let mut offset = table_words;
+    for ii in range(0, segment_count) {
+        let size = unsafe { (*table.offset(ii as int + 1)).get() as uint };
+        if size > words.len() - offset {
+            return invalid_input("Message ends prematurely.");
+        }
+        segment_slices.push((offset, offset + size));
+        offset += size;
+    }
+
+    Ok(segment_slices)
+}
+
+// Interprets `words` as a message in the standard serialization format,
+// without copying. The segments of the returned reader point directly
+// into `words`.
+pub fn read_message_from_words<'a>(words : &'a [Word],
+                                   options : ReaderOptions)
+                                   -> std::io::IoResult<SegmentArrayMessageReader<'a>> {
+    let segment_slices = try!(read_segment_table(words));
+    let segments : Vec<&'a [Word]> =
+        segment_slices.iter().map(|&(a, b)| words.slice(a, b)).collect();
+    Ok(SegmentArrayMessageReader::from_segments(segments, options))
+}
+
+// A message backed by a read-only memory mapping of a file, so that
+// large messages can be read without first loading them into memory.
+pub struct MmapMessageReader {
+    options : ReaderOptions,
+    arena : Box<arena::ReaderArena>,
+    segment_slices : Vec<(uint, uint)>,
+    map : std::os::MemoryMap,
+}
+
+fn map_words<'a>(map : &'a std::os::MemoryMap) -> &'a [Word] {
+    unsafe {
+        std::mem::transmute(std::raw::Slice { data : map.data() as *Word,
+                                              len : map.len() / BYTES_PER_WORD })
+    }
+}
+
+impl MessageReader for MmapMessageReader {
+    fn get_segment<'b>(&'b self, id : uint) -> &'b [Word] {
+        let (a,b) = self.segment_slices.as_slice()[id];
+        map_words(&self.map).slice(a, b)
+    }
+
+    fn arena<'b>(&'b self) -> &'b arena::ReaderArena { &*self.arena }
+    fn mut_arena<'b>(&'b mut self) -> &'b mut arena::ReaderArena { &mut *self.arena }
+
+    fn get_options<'b>(&'b self) -> &'b ReaderOptions {
+        return &self.options;
+    }
+}
+
+// Maps the file at `path` into memory and reads a message from it. The
+// file should not be modified while the returned reader is alive.
+pub fn new_mmap_reader(path : &std::path::Path,
+                       options : ReaderOptions) -> std::io::IoResult<MmapMessageReader> {
+    let size = try!(std::io::fs::stat(path)).size as uint;
+    if size < BYTES_PER_WORD {
+        return invalid_input("Message ends prematurely in segment table.");
+    }
+
+    let fd = path.with_c_str(|p| unsafe { libc::open(p, libc::O_RDONLY, 0) });
+    if fd < 0 {
+        return Err(std::io::IoError::last_error());
+    }
+
+    let map = std::os::MemoryMap::new(size, [std::os::MapReadable, std::os::MapFd(fd)]);
+
+    //# The mapping stays valid after the descriptor is closed.
+    unsafe { libc::close(fd); }
+
+    let map = match map {
+        Ok(map) => map,
+        Err(e) => return Err(std::io::IoError { kind : std::io::OtherIoError,
+                                                 desc : "could not map file",
+                                                 detail : Some(format!("{}", e)) }),
+    };
+
+    let segment_slices = try!(read_segment_table(map_words(&map)));
+    let arena = {
+        let words = map_words(&map);
+        let segments : Vec<&[Word]> =
+            segment_slices.iter().map(|&(a, b)| words.slice(a, b)).collect();
+        arena::ReaderArena::new(segments.as_slice(), options)
+    };
+
+    Ok(MmapMessageReader {
         options : options,
+        arena : arena,
+        segment_slices : segment_slices,
+        map : map,
     })
 }
 
+// A message read out of a byte buffer by `read_message_from_bytes()`.
+pub enum BufferMessageReader<'a> {
+    // The buffer was word-aligned, so the message is read in place.
+    AlignedBuffer(SegmentArrayMessageReader<'a>),
+
+    // The buffer was not word-aligned, so it had to be copied.
+    CopiedBuffer(OwnedSpaceMessageReader),
+}
+
+impl <'a> MessageReader for BufferMessageReader<'a> {
+    fn get_segment<'b>(&'b self, id : uint) -> &'b [Word] {
+        match *self {
+            AlignedBuffer(ref reader) => reader.get_segment(id),
+            CopiedBuffer(ref reader) => reader.get_segment(id),
+        }
+    }
+
+    fn arena<'b>(&'b self) -> &'b arena::ReaderArena {
+        match *self {
+            AlignedBuffer(ref reader) => reader.arena(),
+            CopiedBuffer(ref reader) => reader.arena(),
+        }
+    }
+
+    fn mut_arena<'b>(&'b mut self) -> &'b mut arena::ReaderArena {
+        match *self {
+            AlignedBuffer(ref mut reader) => reader.mut_arena(),
+            CopiedBuffer(ref mut reader) => reader.mut_arena(),
+        }
+    }
+
+    fn get_options<'b>(&'b self) -> &'b ReaderOptions {
+        match *self {
+            AlignedBuffer(ref reader) => reader.get_options(),
+            CopiedBuffer(ref reader) => reader.get_options(),
+        }
+    }
+}
+
+// Like `read_message_from_words()`, but for a buffer of bytes that might
+// not be aligned on a word boundary. Unaligned buffers are copied.
+pub fn read_message_from_bytes<'a>(bytes : &'a [u8],
+                                   options : ReaderOptions)
+                                   -> std::io::IoResult<BufferMessageReader<'a>> {
+    if bytes.as_ptr().to_uint() % BYTES_PER_WORD == 0 {
+        let words : &'a [Word] = unsafe {
+            std::mem::transmute(std::raw::Slice { data : bytes.as_ptr(),
+                                                  len : bytes.len() / BYTES_PER_WORD })
+        };
+        Ok(AlignedBuffer(try!(read_message_from_words(words, options))))
+    } else {
+        let mut reader = std::io::BufReader::new(bytes);
+        Ok(CopiedBuffer(try!(new_reader(&mut reader, options))))
+    }
+}
 
 pub fn write_message<T : std::io::Writer, U : MessageBuilder>(
     output_stream : &mut T,
@@ -173,3 +625,65 @@ pub fn write_message<T : std::io::Writer, U : MessageBuilder>(
 
     output_stream.flush()
 }
+
+fn flat_size_in_words(segments : &[&[Word]]) -> uint {
+    let mut result = (segments.len() + 2) / 2;
+    for segment in segments.iter() {
+        result += segment.len();
+    }
+    result
+}
+
+// Returns the number of words that `write_message()` would write for this message.
+pub fn compute_serialized_size_in_words<U : MessageBuilder>(message : &U) -> uint {
+    message.get_segments_for_output(|segments| flat_size_in_words(segments))
+}
+
+// Writes the segment table followed by the segments to `dst`, which must
+// have room for `flat_size_in_words(segments)` words.
+unsafe fn write_flat(segments : &[&[Word]], dst : *mut u8) {
+    let table : *mut WireValue<u32> = std::mem::transmute(dst);
+    (*table).set((segments.len() - 1) as u32);
+    for i in range(0, segments.len()) {
+        (*table.offset(i as int + 1)).set(segments[i].len() as u32);
+    }
+    if segments.len() % 2 == 0 {
+        // Set padding.
+        (*table.offset(segments.len() as int + 1)).set(0);
+    }
+
+    let mut pos : *mut u8 = dst.offset((((segments.len() + 2) / 2) * BYTES_PER_WORD) as int);
+    for segment in segments.iter() {
+        let len = segment.len() * BYTES_PER_WORD;
+        std::ptr::copy_nonoverlapping_memory(pos, std::mem::transmute::<*Word,*u8>(segment.as_ptr()), len);
+        pos = pos.offset(len as int);
+    }
+}
+
+// Serializes the message into a single buffer of words, segment table included.
+pub fn message_to_flat_words<U : MessageBuilder>(message : &U) -> Vec<Word> {
+    message.get_segments_for_output(|segments| {
+        let mut result = allocate_zeroed_words(flat_size_in_words(segments));
+        unsafe { write_flat(segments, std::mem::transmute(result.as_mut_ptr())); }
+        result
+    })
+}
+
+// Serializes the message into a single buffer of bytes, segment table included.
+pub fn message_to_bytes<U : MessageBuilder>(message : &U) -> Vec<u8> {
+    message.get_segments_for_output(|segments| {
+        let mut result : Vec<u8> =
+            Vec::from_elem(flat_size_in_words(segments) * BYTES_PER_WORD, 0u8);
+        unsafe { write_flat(segments, result.as_mut_ptr()); }
+        result
+    })
+}
+
+// Like `write_message()`, but gathers the whole message into one buffer
+// first, so that the output stream sees a single write.
+pub fn write_message_flat<T : std::io::Writer, U : MessageBuilder>(
+    output_stream : &mut T,
+    message : &U) -> std::io::IoResult<()> {
+    try!(output_stream.write(message_to_bytes(message).as_slice()));
+    output_stream.flush()
+}
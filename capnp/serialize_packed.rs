@@ -5,10 +5,11 @@
  */
 
 use std;
+use std::vec::Vec;
 use io;
 use message::*;
 use serialize;
-use common::ptr_sub;
+use common::{Word, ptr_sub};
 
 
 pub struct PackedInputStream<'a, R> {
@@ -178,6 +179,64 @@ pub fn new_reader<U : io::BufferedInputStream>(input : &mut U,
     serialize::new_reader(&mut packed_input, options)
 }
 
+pub fn new_scratch_space_reader<'a, U : io::BufferedInputStream>(
+    input : &mut U,
+    scratch_space : &'a mut Vec<Word>,
+    options : ReaderOptions) -> std::io::IoResult<serialize::ScratchSpaceMessageReader<'a>> {
+    let mut packed_input = PackedInputStream {
+        inner : input
+    };
+
+    serialize::new_scratch_space_reader(&mut packed_input, scratch_space, options)
+}
+
+// Like `new_reader()`, but returns `None` if the stream ends cleanly
+// before the start of a message. A stream that ends partway through
+// a message is an error.
+pub fn try_new_reader<U : io::BufferedInputStream>(input : &mut U,
+                                                   options : ReaderOptions)
+                                                   -> std::io::IoResult<Option<serialize::OwnedSpaceMessageReader>> {
+    match unsafe { input.get_read_buffer() } {
+        Ok((begin, end)) => if begin == end { return Ok(None) },
+        Err(ref e) if e.kind == std::io::EndOfFile => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    match new_reader(input, options) {
+        Err(ref e) if e.kind == std::io::EndOfFile => {
+            Err(std::io::IoError { kind : std::io::InvalidInput,
+                                   desc : "Message ends prematurely.",
+                                   detail : None })
+        }
+        result => result.map(|message| Some(message)),
+    }
+}
+
+// Reads packed messages from a stream, one after another, until the stream ends.
+pub struct PackedMessageIterator<'a, U> {
+    input : &'a mut U,
+    options : ReaderOptions,
+    done : bool,
+}
+
+impl <'a, U : io::BufferedInputStream> PackedMessageIterator<'a, U> {
+    pub fn new<'b>(input : &'b mut U, options : ReaderOptions) -> PackedMessageIterator<'b, U> {
+        PackedMessageIterator { input : input, options : options, done : false }
+    }
+}
+
+impl <'a, U : io::BufferedInputStream> Iterator<std::io::IoResult<serialize::OwnedSpaceMessageReader>>
+for PackedMessageIterator<'a, U> {
+    fn next(&mut self) -> Option<std::io::IoResult<serialize::OwnedSpaceMessageReader>> {
+        if self.done { return None; }
+        match try_new_reader(self.input, self.options) {
+            Ok(Some(message)) => Some(Ok(message)),
+            Ok(None) => { self.done = true; None }
+            Err(e) => { self.done = true; Some(Err(e)) }
+        }
+    }
+}
+
 pub fn new_reader_unbuffered<U : std::io::Reader>(input : &mut U,
                                                   options : ReaderOptions)
                                                   -> std::io::IoResult<serialize::OwnedSpaceMessageReader> {
@@ -362,3 +421,11 @@ pub fn write_packed_message_unbuffered<T:std::io::Writer,U:MessageBuilder>(
     try!(write_packed_message(&mut buffered, message));
     buffered.flush()
 }
+
+// Returns the number of bytes that `write_packed_message()` would write
+// for this message. Packing is run as usual, but its output is only counted.
+pub fn compute_serialized_size_in_bytes<U:MessageBuilder>(message : &U) -> uint {
+    let mut counter = io::CountingOutputStream::new();
+    write_packed_message(&mut counter, message).unwrap();
+    counter.count
+}
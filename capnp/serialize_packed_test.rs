@@ -27,6 +27,17 @@ pub fn expect_packs_to(unpacked : &[u8],
     assert!(bytes.as_slice().eq(&packed),
             "expected: {:?}, got: {:?}", packed, bytes);
 
+    // --------
+    // count
+
+    let mut counter = io::CountingOutputStream::new();
+    {
+        let mut packedOutputStream = PackedOutputStream {inner : &mut counter};
+        packedOutputStream.write(unpacked).unwrap();
+        packedOutputStream.flush().unwrap();
+    }
+    assert_eq!(counter.count, packed.len());
+
     // --------
     // read
 
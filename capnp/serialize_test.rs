@@ -0,0 +1,232 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+use std;
+use common::{Word, allocate_zeroed_words};
+use error;
+use message::{MessageBuilder, MallocMessageBuilder, MessageReader, BuilderOptions, ReaderOptions};
+use serialize;
+use serialize_packed;
+
+fn message_bytes() -> Vec<u8> {
+    let mut message = MallocMessageBuilder::new_default();
+    message.get_root_internal().set_as_text("hello world");
+
+    let mut writer = std::io::MemWriter::new();
+    serialize::write_message(&mut writer, &message).unwrap();
+    writer.unwrap()
+}
+
+fn bytes_to_words(bytes : &[u8]) -> Vec<Word> {
+    let mut words = allocate_zeroed_words(bytes.len() / 8);
+    unsafe {
+        std::ptr::copy_nonoverlapping_memory(words.as_mut_ptr() as *mut u8, bytes.as_ptr(), bytes.len());
+    }
+    words
+}
+
+#[test]
+fn read_from_words() {
+    let words = bytes_to_words(message_bytes().as_slice());
+    let reader = serialize::read_message_from_words(words.as_slice(), ReaderOptions::new()).unwrap();
+    assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+
+    // The segment should point into `words`, not into a copy.
+    assert_eq!(reader.get_segment(0).as_ptr(), words.as_slice().slice_from(1).as_ptr());
+}
+
+#[test]
+fn read_from_truncated_words() {
+    let words = bytes_to_words(message_bytes().as_slice());
+    for len in range(0, words.len()) {
+        assert!(serialize::read_message_from_words(words.slice_to(len), ReaderOptions::new()).is_err());
+    }
+}
+
+#[test]
+fn read_from_unaligned_bytes() {
+    let bytes = message_bytes();
+
+    // Start the message one byte into a word-aligned buffer.
+    let mut words = allocate_zeroed_words(bytes.len() / 8 + 1);
+    let buffer : &[u8] = unsafe {
+        let start = (words.as_mut_ptr() as *mut u8).offset(1);
+        std::ptr::copy_nonoverlapping_memory(start, bytes.as_ptr(), bytes.len());
+        std::mem::transmute(std::raw::Slice { data : start as *u8, len : bytes.len() })
+    };
+
+    let reader = serialize::read_message_from_bytes(buffer, ReaderOptions::new()).unwrap();
+    match reader {
+        serialize::CopiedBuffer(_) => {}
+        serialize::AlignedBuffer(_) => fail!("expected the unaligned buffer to be copied"),
+    }
+    assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+}
+
+#[test]
+fn read_from_mmap() {
+    let dir = std::io::TempDir::new("capnp-serialize-test").unwrap();
+    let path = dir.path().join("message.bin");
+    std::io::File::create(&path).write(message_bytes().as_slice()).unwrap();
+
+    let reader = serialize::new_mmap_reader(&path, ReaderOptions::new()).unwrap();
+    assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+}
+
+#[test]
+fn read_lazily() {
+    // A one-word first segment forces the text into a second segment.
+    let mut options = BuilderOptions::new();
+    options.first_segment_words(1);
+    let mut message = MallocMessageBuilder::new(options);
+    message.get_root_internal().set_as_text("in the second segment");
+
+    let mut writer = std::io::MemWriter::new();
+    serialize::write_message(&mut writer, &message).unwrap();
+    serialize::write_message(&mut writer, &message).unwrap();
+    let bytes = writer.unwrap();
+
+    let mut input = std::io::BufReader::new(bytes.as_slice());
+    {
+        let reader = serialize::new_lazy_reader(&mut input, ReaderOptions::new()).unwrap();
+        assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(),
+                   "in the second segment");
+    }
+    {
+        // Dropping an unread reader should skip the rest of its message.
+        let _reader = serialize::new_lazy_reader(&mut input, ReaderOptions::new()).unwrap();
+    }
+    assert!(input.eof());
+}
+
+#[test]
+fn read_lazily_truncated() {
+    let mut options = BuilderOptions::new();
+    options.first_segment_words(1);
+    let mut message = MallocMessageBuilder::new(options);
+    message.get_root_internal().set_as_text("in the second segment");
+
+    let mut writer = std::io::MemWriter::new();
+    serialize::write_message(&mut writer, &message).unwrap();
+    let bytes = writer.unwrap();
+
+    // Cut the stream off partway through the second segment.
+    let mut input = std::io::BufReader::new(bytes.slice_to(bytes.len() - 8));
+    let mut reader_options = ReaderOptions::new();
+    reader_options.fail_fast(true);
+    let reader = serialize::new_lazy_reader(&mut input, reader_options).unwrap();
+    assert!(reader.load_error().is_none());
+    assert_eq!(reader.get_root_internal().unwrap().get_as_text().err(),
+               Some(error::SegmentLoadFailed));
+    assert!(reader.load_error().is_some());
+    assert!(reader.get_segment(0).len() > 0);
+    assert_eq!(reader.get_segment(1).len(), 0);
+}
+
+#[test]
+fn flat_output_matches_write_message() {
+    let mut options = BuilderOptions::new();
+    options.first_segment_words(1);
+    let mut message = MallocMessageBuilder::new(options);
+    message.get_root_internal().set_as_text("in the second segment");
+
+    let mut writer = std::io::MemWriter::new();
+    serialize::write_message(&mut writer, &message).unwrap();
+    let expected = writer.unwrap();
+
+    assert_eq!(serialize::message_to_bytes(&message), expected);
+
+    let mut writer = std::io::MemWriter::new();
+    serialize::write_message_flat(&mut writer, &message).unwrap();
+    assert_eq!(writer.unwrap(), expected);
+
+    let words = serialize::message_to_flat_words(&message);
+    let reader = serialize::read_message_from_words(words.as_slice(), ReaderOptions::new()).unwrap();
+    assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(), "in the second segment");
+}
+
+#[test]
+fn compute_serialized_size() {
+    let mut options = BuilderOptions::new();
+    options.first_segment_words(1);
+    let mut message = MallocMessageBuilder::new(options);
+    message.get_root_internal().set_as_text("in the second segment");
+
+    let mut writer = std::io::MemWriter::new();
+    serialize::write_message(&mut writer, &message).unwrap();
+    assert_eq!(serialize::compute_serialized_size_in_words(&message) * 8, writer.unwrap().len());
+
+    let mut writer = std::io::MemWriter::new();
+    serialize_packed::write_packed_message_unbuffered(&mut writer, &message).unwrap();
+    assert_eq!(serialize_packed::compute_serialized_size_in_bytes(&message), writer.unwrap().len());
+}
+
+#[test]
+fn message_iterator() {
+    let mut bytes = message_bytes();
+    bytes.push_all(message_bytes().as_slice());
+
+    let mut input = std::io::BufReader::new(bytes.as_slice());
+    let mut count = 0u;
+    for message in serialize::MessageIterator::new(&mut input, ReaderOptions::new()) {
+        let message = message.unwrap();
+        assert_eq!(message.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+        count += 1;
+    }
+    assert_eq!(count, 2);
+
+    // A truncated trailing message is an error, not the end of the stream.
+    let truncated = bytes.slice_to(bytes.len() - 3);
+    let mut input = std::io::BufReader::new(truncated);
+    let mut iter = serialize::MessageIterator::new(&mut input, ReaderOptions::new());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn packed_message_iterator() {
+    use io;
+
+    let mut writer = std::io::MemWriter::new();
+    for _ in range(0, 3) {
+        let mut message = MallocMessageBuilder::new_default();
+        message.get_root_internal().set_as_text("hello world");
+        serialize_packed::write_packed_message_unbuffered(&mut writer, &message).unwrap();
+    }
+    let bytes = writer.unwrap();
+
+    let mut input = io::ArrayInputStream::new(bytes.as_slice());
+    let mut count = 0u;
+    for message in serialize_packed::PackedMessageIterator::new(&mut input, ReaderOptions::new()) {
+        let message = message.unwrap();
+        assert_eq!(message.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn reuse_scratch_space() {
+    let mut bytes = message_bytes();
+    bytes.push_all(message_bytes().as_slice());
+    let mut input = std::io::BufReader::new(bytes.as_slice());
+
+    let mut scratch_space : Vec<Word> = Vec::new();
+    {
+        let reader = serialize::new_scratch_space_reader(&mut input, &mut scratch_space,
+                                                         ReaderOptions::new()).unwrap();
+        assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+    }
+    let buffer = scratch_space.as_ptr();
+    {
+        let reader = serialize::new_scratch_space_reader(&mut input, &mut scratch_space,
+                                                         ReaderOptions::new()).unwrap();
+        assert_eq!(reader.get_root_internal().unwrap().get_as_text().unwrap(), "hello world");
+    }
+    // The second message fit, so the buffer was not reallocated.
+    assert_eq!(scratch_space.as_ptr(), buffer);
+}
@@ -0,0 +1,209 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+// Messages that are built directly into a caller-supplied region of memory,
+// such as a mapping shared between processes, and read from it in place.
+//
+// The region starts with a header. Its first word holds the number of
+// segments and the number of segments the header has room for, as two
+// little-endian u32s. Each of the following words holds the offset from
+// the start of the region and the length, in words, of one segment.
+// Segments are carved out of the rest of the region as the message grows.
+//
+// A message that outgrows the region, or needs more segments than the
+// header has room for, spills over onto the heap. It can still be built,
+// but write_header() then fails, since the region does not hold all of it.
+
+use std;
+use arena::{BuilderArena, NumWords};
+use common::*;
+use endian::WireValue;
+use message::{AllocationStrategy, BuilderOptions, MessageBuilder, ReaderOptions,
+              SegmentAllocator, SegmentArrayMessageReader, GrowHeuristically, FixedSize,
+              HeapAllocator};
+
+// The part of the region that has not been handed out yet. It is shared
+// between the builder and its allocator.
+struct RegionState {
+    begin : *mut Word,
+    next : *mut Word,
+    end : *mut Word,
+    segments_left : uint,
+    overflowed : bool,
+}
+
+// Hands out consecutive segments from the part of the region after the header.
+struct RegionAllocator {
+    state : *mut RegionState,
+    next_size : WordCount,
+    allocation_strategy : AllocationStrategy,
+    overflow : HeapAllocator,
+}
+
+impl SegmentAllocator for RegionAllocator {
+    fn allocate_segment(&mut self, minimum_size : WordCount) -> (*mut Word, WordCount) {
+        let state = unsafe { &mut *self.state };
+        let available = ptr_sub(state.end, state.next);
+        let size = std::cmp::min(std::cmp::max(minimum_size, self.next_size), available);
+        if state.segments_left == 0 || size < minimum_size {
+            state.overflowed = true;
+            return self.overflow.allocate_segment(minimum_size);
+        }
+
+        let ptr = state.next;
+        unsafe {
+            //# The region may hold an earlier message.
+            std::ptr::zero_memory(ptr, size);
+            state.next = ptr.offset(size as int);
+        }
+        state.segments_left -= 1;
+        match self.allocation_strategy {
+            GrowHeuristically => { self.next_size += size; }
+            FixedSize => { }
+        }
+        (ptr, size)
+    }
+
+    fn deallocate_segment(&mut self, ptr : *mut Word, size : WordCount) {
+        let state = unsafe { &*self.state };
+        if ptr < state.begin || ptr >= state.end {
+            self.overflow.deallocate_segment(ptr, size);
+        }
+        //# Otherwise the memory belongs to the caller.
+    }
+}
+
+pub struct SharedMemoryMessageBuilder<'a> {
+    // Declared before `state` so that it is dropped first, while its
+    // allocator can still look at the state.
+    arena : Box<BuilderArena>,
+    state : Box<RegionState>,
+    region : &'a mut [Word],
+    max_segments : uint,
+}
+
+impl <'a> SharedMemoryMessageBuilder<'a> {
+
+    // `max_segments` is the number of segments that the header has room for.
+    pub fn new<'b>(region : &'b mut [Word], max_segments : uint,
+                   options : BuilderOptions) -> SharedMemoryMessageBuilder<'b> {
+        let header_words = 1 + max_segments;
+        assert!(max_segments > 0 && region.len() > header_words,
+                "shared memory region is too small for its header");
+
+        let mut state = unsafe {
+            let start = region.as_mut_ptr();
+            box RegionState {
+                begin : start.offset(header_words as int),
+                next : start.offset(header_words as int),
+                end : start.offset(region.len() as int),
+                segments_left : max_segments,
+                overflowed : false,
+            }
+        };
+        let allocator = box RegionAllocator {
+            state : &mut *state as *mut RegionState,
+            next_size : options.first_segment_words,
+            allocation_strategy : options.allocation_strategy,
+            overflow : HeapAllocator::new(options.first_segment_words, options.allocation_strategy),
+        };
+        //# The allocator makes the first segment as large as it can, up
+        //# to `options.first_segment_words`.
+        let arena = BuilderArena::new_with_allocator(allocator, NumWords(1), options.fail_fast);
+
+        SharedMemoryMessageBuilder { arena : arena, state : state, region : region,
+                                     max_segments : max_segments }
+    }
+
+    // The number of words in the region that have not been given to a segment.
+    pub fn words_remaining(&self) -> WordCount {
+        ptr_sub(self.state.end, self.state.next)
+    }
+
+    // The number of segments that the header still has room for.
+    pub fn segments_remaining(&self) -> uint {
+        self.state.segments_left
+    }
+
+    // True if part of the message had to be put on the heap, because the
+    // region or its header ran out of room.
+    pub fn overflowed(&self) -> bool {
+        self.state.overflowed
+    }
+
+    // Records the current segments in the header. Call this once the message
+    // is complete, before handing the region over to the reader. Fails, and
+    // leaves the header alone, if the message did not fit in the region.
+    pub fn write_header(&mut self) -> std::io::IoResult<()> {
+        if self.state.overflowed {
+            return Err(std::io::IoError { kind : std::io::OtherIoError,
+                                          desc : "Message does not fit in shared memory region.",
+                                          detail : None });
+        }
+        let start : *Word = self.region.as_ptr();
+        let header : *mut WireValue<u32> = unsafe { std::mem::transmute(self.region.as_mut_ptr()) };
+        let max_segments = self.max_segments;
+        self.arena.get_segments_for_output(|segments| {
+            unsafe {
+                (*header).set(segments.len() as u32);
+                (*header.offset(1)).set(max_segments as u32);
+                for ii in range(0, segments.len()) {
+                    let entry = header.offset(2 * (ii as int + 1));
+                    (*entry).set(ptr_sub(segments[ii].as_ptr(), start) as u32);
+                    (*entry.offset(1)).set(segments[ii].len() as u32);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl <'a> MessageBuilder for SharedMemoryMessageBuilder<'a> {
+    fn mut_arena<'b>(&'b mut self) -> &'b mut BuilderArena {
+        &mut *self.arena
+    }
+    fn arena<'b>(&'b self) -> &'b BuilderArena {
+        & *self.arena
+    }
+}
+
+fn invalid_header<T>(desc : &'static str) -> std::io::IoResult<T> {
+    Err(std::io::IoError { kind : std::io::InvalidInput, desc : desc, detail : None })
+}
+
+// Reads the message that a SharedMemoryMessageBuilder wrote into `region`.
+// The segments of the returned reader point directly into `region`.
+pub fn read_message<'a>(region : &'a [Word],
+                        options : ReaderOptions) -> std::io::IoResult<SegmentArrayMessageReader<'a>> {
+    if region.len() == 0 {
+        return invalid_header("Shared memory region ends prematurely in header.");
+    }
+    let header : *WireValue<u32> = unsafe { std::mem::transmute(region.as_ptr()) };
+    let segment_count = unsafe { (*header).get() as uint };
+    let max_segments = unsafe { (*header.offset(1)).get() as uint };
+
+    if segment_count == 0 || segment_count > max_segments {
+        return invalid_header("Shared memory header has an invalid segment count.");
+    }
+    let header_words = 1 + max_segments;
+    if region.len() < header_words {
+        return invalid_header("Shared memory region ends prematurely in header.");
+    }
+
+    let mut segments : Vec<&'a [Word]> = Vec::with_capacity(segment_count);
+    for ii in range(0, segment_count) {
+        let (offset, len) = unsafe {
+            let entry = header.offset(2 * (ii as int + 1));
+            ((*entry).get() as uint, (*entry.offset(1)).get() as uint)
+        };
+        if offset < header_words || offset > region.len() || len > region.len() - offset {
+            return invalid_header("Shared memory header describes an out-of-bounds segment.");
+        }
+        segments.push(region.slice(offset, offset + len));
+    }
+
+    Ok(SegmentArrayMessageReader::from_segments(segments, options))
+}
@@ -0,0 +1,39 @@
+/*
+ * Copyright (c) 2014, David Renshaw (dwrenshaw@gmail.com)
+ *
+ * See the LICENSE file in the capnproto-rust root directory.
+ */
+
+// Walking a message without generated code. AnyPointer::Reader::visit()
+// reports every object reachable from a pointer, in preorder, to a Visitor.
+// All methods have empty defaults, so implementations only need to handle
+// what they are interested in.
+//
+// The wire format does not distinguish Text from Data or List(UInt8), so
+// byte lists that end in a NUL byte and are valid UTF-8 are reported as
+// text, and all other byte lists as data.
+
+use common::*;
+use layout::FieldSize;
+
+pub trait Visitor {
+    // Called on entering a struct, before its pointers are visited.
+    fn visit_struct(&mut self, _data_words : WordCount, _pointer_count : WirePointerCount) {}
+    fn leave_struct(&mut self) {}
+
+    // Called on entering a list that is not text or data, before its
+    // elements are visited. Elements of InlineComposite lists are then
+    // reported as structs.
+    fn visit_list(&mut self, _element_size : FieldSize, _element_count : ElementCount) {}
+    fn leave_list(&mut self) {}
+
+    fn visit_text(&mut self, _text : &str) {}
+    fn visit_data(&mut self, _data : &[u8]) {}
+
+    // `index` is the capability's index in the message's cap table.
+    fn visit_capability(&mut self, _index : uint) {}
+
+    // Called when a far pointer is followed to another segment, before
+    // the object it points to is visited.
+    fn visit_far_pointer(&mut self, _segment_id : u32, _double_far : bool) {}
+}
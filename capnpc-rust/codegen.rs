@@ -7,7 +7,7 @@
 use capnp;
 use collections;
 use std;
-use schema_capnp;
+use capnp::schema_capnp;
 
 pub fn tuple_option<T,U>(t : Option<T>, u : Option<U>) -> Option<(T,U)> {
     match (t, u) {
@@ -70,7 +70,6 @@ fn prim_type_str (typ : schema_capnp::Type::WhichReader) -> &'static str {
     }
 }
 
-#[allow(dead_code)]
 fn camel_to_upper_case(s : &str) -> String {
     use std::ascii::*;
     let mut result_chars : Vec<char> = Vec::new();
@@ -160,31 +159,31 @@ fn stringify(ft : & FormattedText) -> String {
 fn populate_scope_map(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
                       scope_map : &mut collections::hashmap::HashMap<u64, Vec<String>>,
                       scope_names : Vec<String>,
-                      node_id : u64) {
+                      node_id : u64) -> capnp::Result<()> {
 
     scope_map.insert(node_id, scope_names.clone());
 
     // unused nodes in imported files might be omitted from the node map
-    let node_reader = match node_map.find(&node_id) { Some(node) => node, None => return (), };
+    let node_reader = match node_map.find(&node_id) { Some(node) => node, None => return Ok(()), };
 
-    let nested_nodes = node_reader.get_nested_nodes();
+    let nested_nodes = try!(node_reader.get_nested_nodes());
     for ii in range(0, nested_nodes.size()) {
         let mut scope_names = scope_names.clone();
-        scope_names.push(nested_nodes[ii].get_name().to_string());
-        populate_scope_map(node_map, scope_map, scope_names, nested_nodes[ii].get_id());
+        scope_names.push(try!(nested_nodes[ii].get_name()).to_string());
+        try!(populate_scope_map(node_map, scope_map, scope_names, nested_nodes[ii].get_id()));
     }
 
     match node_reader.which() {
         Some(schema_capnp::Node::Struct(struct_reader)) => {
-            let fields = struct_reader.get_fields();
+            let fields = try!(struct_reader.get_fields());
             for jj in range(0, fields.size()) {
                 let field = fields[jj];
                 match field.which() {
                     Some(schema_capnp::Field::Group(group)) => {
-                        let name = capitalize_first_letter(field.get_name());
+                        let name = capitalize_first_letter(try!(field.get_name()));
                         let mut scope_names = scope_names.clone();
                         scope_names.push(name);
-                        populate_scope_map(node_map, scope_map, scope_names, group.get_type_id());
+                        try!(populate_scope_map(node_map, scope_map, scope_names, group.get_type_id()));
                     }
                     _ => {}
                 }
@@ -192,6 +191,7 @@ fn populate_scope_map(node_map : &collections::hashmap::HashMap<u64, schema_capn
         }
         _ => {  }
     }
+    Ok(())
 }
 
 fn generate_import_statements() -> FormattedText {
@@ -203,17 +203,23 @@ fn generate_import_statements() -> FormattedText {
         Line("use capnp::layout;".to_string()),
         Line("use capnp::layout::{FromStructBuilder, FromStructReader, ToStructReader};".to_string()),
         Line("use capnp::{PrimitiveList, EnumList, StructList, TextList, DataList, ListList};".to_string()),
-        Line("use capnp::list::ToU16;".to_string()),
+        Line("use capnp::{AnyPointerList, CapabilityList};".to_string()),
+        Line("use capnp::common::Word;".to_string()),
+        Line("use capnp::list::{FromPointerReader, FromPointerBuilder, InitPointerBuilder, ToU16};".to_string()),
+        Line("use capnp::orphan::{Orphan, Orphanage, FromOrphanBuilder};".to_string()),
+        Line("use capnp::Result;".to_string()),
+        Line("use capnp::schema_capnp;".to_string()),
     ))
 }
 
-fn list_list_type_param(scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
+fn list_list_type_param(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                        scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
                         typ : schema_capnp::Type::Reader,
                         is_reader: bool,
-                        lifetime_name: &str) -> String {
+                        lifetime_name: &str) -> capnp::Result<String> {
     use schema_capnp::Type;
     let module = if is_reader { "Reader" } else { "Builder" };
-    match typ.which() {
+    Ok(match typ.which() {
         None => fail!("unsupported type"),
         Some(t) => {
             match t {
@@ -234,21 +240,241 @@ fn list_list_type_param(scope_map : &collections::hashmap::HashMap<u64, Vec<Stri
                     format!("DataList::{}<{}>", module, lifetime_name)
                 }
                 Type::Struct(st) => {
-                    format!("StructList::{}<{lifetime}, {}::{}<{lifetime}>>", module,
-                            scope_map.get(&st.get_type_id()).connect("::"), module, lifetime = lifetime_name)
+                    let args = try!(brand_arguments(node_map, scope_map, st.get_type_id(), try!(st.get_brand()),
+                                                    is_reader, lifetime_name, false));
+                    format!("StructList::{}<{lifetime}, {}::{}<{lifetime}{}>>", module,
+                            scope_map.get(&st.get_type_id()).connect("::"), module,
+                            type_args_suffix(args.as_slice()), lifetime = lifetime_name)
                 }
                 Type::List(t) => {
-                    let inner = list_list_type_param(scope_map, t.get_element_type(), is_reader, lifetime_name);
+                    let inner = try!(list_list_type_param(node_map, scope_map, try!(t.get_element_type()),
+                                                          is_reader, lifetime_name));
                     format!("ListList::{}<{}, {}>", module, lifetime_name, inner)
                 }
-                Type::AnyPointer(()) => {
-                    fail!("List(AnyPointer) is unsupported");
+                Type::AnyPointer(any) => {
+                    match any.which() {
+                        Some(Type::AnyPointer::Parameter(p)) => {
+                            format!("ListList::{}<{}, {}>", module, lifetime_name,
+                                    try!(parameter_name(node_map, p.get_scope_id(), p.get_parameter_index())))
+                        }
+                        _ => format!("AnyPointerList::{}<{}>", module, lifetime_name),
+                    }
                 }
-                Type::Interface(_i) => {
-                    fail!("unimplemented");
+                Type::Interface(i) => {
+                    format!("CapabilityList::{}<{}, {}>", module, lifetime_name,
+                            try!(client_type_text(node_map, scope_map, i, is_reader, lifetime_name)))
                 }
             }
         }
+    })
+}
+
+// The scope that a node is nested in. The parameter and result structs that
+// are implicitly declared for a method have no scope of their own, so they
+// are treated as nested in the method's interface.
+fn parent_scope_id(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                   node : schema_capnp::Node::Reader) -> capnp::Result<u64> {
+    use schema_capnp::Node;
+
+    if node.get_scope_id() != 0 { return Ok(node.get_scope_id()); }
+    let id = node.get_id();
+    for (_, candidate) in node_map.iter() {
+        match candidate.which() {
+            Some(Node::Interface(interface)) => {
+                let methods = try!(interface.get_methods());
+                for ii in range(0, methods.size()) {
+                    let method = methods[ii];
+                    if method.get_param_struct_type() == id || method.get_result_struct_type() == id {
+                        return Ok(candidate.get_id());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(0)
+}
+
+// The generic scopes around a node, including the node itself, outermost
+// first, each with the names of its type parameters.
+fn generic_scopes(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                  node_id : u64) -> capnp::Result<Vec<(u64, Vec<String>)>> {
+    let node = match node_map.find(&node_id) {
+        Some(node) => *node,
+        None => return Ok(Vec::new()),
+    };
+    let mut result = try!(generic_scopes(node_map, try!(parent_scope_id(node_map, node))));
+    let parameters = try!(node.get_parameters());
+    if parameters.size() > 0 {
+        let mut names = Vec::new();
+        for ii in range(0, parameters.size()) {
+            names.push(try!(parameters[ii].get_name()).to_string());
+        }
+        result.push((node_id, names));
+    }
+    Ok(result)
+}
+
+// The type parameters of the generated Reader, Builder, Pipeline or Client
+// for a node. Nested nodes inherit the parameters of their generic scopes.
+fn type_parameters(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                   node_id : u64) -> capnp::Result<Vec<String>> {
+    let mut result = Vec::new();
+    for &(_, ref names) in try!(generic_scopes(node_map, node_id)).iter() {
+        result.push_all(names.as_slice());
+    }
+    Ok(result)
+}
+
+fn parameter_name(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                  scope_id : u64, index : u16) -> capnp::Result<String> {
+    let parameters = try!(node_map.get(&scope_id).get_parameters());
+    Ok(try!(parameters[index as uint].get_name()).to_string())
+}
+
+// Formats type arguments to follow a lifetime, as in `Reader<'a, K, V>`.
+fn type_args_suffix(args : &[String]) -> String {
+    if args.is_empty() { "".to_string() } else { format!(", {}", args.connect(", ")) }
+}
+
+// Pipelines take their type arguments in reader form, as in `Pipeline<K, V>`.
+fn pipeline_args_text(args : &[String]) -> String {
+    if args.is_empty() { "".to_string() } else { format!("<{}>", args.connect(", ")) }
+}
+
+fn any_pointer_type(is_reader : bool, lifetime_name : &str) -> String {
+    format!("AnyPointer::{}<{}>", if is_reader { "Reader" } else { "Builder" }, lifetime_name)
+}
+
+// The type arguments that `brand` gives to the parameters of `type_id` and
+// of the generic scopes around it. Parameters that the brand leaves unbound
+// become AnyPointer. Inherited ones keep their names, unless `inherit_as_any`
+// is set because those names are not in scope.
+fn brand_arguments(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                   scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
+                   type_id : u64,
+                   brand : schema_capnp::Brand::Reader,
+                   is_reader : bool,
+                   lifetime_name : &str,
+                   inherit_as_any : bool) -> capnp::Result<Vec<String>> {
+    use schema_capnp::Brand;
+
+    let scopes = try!(brand.get_scopes());
+    let mut result = Vec::new();
+    for &(scope_id, ref names) in try!(generic_scopes(node_map, type_id)).iter() {
+        let mut bound = false;
+        for ii in range(0, scopes.size()) {
+            let scope = scopes[ii];
+            if scope.get_scope_id() != scope_id { continue; }
+            match scope.which() {
+                Some(Brand::Scope::Bind(bindings)) => {
+                    let bindings = try!(bindings);
+                    for jj in range(0, bindings.size()) {
+                        match bindings[jj].which() {
+                            Some(Brand::Binding::Type(t)) => {
+                                result.push(try!(type_text(node_map, scope_map, try!(t),
+                                                           is_reader, lifetime_name)));
+                            }
+                            _ => result.push(any_pointer_type(is_reader, lifetime_name)),
+                        }
+                    }
+                    bound = true;
+                }
+                Some(Brand::Scope::Inherit(())) if !inherit_as_any => {
+                    result.push_all(names.as_slice());
+                    bound = true;
+                }
+                _ => {}
+            }
+        }
+        if !bound {
+            for _ in names.iter() {
+                result.push(any_pointer_type(is_reader, lifetime_name));
+            }
+        }
+    }
+    Ok(result)
+}
+
+// The Rust type of a pointer-typed value, for use as a type argument.
+fn type_text(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+             scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
+             typ : schema_capnp::Type::Reader,
+             is_reader : bool,
+             lifetime_name : &str) -> capnp::Result<String> {
+    use schema_capnp::Type;
+    let module = if is_reader { "Reader" } else { "Builder" };
+    Ok(match typ.which() {
+        Some(Type::Text(())) => format!("Text::{}<{}>", module, lifetime_name),
+        Some(Type::Data(())) => format!("Data::{}<{}>", module, lifetime_name),
+        Some(Type::Struct(st)) => {
+            let args = try!(brand_arguments(node_map, scope_map, st.get_type_id(), try!(st.get_brand()),
+                                            is_reader, lifetime_name, false));
+            format!("{}::{}<{}{}>", scope_map.get(&st.get_type_id()).connect("::"), module,
+                    lifetime_name, type_args_suffix(args.as_slice()))
+        }
+        Some(Type::List(ot)) => {
+            try!(list_list_type_param(node_map, scope_map, try!(ot.get_element_type()),
+                                      is_reader, lifetime_name))
+        }
+        Some(Type::Interface(interface)) => {
+            try!(client_type_text(node_map, scope_map, interface, is_reader, lifetime_name))
+        }
+        Some(Type::AnyPointer(any)) => {
+            match any.which() {
+                Some(Type::AnyPointer::Parameter(p)) => {
+                    try!(parameter_name(node_map, p.get_scope_id(), p.get_parameter_index()))
+                }
+                _ => any_pointer_type(is_reader, lifetime_name),
+            }
+        }
+        _ => fail!("type arguments must be pointer types"),
+    })
+}
+
+fn client_type_text(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                    scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
+                    interface : schema_capnp::Type::Interface::Reader,
+                    is_reader : bool,
+                    lifetime_name : &str) -> capnp::Result<String> {
+    let the_mod = scope_map.get(&interface.get_type_id()).connect("::");
+    let args = try!(brand_arguments(node_map, scope_map, interface.get_type_id(),
+                                    try!(interface.get_brand()), is_reader, lifetime_name, false));
+    if args.is_empty() {
+        Ok(format!("{}::Client", the_mod))
+    } else {
+        Ok(format!("{}::Client<{}>", the_mod, args.connect(", ")))
+    }
+}
+
+// If `typ` is one of the type parameters in scope, its name.
+fn parameter_type_name(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                       typ : schema_capnp::Type::Reader) -> capnp::Result<Option<String>> {
+    use schema_capnp::Type;
+    match typ.which() {
+        Some(Type::AnyPointer(any)) => {
+            match any.which() {
+                Some(Type::AnyPointer::Parameter(p)) => {
+                    Ok(Some(try!(parameter_name(node_map, p.get_scope_id(), p.get_parameter_index()))))
+                }
+                _ => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+// Whether the Rust type of `typ` mentions type parameters, so that setters
+// taking it have to be generic.
+fn is_generic_type(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                   typ : schema_capnp::Type::Reader) -> capnp::Result<bool> {
+    use schema_capnp::Type;
+    match typ.which() {
+        Some(Type::Struct(st)) => Ok(!try!(type_parameters(node_map, st.get_type_id())).is_empty()),
+        Some(Type::Interface(i)) => Ok(!try!(type_parameters(node_map, i.get_type_id())).is_empty()),
+        Some(Type::List(ot)) => is_generic_type(node_map, try!(ot.get_element_type())),
+        Some(Type::AnyPointer(_)) => Ok(try!(parameter_type_name(node_map, typ)).is_some()),
+        _ => Ok(false),
     }
 }
 
@@ -276,11 +502,155 @@ fn prim_default (value : &schema_capnp::Value::Reader) -> Option<String> {
     }
 }
 
-fn getter_text (_node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+fn default_value_name(field : &schema_capnp::Field::Reader) -> capnp::Result<String> {
+    Ok(format!("DEFAULT_{}", camel_to_upper_case(try!(field.get_name()))))
+}
+
+// Returns the pointer-valued default of a struct or list field, if it has one.
+fn pointer_default<'a>(value : schema_capnp::Value::Reader<'a>) -> Option<capnp::AnyPointer::Reader<'a>> {
+    use schema_capnp::Value;
+    match value.which() {
+        Some(Value::Struct(p)) | Some(Value::List(p)) if !p.is_null() => Some(p),
+        _ => None,
+    }
+}
+
+fn pointer_default_text(field : &schema_capnp::Field::Reader,
+                        value : schema_capnp::Value::Reader) -> capnp::Result<String> {
+    match pointer_default(value) {
+        Some(_) => {
+            Ok(format!("unsafe \\{ std::mem::transmute({}.words.as_ptr()) \\}",
+                       try!(default_value_name(field))))
+        }
+        None => Ok("std::ptr::null()".to_string()),
+    }
+}
+
+// Encodes a value as a single flat segment whose first word is the root pointer.
+// `word_count` is the size of the value, not counting the root pointer.
+fn encode_words(word_count : uint,
+                init : |capnp::AnyPointer::Builder| -> capnp::Result<()>) -> capnp::Result<Vec<u8>> {
+    use capnp::MessageBuilder;
+
+    let mut options = capnp::BuilderOptions::new();
+    options.first_segment_words(word_count + 1);
+    let mut message = capnp::MallocMessageBuilder::new(options);
+    try!(init(message.get_root_internal()));
+
+    Ok(message.get_segments_for_output(|segments| {
+        assert!(segments.len() == 1, "default value did not fit in a single segment");
+        let words = segments[0];
+        unsafe {
+            std::slice::raw::buf_as_slice(words.as_ptr() as *u8,
+                                          words.len() * capnp::common::BYTES_PER_WORD,
+                                          |bytes| Vec::from_slice(bytes))
+        }
+    }))
+}
+
+fn encode_default_words(value : capnp::AnyPointer::Reader) -> capnp::Result<Vec<u8>> {
+    let size = try!(value.total_size());
+    encode_words(size.word_count as uint, |root| root.set_as(value))
+}
+
+fn aligned_data_text(name : &str, bytes : &[u8]) -> FormattedText {
+    let mut lines = Vec::new();
+    lines.push(Line(format!("pub static {} : layout::AlignedData<[u8, .. {}]> = layout::AlignedData \\{",
+                            name, bytes.len())));
+    lines.push(Indent(box Line("_dummy : 0,".to_string())));
+    lines.push(Indent(box Line("words : [".to_string())));
+    for word in bytes.chunks(8) {
+        let strs : Vec<String> = word.iter().map(|b| format!("0x{:02x}", *b)).collect();
+        lines.push(Indent(box Indent(box Line(format!("{},", strs.connect(", "))))));
+    }
+    lines.push(Indent(box Line("]".to_string())));
+    lines.push(Line("};".to_string()));
+    Branch(lines)
+}
+
+// Embeds the encoded node in its generated module, along with a `schema()`
+// accessor that reads it back, so that tools can inspect the schema at runtime.
+fn schema_text(node : schema_capnp::Node::Reader) -> capnp::Result<FormattedText> {
+    use capnp::layout::ToStructReader;
+
+    let size = try!(node.struct_reader().total_size());
+    let bytes = try!(encode_words(size.word_count as uint, |root| root.set_as_struct(&node)));
+    Ok(Branch(vec!(
+        aligned_data_text("ENCODED_NODE", bytes.as_slice()),
+        BlankLine,
+        Line("pub fn schema() -> Result<schema_capnp::Node::Reader<'static>> {".to_string()),
+        Indent(box Line("let root = layout::PointerReader::get_root_unchecked(unsafe { std::mem::transmute(ENCODED_NODE.words.as_ptr()) });".to_string())),
+        Indent(box Line("root.get_struct(std::ptr::null()).map(|s| FromStructReader::new(s))".to_string())),
+        Line("}".to_string()))))
+}
+
+fn generate_default_value(field : &schema_capnp::Field::Reader) -> capnp::Result<Option<FormattedText>> {
+    use schema_capnp::Field;
+    let reg_field = match field.which() {
+        Some(Field::Slot(reg_field)) => reg_field,
+        _ => return Ok(None),
+    };
+    let value = match pointer_default(try!(reg_field.get_default_value())) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let bytes = try!(encode_default_words(value));
+    Ok(Some(aligned_data_text(try!(default_value_name(field)).as_slice(), bytes.as_slice())))
+}
+
+// Emits the encoded value of a pointer-typed constant along with an accessor
+// that reads it. For a constant `fooBar`, these are `FOO_BAR` and `get_foo_bar()`.
+fn pointer_constant_text(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+                         scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
+                         name : &str,
+                         typ : schema_capnp::Type::Reader,
+                         bytes : &[u8]) -> capnp::Result<FormattedText> {
+    use schema_capnp::Type;
+
+    let styled_name = camel_to_upper_case(name);
+    let root = format!("layout::PointerReader::get_root_unchecked(unsafe \\{ std::mem::transmute({}.words.as_ptr()) \\})",
+                       styled_name);
+    let (result_type, body) = match typ.which() {
+        Some(Type::Text(())) => {
+            ("Result<Text::Reader<'static>>".to_string(),
+             format!("{}.get_text(std::ptr::null(), 0)", root))
+        }
+        Some(Type::Data(())) => {
+            ("Result<Data::Reader<'static>>".to_string(),
+             format!("{}.get_data(std::ptr::null(), 0)", root))
+        }
+        Some(Type::Struct(_)) => {
+            (format!("Result<{}>", try!(type_text(node_map, scope_map, typ, true, "'static"))),
+             format!("{}.get_struct(std::ptr::null()).map(|s| FromStructReader::new(s))", root))
+        }
+        Some(Type::List(_)) => {
+            (format!("Result<{}>", try!(type_text(node_map, scope_map, typ, true, "'static"))),
+             format!("FromPointerReader::get_from_pointer(&{}, std::ptr::null())", root))
+        }
+        Some(Type::AnyPointer(_)) => {
+            ("AnyPointer::Reader<'static>".to_string(),
+             format!("AnyPointer::Reader::new({})", root))
+        }
+        Some(Type::Interface(_)) => {
+            (format!("Result<{}>", try!(type_text(node_map, scope_map, typ, true, "'static"))),
+             format!("{}.get_capability().map(|c| FromClientHook::new(c))", root))
+        }
+        _ => fail!("not a pointer type"),
+    };
+
+    Ok(Branch(vec!(
+        aligned_data_text(styled_name.as_slice(), bytes),
+        Line(format!("pub fn get_{}() -> {} \\{", camel_to_snake_case(name), result_type)),
+        Indent(box Line(body)),
+        Line("}".to_string()))))
+}
+
+fn getter_text (node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
                scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
                field : &schema_capnp::Field::Reader,
                isReader : bool)
-    -> (String, FormattedText) {
+    -> capnp::Result<(String, FormattedText)> {
 
     use schema_capnp::*;
 
@@ -288,12 +658,14 @@ fn getter_text (_node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
         None => fail!("unrecognized field type"),
         Some(Field::Group(group)) => {
             let theMod = scope_map.get(&group.get_type_id()).connect("::");
+            let params = try!(type_parameters(node_map, group.get_type_id()));
+            let args = type_args_suffix(params.as_slice());
             if isReader {
-                return (format!("{}::Reader<'a>", theMod),
-                        Line("FromStructReader::new(self.reader)".to_string()));
+                return Ok((format!("{}::Reader<'a{}>", theMod, args),
+                           Line("FromStructReader::new(self.reader)".to_string())));
             } else {
-                return (format!("{}::Builder<'a>", theMod),
-                        Line("FromStructBuilder::new(self.builder)".to_string()));
+                return Ok((format!("{}::Builder<'a{}>", theMod, args),
+                           Line("FromStructBuilder::new(self.builder)".to_string())));
             }
         }
         Some(Field::Slot(reg_field)) => {
@@ -303,117 +675,155 @@ fn getter_text (_node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
             let member = if isReader { "reader" } else { "builder" };
             let module = if isReader { "Reader" } else { "Builder" };
             let moduleWithVar = if isReader { "Reader<'a>" } else { "Builder<'a>" };
+            let default_ptr = try!(pointer_default_text(field, try!(reg_field.get_default_value())));
 
-            match tuple_option(reg_field.get_type().which(), reg_field.get_default_value().which()) {
-                Some((Type::Void(()), Value::Void(()))) => { return ("()".to_string(), Line("()".to_string()))}
+            match tuple_option(try!(reg_field.get_type()).which(),
+                               try!(reg_field.get_default_value()).which()) {
+                Some((Type::Void(()), Value::Void(()))) => { return Ok(("()".to_string(), Line("()".to_string())))}
                 Some((Type::Bool(()), Value::Bool(b))) => {
                     if b {
-                        return ("bool".to_string(), Line(format!("self.{}.get_bool_field_mask({}, true)",
-                                                                member, offset)))
+                        return Ok(("bool".to_string(), Line(format!("self.{}.get_bool_field_mask({}, true)",
+                                                                   member, offset))))
                     } else {
-                        return ("bool".to_string(), Line(format!("self.{}.get_bool_field({})",
-                                                                member, offset)))
+                        return Ok(("bool".to_string(), Line(format!("self.{}.get_bool_field({})",
+                                                                   member, offset))))
                     }
                 }
-                Some((Type::Int8(()), Value::Int8(i))) => return common_case("i8", member, offset, i),
-                Some((Type::Int16(()), Value::Int16(i))) => return common_case("i16", member, offset, i),
-                Some((Type::Int32(()), Value::Int32(i))) => return common_case("i32", member, offset, i),
-                Some((Type::Int64(()), Value::Int64(i))) => return common_case("i64", member, offset, i),
-                Some((Type::Uint8(()), Value::Uint8(i))) => return common_case("u8", member, offset, i),
-                Some((Type::Uint16(()), Value::Uint16(i))) => return common_case("u16", member, offset, i),
-                Some((Type::Uint32(()), Value::Uint32(i))) => return common_case("u32", member, offset, i),
-                Some((Type::Uint64(()), Value::Uint64(i))) => return common_case("u64", member, offset, i),
-                Some((Type::Float32(()), Value::Float32(f))) => return common_case("f32", member, offset, f),
-                Some((Type::Float64(()), Value::Float64(f))) => return common_case("f64", member, offset, f),
+                Some((Type::Int8(()), Value::Int8(i))) => return Ok(common_case("i8", member, offset, i)),
+                Some((Type::Int16(()), Value::Int16(i))) => return Ok(common_case("i16", member, offset, i)),
+                Some((Type::Int32(()), Value::Int32(i))) => return Ok(common_case("i32", member, offset, i)),
+                Some((Type::Int64(()), Value::Int64(i))) => return Ok(common_case("i64", member, offset, i)),
+                Some((Type::Uint8(()), Value::Uint8(i))) => return Ok(common_case("u8", member, offset, i)),
+                Some((Type::Uint16(()), Value::Uint16(i))) => return Ok(common_case("u16", member, offset, i)),
+                Some((Type::Uint32(()), Value::Uint32(i))) => return Ok(common_case("u32", member, offset, i)),
+                Some((Type::Uint64(()), Value::Uint64(i))) => return Ok(common_case("u64", member, offset, i)),
+                Some((Type::Float32(()), Value::Float32(f))) => return Ok(common_case("f32", member, offset, f)),
+                Some((Type::Float64(()), Value::Float64(f))) => return Ok(common_case("f64", member, offset, f)),
                 Some((Type::Text(()), _)) => {
-                    return (format!("Text::{}", moduleWithVar),
-                            Line(format!("self.{}.get_pointer_field({}).get_text(std::ptr::null(), 0)",
-                                      member, offset)));
+                    return Ok((format!("Result<Text::{}>", moduleWithVar),
+                               Line(format!("self.{}.get_pointer_field({}).get_text(std::ptr::null(), 0)",
+                                         member, offset))));
                 }
                 Some((Type::Data(()), _)) => {
-                    return (format!("Data::{}", moduleWithVar),
-                            Line(format!("self.{}.get_pointer_field({}).get_data(std::ptr::null(), 0)",
-                                      member, offset)));
+                    return Ok((format!("Result<Data::{}>", moduleWithVar),
+                               Line(format!("self.{}.get_pointer_field({}).get_data(std::ptr::null(), 0)",
+                                         member, offset))));
                 }
                 Some((Type::List(ot1), _)) => {
-                    match ot1.get_element_type().which() {
+                    match try!(ot1.get_element_type()).which() {
                         None => { fail!("unsupported type") }
                         Some(Type::Struct(st)) => {
                             let theMod = scope_map.get(&st.get_type_id()).connect("::");
+                            let list_type = try!(list_list_type_param(node_map, scope_map, try!(ot1.get_element_type()),
+                                                                      isReader, "'a"));
                             if isReader {
-                                return (format!("StructList::{}<'a,{}::{}<'a>>", module, theMod, module),
-                                        Line(format!("StructList::{}::new(self.{}.get_pointer_field({}).get_list({}::STRUCT_SIZE.preferred_list_encoding, std::ptr::null()))",
-                                                     module, member, offset, theMod))
-                                        );
+                                return Ok((format!("Result<{}>", list_type),
+                                           Line(format!("self.{}.get_pointer_field({}).get_list({}::STRUCT_SIZE.preferred_list_encoding, {}).map(|l| StructList::{}::new(l))",
+                                                        member, offset, theMod, default_ptr, module))
+                                           ));
                             } else {
-                                return (format!("StructList::{}<'a,{}::{}<'a>>", module, theMod, module),
-                                        Line(format!("StructList::{}::new(self.{}.get_pointer_field({}).get_struct_list({}::STRUCT_SIZE, std::ptr::null()))",
-                                                     module, member, offset, theMod))
-                                        );
+                                return Ok((format!("Result<{}>", list_type),
+                                           Line(format!("self.{}.get_pointer_field({}).get_struct_list({}::STRUCT_SIZE, {}).map(|l| StructList::{}::new(l))",
+                                                        member, offset, theMod, default_ptr, module))
+                                           ));
                             }
                         }
                         Some(Type::Enum(e)) => {
                             let theMod = scope_map.get(&e.get_type_id()).connect("::");
                             let fullModuleName = format!("{}::Reader", theMod);
-                            return (format!("EnumList::{}<'a,{}>",module,fullModuleName),
-                                    Line(format!("EnumList::{}::new(self.{}.get_pointer_field({}).get_list(layout::TwoBytes, std::ptr::null()))",
-                                         module, member, offset)));
+                            return Ok((format!("Result<EnumList::{}<'a,{}>>",module,fullModuleName),
+                                       Line(format!("self.{}.get_pointer_field({}).get_list(layout::TwoBytes, {}).map(|l| EnumList::{}::new(l))",
+                                            member, offset, default_ptr, module))));
                         }
                         Some(Type::List(t1)) => {
-                            let type_param = list_list_type_param(scope_map, t1.get_element_type(), isReader, "'a");
-                            return (format!("ListList::{}<'a,{}>", module, type_param),
-                                    Line(format!("ListList::{}::new(self.{}.get_pointer_field({}).get_list(layout::Pointer, std::ptr::null()))",
-                                                 module, member, offset)))
+                            let type_param = try!(list_list_type_param(node_map, scope_map, try!(t1.get_element_type()),
+                                                                       isReader, "'a"));
+                            return Ok((format!("Result<ListList::{}<'a,{}>>", module, type_param),
+                                       Line(format!("self.{}.get_pointer_field({}).get_list(layout::Pointer, {}).map(|l| ListList::{}::new(l))",
+                                                    member, offset, default_ptr, module))))
                         }
                         Some(Type::Text(())) => {
-                            return (format!("TextList::{}<'a>", module),
-                                    Line(format!("TextList::{}::new(self.{}.get_pointer_field({}).get_list(layout::Pointer, std::ptr::null()))",
-                                                 module, member, offset)))
+                            return Ok((format!("Result<TextList::{}<'a>>", module),
+                                       Line(format!("self.{}.get_pointer_field({}).get_list(layout::Pointer, {}).map(|l| TextList::{}::new(l))",
+                                                    member, offset, default_ptr, module))))
                         }
                         Some(Type::Data(())) => {
-                            return (format!("DataList::{}<'a>", module),
-                                    Line(format!("DataList::{}::new(self.{}.get_pointer_field({}).get_list(layout::Pointer, std::ptr::null()))",
-                                                 module, member, offset)))
+                            return Ok((format!("Result<DataList::{}<'a>>", module),
+                                       Line(format!("self.{}.get_pointer_field({}).get_list(layout::Pointer, {}).map(|l| DataList::{}::new(l))",
+                                                    member, offset, default_ptr, module))))
+                        }
+                        Some(Type::Interface(_)) => {
+                            let list_type = try!(list_list_type_param(node_map, scope_map, try!(ot1.get_element_type()),
+                                                                      isReader, "'a"));
+                            return Ok((format!("Result<{}>", list_type),
+                                       Line(format!("self.{}.get_pointer_field({}).get_list(layout::Pointer, {}).map(|l| CapabilityList::{}::new(l))",
+                                                    member, offset, default_ptr, module))))
+                        }
+                        Some(Type::AnyPointer(_)) => {
+                            match try!(parameter_type_name(node_map, try!(ot1.get_element_type()))) {
+                                Some(param) => {
+                                    return Ok((format!("Result<ListList::{}<'a,{}>>", module, param),
+                                               Line(format!("self.{}.get_pointer_field({}).get_list(layout::Pointer, {}).map(|l| ListList::{}::new(l))",
+                                                            member, offset, default_ptr, module))))
+                                }
+                                None => {}
+                            }
+                            return Ok((format!("Result<AnyPointerList::{}<'a>>", module),
+                                       Line(format!("self.{}.get_pointer_field({}).get_list(layout::Pointer, {}).map(|l| AnyPointerList::{}::new(l))",
+                                                    member, offset, default_ptr, module))))
                         }
-                        Some(Type::Interface(_)) => {fail!("unimplemented") }
-                        Some(Type::AnyPointer(())) => {fail!("List(AnyPointer) is unsupported")}
                         Some(primType) => {
                             let typeStr = prim_type_str(primType);
                             let sizeStr = element_size_str(element_size(primType));
-                            return
-                                (format!("PrimitiveList::{}<'a,{}>", module, typeStr),
-                                 Line(format!("PrimitiveList::{}::new(self.{}.get_pointer_field({}).get_list(layout::{}, std::ptr::null()))",
-                                           module, member, offset, sizeStr)))
+                            return Ok(
+                                (format!("Result<PrimitiveList::{}<'a,{}>>", module, typeStr),
+                                 Line(format!("self.{}.get_pointer_field({}).get_list(layout::{}, {}).map(|l| PrimitiveList::{}::new(l))",
+                                           member, offset, sizeStr, default_ptr, module))))
                         }
                     }
                 }
                 Some((Type::Enum(en), _)) => {
                     let scope = scope_map.get(&en.get_type_id());
                     let theMod = scope.connect("::");
-                    return
+                    return Ok(
                         (format!("Option<{}::Reader>", theMod), // Enums don't have builders.
                          Branch(vec!(
                             Line(format!("FromPrimitive::from_u16(self.{}.get_data_field::<u16>({}))",
                                         member, offset))
-                              )));
+                              ))));
                 }
                 Some((Type::Struct(st), _)) => {
                     let theMod = scope_map.get(&st.get_type_id()).connect("::");
                     let middleArg = if isReader {format!("")} else {format!("{}::STRUCT_SIZE,", theMod)};
-                    return (format!("{}::{}", theMod, moduleWithVar),
-                            Line(format!("FromStruct{}::new(self.{}.get_pointer_field({}).get_struct({} std::ptr::null()))",
-                                      module, member, offset, middleArg)))
+                    let struct_type = try!(type_text(node_map, scope_map, try!(reg_field.get_type()),
+                                                     isReader, "'a"));
+                    return Ok((format!("Result<{}>", struct_type),
+                               Line(format!("self.{}.get_pointer_field({}).get_struct({} {}).map(|s| FromStruct{}::new(s))",
+                                         member, offset, middleArg, default_ptr, module))))
                 }
                 Some((Type::Interface(interface), _)) => {
-                    let theMod = scope_map.get(&interface.get_type_id()).connect("::");
-                    return (format!("{}::Client", theMod),
-                            Line(format!("FromClientHook::new(self.{}.get_pointer_field({}).get_capability())",
-                                         member, offset)));
+                    let client_type = try!(client_type_text(node_map, scope_map, interface, isReader, "'a"));
+                    return Ok((format!("Result<{}>", client_type),
+                               Line(format!("self.{}.get_pointer_field({}).get_capability().map(|c| FromClientHook::new(c))",
+                                            member, offset))));
                 }
-                Some((Type::AnyPointer(()), _)) => {
-                    return (format!("AnyPointer::{}<'a>", module),
-                            Line(format!("AnyPointer::{}::new(self.{}.get_pointer_field({}))",
-                                         module, member, offset)))
+                Some((Type::AnyPointer(_), _)) => {
+                    match try!(parameter_type_name(node_map, try!(reg_field.get_type()))) {
+                        Some(param) => {
+                            let getter = if isReader {
+                                format!("FromPointerReader::get_from_pointer(&self.reader.get_pointer_field({}), std::ptr::null())",
+                                        offset)
+                            } else {
+                                format!("FromPointerBuilder::get_from_pointer(self.builder.get_pointer_field({}), std::ptr::null())",
+                                        offset)
+                            };
+                            return Ok((format!("Result<{}>", param), Line(getter)))
+                        }
+                        None => {}
+                    }
+                    return Ok((format!("AnyPointer::{}<'a>", module),
+                               Line(format!("AnyPointer::{}::new(self.{}.get_pointer_field({}))",
+                                            module, member, offset))))
                 }
                 None => {
                     // XXX should probably silently ignore, instead.
@@ -444,7 +854,7 @@ fn getter_text (_node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
 
 fn zero_fields_of_group(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
                         node_id : u64
-                        ) -> FormattedText {
+                        ) -> capnp::Result<FormattedText> {
     use schema_capnp::{Node, Field, Type};
     match node_map.get(&node_id).which() {
         Some(Node::Struct(st)) => {
@@ -454,15 +864,15 @@ fn zero_fields_of_group(node_map : &collections::hashmap::HashMap<u64, schema_ca
                     Line(format!("self.builder.set_data_field::<u16>({}, 0);",
                                  st.get_discriminant_offset())));
             }
-            let fields = st.get_fields();
+            let fields = try!(st.get_fields());
             for ii in range(0, fields.size()) {
                 match fields[ii].which() {
                     None => {fail!()}
                     Some(Field::Group(group)) => {
-                        result.push(zero_fields_of_group(node_map, group.get_type_id()));
+                        result.push(try!(zero_fields_of_group(node_map, group.get_type_id())));
                     }
                     Some(Field::Slot(slot)) => {
-                        match slot.get_type().which(){
+                        match try!(slot.get_type()).which(){
                             Some(typ) => {
                                 match typ {
                                     Type::Void(()) => {}
@@ -484,7 +894,7 @@ fn zero_fields_of_group(node_map : &collections::hashmap::HashMap<u64, schema_ca
                                         if !result.contains(&line) { result.push(line) }
                                     }
                                     Type::Struct(_) | Type::List(_) | Type::Text(()) | Type::Data(()) |
-                                    Type::AnyPointer(()) |
+                                    Type::AnyPointer(_) |
                                     Type::Interface(_) // Is this the right thing to do for interfaces?
                                         => {
                                         let line = Line(format!("self.builder.get_pointer_field({}).clear();",
@@ -499,17 +909,22 @@ fn zero_fields_of_group(node_map : &collections::hashmap::HashMap<u64, schema_ca
                     }
                 }
             }
-            return Branch(result);
+            return Ok(Branch(result));
         }
         _ => { fail!("expected a struct") }
     }
 }
 
+// Also returns the initter of a field whose type is a type parameter, along
+// with the parameter's name. It goes in its own impl block, since it is
+// only available when the parameter is bound to a type that can be
+// initialized in place.
 fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
                   scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
                   discriminantOffset : u32,
                   styled_name : &str,
-                  field :&schema_capnp::Field::Reader) -> FormattedText {
+                  field :&schema_capnp::Field::Reader)
+                  -> capnp::Result<(FormattedText, Option<(String, FormattedText)>)> {
 
     use schema_capnp::*;
 
@@ -530,24 +945,30 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
                          discriminantValue as uint)));
     }
 
-    let mut setter_lifetime_param = "";
+    let mut setter_lifetime_param = "".to_string();
+    let mut setter_return_type = "";
+    let mut init_param : Option<String> = None;
+
+    // Pointer fields that can be disowned and adopted.
+    let mut orphan_offset : Option<uint> = None;
 
     let (maybe_reader_type, maybe_builder_type) : (Option<String>, Option<String>) = match field.which() {
         None => fail!("unrecognized field type"),
         Some(Field::Group(group)) => {
             let scope = scope_map.get(&group.get_type_id());
             let theMod = scope.connect("::");
+            let params = try!(type_parameters(node_map, group.get_type_id()));
 
-            initter_interior.push(zero_fields_of_group(node_map, group.get_type_id()));
+            initter_interior.push(try!(zero_fields_of_group(node_map, group.get_type_id())));
 
             initter_interior.push(Line(format!("FromStructBuilder::new(self.builder)")));
 
-            (None, Some(format!("{}::Builder<'a>", theMod)))
+            (None, Some(format!("{}::Builder<'a{}>", theMod, type_args_suffix(params.as_slice()))))
         }
         Some(Field::Slot(reg_field)) => {
-            fn common_case (typ: &str, offset : uint, reg_field : Field::Slot::Reader,
+            fn common_case (typ: &str, offset : uint, default_value : Value::Reader,
                             setter_interior : &mut Vec<FormattedText> ) -> (Option<String>, Option<String>) {
-                match prim_default(&reg_field.get_default_value()) {
+                match prim_default(&default_value) {
                     None => {
                         setter_interior.push(Line(format!("self.builder.set_data_field::<{}>({}, value);",
                                                           typ, offset)));
@@ -563,14 +984,16 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
 
 
             let offset = reg_field.get_offset() as uint;
+            let default_value = try!(reg_field.get_default_value());
+            let param_name = try!(parameter_type_name(node_map, try!(reg_field.get_type())));
 
-            match reg_field.get_type().which() {
+            match try!(reg_field.get_type()).which() {
                 Some(Type::Void(())) => {
                     setter_param = "_value".to_string();
                     (Some("()".to_string()), None)
                 }
                 Some(Type::Bool(())) => {
-                    match prim_default(&reg_field.get_default_value()) {
+                    match prim_default(&default_value) {
                         None => {
                             setter_interior.push(Line(format!("self.builder.set_bool_field({}, value);", offset)));
                         }
@@ -581,22 +1004,23 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
                     }
                     (Some("bool".to_string()), None)
                 }
-                Some(Type::Int8(())) => common_case("i8", offset, reg_field, &mut setter_interior),
-                Some(Type::Int16(())) => common_case("i16", offset, reg_field, &mut setter_interior),
-                Some(Type::Int32(())) => common_case("i32", offset, reg_field, &mut setter_interior),
-                Some(Type::Int64(())) => common_case("i64", offset, reg_field, &mut setter_interior),
-                Some(Type::Uint8(())) => common_case("u8", offset, reg_field, &mut setter_interior),
-                Some(Type::Uint16(())) => common_case("u16", offset, reg_field, &mut setter_interior),
-                Some(Type::Uint32(())) => common_case("u32", offset, reg_field, &mut setter_interior),
-                Some(Type::Uint64(())) => common_case("u64", offset, reg_field, &mut setter_interior),
-                Some(Type::Float32(())) => common_case("f32", offset, reg_field, &mut setter_interior),
-                Some(Type::Float64(())) => common_case("f64", offset, reg_field, &mut setter_interior),
+                Some(Type::Int8(())) => common_case("i8", offset, default_value, &mut setter_interior),
+                Some(Type::Int16(())) => common_case("i16", offset, default_value, &mut setter_interior),
+                Some(Type::Int32(())) => common_case("i32", offset, default_value, &mut setter_interior),
+                Some(Type::Int64(())) => common_case("i64", offset, default_value, &mut setter_interior),
+                Some(Type::Uint8(())) => common_case("u8", offset, default_value, &mut setter_interior),
+                Some(Type::Uint16(())) => common_case("u16", offset, default_value, &mut setter_interior),
+                Some(Type::Uint32(())) => common_case("u32", offset, default_value, &mut setter_interior),
+                Some(Type::Uint64(())) => common_case("u64", offset, default_value, &mut setter_interior),
+                Some(Type::Float32(())) => common_case("f32", offset, default_value, &mut setter_interior),
+                Some(Type::Float64(())) => common_case("f64", offset, default_value, &mut setter_interior),
                 Some(Type::Text(())) => {
                     setter_interior.push(Line(format!("self.builder.get_pointer_field({}).set_text(value);",
                                                       offset)));
                     initter_interior.push(Line(format!("self.builder.get_pointer_field({}).init_text(size)",
                                                        offset)));
                     initter_params.push("size : uint");
+                    orphan_offset = Some(offset);
                     (Some("Text::Reader".to_string()), Some("Text::Builder<'a>".to_string()))
                 }
                 Some(Type::Data(())) => {
@@ -605,15 +1029,18 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
                     initter_interior.push(Line(format!("self.builder.get_pointer_field({}).init_data(size)",
                                                        offset)));
                     initter_params.push("size : uint");
+                    orphan_offset = Some(offset);
                     (Some("Data::Reader".to_string()), Some("Data::Builder<'a>".to_string()))
                 }
                 Some(Type::List(ot1)) => {
                     setter_interior.push(
                         Line(format!("self.builder.get_pointer_field({}).set_list(&value.reader)",
                                      offset)));
+                    setter_return_type = " -> Result<()>";
+                    orphan_offset = Some(offset);
 
                     initter_params.push("size : uint");
-                    match ot1.get_element_type().which() {
+                    match try!(ot1.get_element_type()).which() {
                         None => fail!("unsupported type"),
                         Some(t1) => {
                             match t1 {
@@ -655,16 +1082,26 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
                                     let id = st.get_type_id();
                                     let scope = scope_map.get(&id);
                                     let theMod = scope.connect("::");
+                                    let element_type = try!(type_text(node_map, scope_map, try!(ot1.get_element_type()),
+                                                                      false, "'a"));
+                                    let builder_type = format!("StructList::Builder<'a,{}>", element_type);
 
-                                    initter_interior.push(Line(format!("StructList::Builder::<'a, {}::Builder<'a>>::new(", theMod)));
+                                    initter_interior.push(Line(format!("StructList::Builder::<'a, {}>::new(", element_type)));
                                     initter_interior.push(
                                        Indent(
                                           box Line(
                                              format!("self.builder.get_pointer_field({}).init_struct_list(size, {}::STRUCT_SIZE))",
                                                   offset, theMod))));
 
-                                    (Some(format!("StructList::Reader<'a,{}::Reader<'a>>", theMod)),
-                                     Some(format!("StructList::Builder<'a,{}::Builder<'a>>", theMod)))
+                                    if try!(is_generic_type(node_map, try!(ot1.get_element_type()))) {
+                                        //# The elements may be branded differently in the
+                                        //# reader that is being copied from.
+                                        setter_lifetime_param = "<R>".to_string();
+                                        (Some("StructList::Reader<'a,R>".to_string()), Some(builder_type))
+                                    } else {
+                                        (Some(format!("StructList::Reader<'a,{}::Reader<'a>>", theMod)),
+                                         Some(builder_type))
+                                    }
                                 }
                                 Type::Text(()) => {
                                     initter_interior.push(
@@ -681,20 +1118,60 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
                                      Some(format!("DataList::Builder<'a>")))
                                 }
                                 Type::List(t1) => {
-                                    let type_param = list_list_type_param(scope_map, t1.get_element_type(),
-                                                                          false, "'a");
+                                    let type_param = try!(list_list_type_param(node_map, scope_map, try!(t1.get_element_type()),
+                                                                               false, "'a"));
                                     initter_interior.push(
                                         Line(format!("ListList::Builder::<'a,{}>::new(self.builder.get_pointer_field({}).init_list(layout::Pointer,size))",
                                                      type_param, offset)));
 
-                                    setter_lifetime_param = "<'b>";
-
-                                    (Some(format!("ListList::Reader<'b, {}>",
-                                             list_list_type_param(scope_map, t1.get_element_type(), true, "'b"))),
-                                     Some(format!("ListList::Builder<'a, {}>", type_param)))
+                                    if try!(is_generic_type(node_map, try!(t1.get_element_type()))) {
+                                        setter_lifetime_param = "<'b, R>".to_string();
+                                        (Some("ListList::Reader<'b, R>".to_string()),
+                                         Some(format!("ListList::Builder<'a, {}>", type_param)))
+                                    } else {
+                                        setter_lifetime_param = "<'b>".to_string();
+                                        (Some(format!("ListList::Reader<'b, {}>",
+                                                 try!(list_list_type_param(node_map, scope_map, try!(t1.get_element_type()),
+                                                                           true, "'b")))),
+                                         Some(format!("ListList::Builder<'a, {}>", type_param)))
+                                    }
+                                }
+                                Type::AnyPointer(any) => {
+                                    match any.which() {
+                                        Some(Type::AnyPointer::Parameter(p)) => {
+                                            let param = try!(parameter_name(node_map, p.get_scope_id(),
+                                                                            p.get_parameter_index()));
+                                            initter_interior.push(
+                                                Line(format!("ListList::Builder::<'a,{}>::new(self.builder.get_pointer_field({}).init_list(layout::Pointer,size))",
+                                                             param, offset)));
+                                            setter_lifetime_param = "<'b, R>".to_string();
+                                            (Some("ListList::Reader<'b, R>".to_string()),
+                                             Some(format!("ListList::Builder<'a,{}>", param)))
+                                        }
+                                        _ => {
+                                            initter_interior.push(
+                                                Line(format!("AnyPointerList::Builder::<'a>::new(self.builder.get_pointer_field({}).init_list(layout::Pointer, size))", offset)));
+
+                                            (Some(format!("AnyPointerList::Reader")),
+                                             Some(format!("AnyPointerList::Builder<'a>")))
+                                        }
+                                    }
+                                }
+                                Type::Interface(i) => {
+                                    let client_type = try!(client_type_text(node_map, scope_map, i, false, "'a"));
+                                    initter_interior.push(
+                                        Line(format!("CapabilityList::Builder::<'a, {}>::new(self.builder.get_pointer_field({}).init_list(layout::Pointer, size))",
+                                                     client_type, offset)));
+
+                                    if try!(is_generic_type(node_map, try!(ot1.get_element_type()))) {
+                                        setter_lifetime_param = "<R>".to_string();
+                                        (Some("CapabilityList::Reader<'a, R>".to_string()),
+                                         Some(format!("CapabilityList::Builder<'a, {}>", client_type)))
+                                    } else {
+                                        (Some(format!("CapabilityList::Reader<'a, {}>", client_type)),
+                                         Some(format!("CapabilityList::Builder<'a, {}>", client_type)))
+                                    }
                                 }
-                                Type::AnyPointer(()) => {fail!("List(AnyPointer) not supported")}
-                                Type::Interface(_) => { fail!("unimplemented") }
                             }
                         }
                     }
@@ -711,19 +1188,46 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
                     let theMod = scope_map.get(&st.get_type_id()).connect("::");
                     setter_interior.push(
                         Line(format!("self.builder.get_pointer_field({}).set_struct(&value.struct_reader())", offset)));
+                    setter_return_type = " -> Result<()>";
+                    orphan_offset = Some(offset);
                     initter_interior.push(
                       Line(format!("FromStructBuilder::new(self.builder.get_pointer_field({}).init_struct({}::STRUCT_SIZE))",
                                    offset, theMod)));
-                    (Some(format!("{}::Reader", theMod)), Some(format!("{}::Builder<'a>", theMod)))
+                    let builder_type = try!(type_text(node_map, scope_map, try!(reg_field.get_type()), false, "'a"));
+                    if try!(is_generic_type(node_map, try!(reg_field.get_type()))) {
+                        setter_lifetime_param = "<R : ToStructReader<'a>>".to_string();
+                        (Some("R".to_string()), Some(builder_type))
+                    } else {
+                        (Some(format!("{}::Reader", theMod)), Some(builder_type))
+                    }
                 }
                 Some(Type::Interface(interface)) => {
                     let theMod = scope_map.get(&interface.get_type_id()).connect("::");
                     setter_interior.push(
                         Line(format!("self.builder.get_pointer_field({}).set_capability(value.client.hook);",
                                      offset)));
-                    (Some(format!("{}::Client",theMod)), None)
+                    let params = try!(type_parameters(node_map, interface.get_type_id()));
+                    if params.is_empty() {
+                        (Some(format!("{}::Client",theMod)), None)
+                    } else {
+                        let args : Vec<String> = range(0, params.len()).map(|ii| format!("C{}", ii)).collect();
+                        setter_lifetime_param = format!("<{}>", args.connect(", "));
+                        (Some(format!("{}::Client<{}>", theMod, args.connect(", "))), None)
+                    }
+                }
+                Some(Type::AnyPointer(_)) if param_name.is_some() => {
+                    setter_interior.push(
+                        Line(format!("AnyPointer::Builder::new(self.builder.get_pointer_field({})).set_as(value)",
+                                     offset)));
+                    setter_return_type = " -> Result<()>";
+                    initter_interior.push(
+                        Line(format!("InitPointerBuilder::init_pointer(self.builder.get_pointer_field({}), size)",
+                                     offset)));
+                    initter_params.push("size : uint");
+                    init_param = param_name.clone();
+                    (Some("AnyPointer::Reader".to_string()), param_name)
                 }
-                Some(Type::AnyPointer(())) => {
+                Some(Type::AnyPointer(_)) => {
                     initter_interior.push(Line(format!("let result = AnyPointer::Builder::new(self.builder.get_pointer_field({}));",
                                                offset)));
                     initter_interior.push(Line("result.clear();".to_string()));
@@ -735,11 +1239,13 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
         }
     };
     let mut result = Vec::new();
+    let mut param_initter = None;
     match maybe_reader_type {
         Some(reader_type) => {
             result.push(Line("#[inline]".to_string()));
-            result.push(Line(format!("pub fn set_{}{}(&self, {} : {}) \\{",
-                                     styled_name, setter_lifetime_param, setter_param, reader_type)));
+            result.push(Line(format!("pub fn set_{}{}(&self, {} : {}){} \\{",
+                                     styled_name, setter_lifetime_param, setter_param, reader_type,
+                                     setter_return_type)));
             result.push(Indent(box Branch(setter_interior)));
             result.push(Line("}".to_string()));
         }
@@ -747,16 +1253,50 @@ fn generate_setter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::
     }
     match maybe_builder_type {
         Some(builder_type) => {
-            result.push(Line("#[inline]".to_string()));
             let args = initter_params.connect(", ");
-            result.push(Line(format!("pub fn init_{}(&self, {}) -> {} \\{",
-                                     styled_name, args, builder_type)));
-            result.push(Indent(box Branch(initter_interior)));
-            result.push(Line("}".to_string()));
+            let initter = Branch(vec!(
+                Line("#[inline]".to_string()),
+                Line(format!("pub fn init_{}(&self, {}) -> {} \\{",
+                             styled_name, args, builder_type)),
+                Indent(box Branch(initter_interior)),
+                Line("}".to_string())));
+            match init_param {
+                Some(param) => param_initter = Some((param, initter)),
+                None => result.push(initter),
+            }
+
+            match orphan_offset {
+                Some(offset) => {
+                    let mut adopter_interior = Vec::new();
+                    if discriminantValue != Field::NO_DISCRIMINANT {
+                        adopter_interior.push(
+                            Line(format!("self.builder.set_data_field::<u16>({}, {});",
+                                         discriminantOffset as uint,
+                                         discriminantValue as uint)));
+                    }
+                    adopter_interior.push(
+                        Line(format!("self.builder.get_pointer_field({}).adopt(value.into_builder());",
+                                     offset)));
+
+                    result.push(Line("#[inline]".to_string()));
+                    result.push(Line(format!("pub fn adopt_{}(&self, value : Orphan<'a, {}>) \\{",
+                                             styled_name, builder_type)));
+                    result.push(Indent(box Branch(adopter_interior)));
+                    result.push(Line("}".to_string()));
+
+                    result.push(Line("#[inline]".to_string()));
+                    result.push(Line(format!("pub fn disown_{}(&self) -> Orphan<'a, {}> \\{",
+                                             styled_name, builder_type)));
+                    result.push(Indent(box Line(format!("Orphan::new(self.builder.get_pointer_field({}).disown())",
+                                                        offset))));
+                    result.push(Line("}".to_string()));
+                }
+                None => {}
+            }
         }
         None => {}
     }
-    return Branch(result);
+    return Ok((Branch(result), param_initter));
 }
 
 
@@ -765,8 +1305,9 @@ fn generate_union(node_map : &collections::hashmap::HashMap<u64, schema_capnp::N
                   scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
                   discriminant_offset : u32,
                   fields : &[schema_capnp::Field::Reader],
+                  params : &[String],
                   is_reader : bool)
-                  -> (FormattedText, FormattedText, FormattedText)
+                  -> capnp::Result<(FormattedText, FormattedText, FormattedText)>
 {
     use schema_capnp::*;
 
@@ -789,10 +1330,10 @@ fn generate_union(node_map : &collections::hashmap::HashMap<u64, schema_capnp::N
 
         let dvalue = field.get_discriminant_value() as uint;
 
-        let fieldName = field.get_name();
+        let fieldName = try!(field.get_name());
         let enumerantName = capitalize_first_letter(fieldName);
 
-        let (ty, get) = getter_text(node_map, scope_map, field, is_reader);
+        let (ty, get) = try!(getter_text(node_map, scope_map, field, is_reader));
 
         getter_interior.push(Branch(vec!(
                     Line(format!("{} => \\{", dvalue)),
@@ -808,10 +1349,15 @@ fn generate_union(node_map : &collections::hashmap::HashMap<u64, schema_capnp::N
                 new_ty_param(&mut ty_params)
             }
             Some(Field::Slot(reg_field)) => {
-                match reg_field.get_type().which() {
+                let generic = try!(is_generic_type(node_map, try!(reg_field.get_type())));
+                match try!(reg_field.get_type()).which() {
                     Some(Type::Text(())) | Some(Type::Data(())) |
                     Some(Type::List(_)) | Some(Type::Struct(_)) |
-                    Some(Type::AnyPointer(())) => {
+                    Some(Type::AnyPointer(_)) => {
+                        ty_args.push(ty);
+                        new_ty_param(&mut ty_params)
+                    }
+                    Some(Type::Interface(_)) if generic => {
                         ty_args.push(ty);
                         new_ty_param(&mut ty_params)
                     }
@@ -852,7 +1398,7 @@ fn generate_union(node_map : &collections::hashmap::HashMap<u64, schema_capnp::N
     let concrete_type =
             format!("Which{}{}",
                     if is_reader {"Reader"} else {"Builder"},
-                    if ty_params.len() > 0 {"<'a>"} else {""});
+                    if ty_params.len() > 0 {format!("<'a{}>", type_args_suffix(params))} else {"".to_string()});
 
     let typedef = Line(format!("pub type {} = Which{};",
                                concrete_type,
@@ -870,13 +1416,13 @@ fn generate_union(node_map : &collections::hashmap::HashMap<u64, schema_capnp::N
 
     // TODO set_which() for builders?
 
-    return (result, getter_result, typedef);
+    return Ok((result, getter_result, typedef));
 }
 
 fn generate_haser(discriminant_offset : u32,
                   styled_name : &str,
                   field :&schema_capnp::Field::Reader,
-                  is_reader : bool) -> FormattedText {
+                  is_reader : bool) -> capnp::Result<FormattedText> {
 
     use schema_capnp::*;
 
@@ -895,10 +1441,10 @@ fn generate_haser(discriminant_offset : u32,
     match field.which() {
         None | Some(Field::Group(_)) => {},
         Some(Field::Slot(reg_field)) => {
-            match reg_field.get_type().which() {
+            match try!(reg_field.get_type()).which() {
                 Some(Type::Text(())) | Some(Type::Data(())) |
                     Some(Type::List(_)) | Some(Type::Struct(_)) |
-                    Some(Type::AnyPointer(())) => {
+                    Some(Type::AnyPointer(_)) => {
                     interior.push(
                         Line(format!("!self.{}.get_pointer_field({}).is_null()",
                                      member, reg_field.get_offset())));
@@ -913,53 +1459,56 @@ fn generate_haser(discriminant_offset : u32,
         }
     }
 
-    Branch(result)
+    Ok(Branch(result))
 }
 
-fn generate_pipeline_getter(_node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
+fn generate_pipeline_getter(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
                             scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
-                            field : schema_capnp::Field::Reader) -> FormattedText {
+                            field : schema_capnp::Field::Reader) -> capnp::Result<FormattedText> {
     use schema_capnp::{Field, Type};
 
-    let name = field.get_name();
+    let name = try!(field.get_name());
 
     match field.which() {
         None => fail!("unrecognized field type"),
         Some(Field::Group(group)) => {
             let theMod = scope_map.get(&group.get_type_id()).connect("::");
-            return Branch(vec!(Line(format!("pub fn get_{}(&self) -> {}::Pipeline \\{",
-                                            camel_to_snake_case(name),
-                                            theMod)),
-                               Indent(box Line("FromTypelessPipeline::new(self._typeless.noop())".to_string())),
-                               Line("}".to_string())));
+            let params = try!(type_parameters(node_map, group.get_type_id()));
+            return Ok(Branch(vec!(Line(format!("pub fn get_{}(&self) -> {}::Pipeline{} \\{",
+                                               camel_to_snake_case(name),
+                                               theMod, pipeline_args_text(params.as_slice()))),
+                                  Indent(box Line("FromTypelessPipeline::new(self._typeless.noop())".to_string())),
+                                  Line("}".to_string()))));
         }
         Some(Field::Slot(reg_field)) => {
-            match reg_field.get_type().which() {
+            match try!(reg_field.get_type()).which() {
                 None => fail!("unrecognized type"),
                 Some(Type::Struct(st)) => {
                     let theMod = scope_map.get(&st.get_type_id()).connect("::");
-                    return Branch(vec!(
-                        Line(format!("pub fn get_{}(&self) -> {}::Pipeline \\{",
+                    let args = try!(brand_arguments(node_map, scope_map, st.get_type_id(), try!(st.get_brand()),
+                                                    true, "'static", false));
+                    return Ok(Branch(vec!(
+                        Line(format!("pub fn get_{}(&self) -> {}::Pipeline{} \\{",
                                      camel_to_snake_case(name),
-                                     theMod)),
+                                     theMod, pipeline_args_text(args.as_slice()))),
                         Indent(box Line(
                             format!("FromTypelessPipeline::new(self._typeless.get_pointer_field({}))",
                                     reg_field.get_offset()))),
-                        Line("}".to_string())));
+                        Line("}".to_string()))));
                 }
                 Some(Type::Interface(interface)) => {
-                    let theMod = scope_map.get(&interface.get_type_id()).connect("::");
-                    return Branch(vec!(
-                        Line(format!("pub fn get_{}(&self) -> {}::Client \\{",
+                    let client_type = try!(client_type_text(node_map, scope_map, interface, true, "'static"));
+                    return Ok(Branch(vec!(
+                        Line(format!("pub fn get_{}(&self) -> {} \\{",
                                      camel_to_snake_case(name),
-                                     theMod)),
+                                     client_type)),
                         Indent(box Line(
                             format!("FromClientHook::new(self._typeless.get_pointer_field({}).as_cap())",
                                     reg_field.get_offset()))),
-                        Line("}".to_string())));
+                        Line("}".to_string()))));
                 }
                 _ => {
-                    return Branch(Vec::new());
+                    return Ok(Branch(Vec::new()));
                 }
             }
         }
@@ -970,18 +1519,18 @@ fn generate_pipeline_getter(_node_map : &collections::hashmap::HashMap<u64, sche
 fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::Node::Reader>,
                  scope_map : &collections::hashmap::HashMap<u64, Vec<String>>,
                  node_id : u64,
-                 node_name: &str) -> FormattedText {
+                 node_name: &str) -> capnp::Result<FormattedText> {
     use schema_capnp::*;
 
     let mut output: Vec<FormattedText> = Vec::new();
     let mut nested_output: Vec<FormattedText> = Vec::new();
 
     let node_reader = node_map.get(&node_id);
-    let nested_nodes = node_reader.get_nested_nodes();
+    let nested_nodes = try!(node_reader.get_nested_nodes());
     for ii in range(0, nested_nodes.size()) {
         let id = nested_nodes[ii].get_id();
-        nested_output.push(generate_node(node_map, scope_map,
-                                         id, scope_map.get(&id).last().unwrap().as_slice()));
+        nested_output.push(try!(generate_node(node_map, scope_map,
+                                              id, scope_map.get(&id).last().unwrap().as_slice())));
     }
 
     match node_reader.which() {
@@ -996,6 +1545,7 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
 
             let mut preamble = Vec::new();
             let mut builder_members = Vec::new();
+            let mut param_init_impls = Vec::new();
             let mut reader_members = Vec::new();
             let mut union_fields = Vec::new();
             let mut which_enums = Vec::new();
@@ -1012,8 +1562,19 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
             let discriminantCount = struct_reader.get_discriminant_count();
             let discriminant_offset = struct_reader.get_discriminant_offset();
 
+            let params = try!(type_parameters(node_map, node_id));
+            let ta = type_args_suffix(params.as_slice());
+            let mut reader_bounds = String::new();
+            let mut builder_bounds = String::new();
+            for param in params.iter() {
+                reader_bounds.push_str(format!(", {} : FromPointerReader<'a>", param).as_slice());
+                builder_bounds.push_str(format!(", {} : FromPointerBuilder<'a>", param).as_slice());
+            }
+
             preamble.push(generate_import_statements());
             preamble.push(BlankLine);
+            preamble.push(try!(schema_text(node_reader)));
+            preamble.push(BlankLine);
 
 
             if !isGroup {
@@ -1029,18 +1590,26 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                 preamble.push(BlankLine);
             }
 
-            let fields = struct_reader.get_fields();
+            let fields = try!(struct_reader.get_fields());
             for ii in range(0, fields.size()) {
                 let field = fields[ii];
-                let name = field.get_name();
+                let name = try!(field.get_name());
                 let styled_name = camel_to_snake_case(name);
 
                 let discriminantValue = field.get_discriminant_value();
                 let isUnionField = discriminantValue != Field::NO_DISCRIMINANT;
 
+                match try!(generate_default_value(&field)) {
+                    Some(default_value) => {
+                        preamble.push(default_value);
+                        preamble.push(BlankLine);
+                    }
+                    None => {}
+                }
+
                 if !isUnionField {
-                    pipeline_impl_interior.push(generate_pipeline_getter(node_map, scope_map, field));
-                    let (ty, get) = getter_text(node_map, scope_map, &field, true);
+                    pipeline_impl_interior.push(try!(generate_pipeline_getter(node_map, scope_map, field)));
+                    let (ty, get) = try!(getter_text(node_map, scope_map, &field, true));
 
                     reader_members.push(
                         Branch(vec!(
@@ -1049,7 +1618,7 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                             Indent(box get),
                             Line("}".to_string()))));
 
-                    let (tyB, getB) = getter_text(node_map, scope_map, &field, false);
+                    let (tyB, getB) = try!(getter_text(node_map, scope_map, &field, false));
 
                     builder_members.push(
                         Branch(vec!(
@@ -1062,18 +1631,33 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                     union_fields.push(field);
                 }
 
-                builder_members.push(generate_setter(node_map, scope_map,
-                                                    discriminant_offset,
-                                                    styled_name.as_slice(), &field));
+                let (setter, param_initter) = try!(generate_setter(node_map, scope_map,
+                                                                   discriminant_offset,
+                                                                   styled_name.as_slice(), &field));
+                builder_members.push(setter);
+                match param_initter {
+                    Some((param, initter)) => {
+                        let mut bounds = String::new();
+                        for p in params.iter() {
+                            let bound = if *p == param { "InitPointerBuilder" } else { "FromPointerBuilder" };
+                            bounds.push_str(format!(", {} : {}<'a>", p, bound).as_slice());
+                        }
+                        param_init_impls.push(Branch(vec!(
+                            Line(format!("impl <'a{}> Builder<'a{}> \\{", bounds, ta)),
+                            Indent(box initter),
+                            Line("}".to_string()))));
+                    }
+                    None => {}
+                }
 
-                reader_members.push(generate_haser(discriminant_offset, styled_name.as_slice(), &field, true));
-                builder_members.push(generate_haser(discriminant_offset, styled_name.as_slice(), &field, false));
+                reader_members.push(try!(generate_haser(discriminant_offset, styled_name.as_slice(), &field, true)));
+                builder_members.push(try!(generate_haser(discriminant_offset, styled_name.as_slice(), &field, false)));
 
                 match field.which() {
                     Some(Field::Group(group)) => {
                         let id = group.get_type_id();
-                        let text = generate_node(node_map, scope_map,
-                                                 id, scope_map.get(&id).last().unwrap().as_slice());
+                        let text = try!(generate_node(node_map, scope_map,
+                                                      id, scope_map.get(&id).last().unwrap().as_slice()));
                         nested_output.push(text);
                     }
                     _ => { }
@@ -1083,77 +1667,166 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
 
             if discriminantCount > 0 {
                 let (which_enums1, union_getter, typedef) =
-                    generate_union(node_map, scope_map,
-                                   discriminant_offset, union_fields.as_slice(), true);
+                    try!(generate_union(node_map, scope_map,
+                                        discriminant_offset, union_fields.as_slice(), params.as_slice(), true));
                 which_enums.push(which_enums1);
                 which_enums.push(typedef);
                 reader_members.push(union_getter);
 
                 let (_, union_getter, typedef) =
-                    generate_union(node_map, scope_map,
-                                   discriminant_offset, union_fields.as_slice(), false);
+                    try!(generate_union(node_map, scope_map,
+                                        discriminant_offset, union_fields.as_slice(), params.as_slice(), false));
                 which_enums.push(typedef);
                 builder_members.push(union_getter);
             }
 
-            let builderStructSize =
+            let builderTraits =
                 if isGroup { Branch(Vec::new()) }
                 else {
                     Branch(vec!(
-                        Line("impl <'a> layout::HasStructSize for Builder<'a> {".to_string()),
+                        Line(format!("impl <'a{}> layout::HasStructSize for Builder<'a{}> \\{", ta, ta)),
                         Indent(box Branch(vec!(Line("#[inline]".to_string()),
-                                            Line("fn struct_size(_unused_self : Option<Builder>) -> layout::StructSize { STRUCT_SIZE }".to_string())))),
-                       Line("}".to_string())))
+                                            Line(format!("fn struct_size(_unused_self : Option<Builder<'a{}>>) -> layout::StructSize \\{ STRUCT_SIZE \\}", ta))))),
+                        Line("}".to_string()),
+                        BlankLine,
+                        Line(format!("impl <'a{}> FromOrphanBuilder<'a> for Builder<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line("fn init_orphan(orphanage : &Orphanage<'a>, _size : uint) -> layout::OrphanBuilder<'a> {".to_string()),
+                            Indent(box Line("layout::OrphanBuilder::init_struct(orphanage.arena(), STRUCT_SIZE)".to_string())),
+                            Line("}".to_string()),
+                            Line(format!("fn get_from_orphan(builder : &mut layout::OrphanBuilder<'a>) -> Result<Builder<'a{}>> \\{", ta)),
+                            Indent(box Line("builder.as_struct(STRUCT_SIZE).map(|s| FromStructBuilder::new(s))".to_string())),
+                            Line("}".to_string())))),
+                        Line("}".to_string()),
+                        BlankLine,
+                        Line(format!("impl <'a{}> FromPointerBuilder<'a> for Builder<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line(format!("fn get_from_pointer(builder : layout::PointerBuilder<'a>, default_value : *Word) -> Result<Builder<'a{}>> \\{", ta)),
+                            Indent(box Line("builder.get_struct(STRUCT_SIZE, default_value).map(|s| FromStructBuilder::new(s))".to_string())),
+                            Line("}".to_string())))),
+                        Line("}".to_string()),
+                        BlankLine,
+                        Line(format!("impl <'a{}> InitPointerBuilder<'a> for Builder<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line(format!("fn init_pointer(builder : layout::PointerBuilder<'a>, _size : uint) -> Builder<'a{}> \\{", ta)),
+                            Indent(box Line("FromStructBuilder::new(builder.init_struct(STRUCT_SIZE))".to_string())),
+                            Line("}".to_string())))),
+                        Line("}".to_string())))
+            };
+
+            let readerTraits =
+                if isGroup { Branch(Vec::new()) }
+                else {
+                    Branch(vec!(
+                        Line(format!("impl <'a{}> FromPointerReader<'a> for Reader<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line(format!("fn get_from_pointer(reader : &layout::PointerReader<'a>, default_value : *Word) -> Result<Reader<'a{}>> \\{", ta)),
+                            Indent(box Line("reader.get_struct(default_value).map(|s| FromStructReader::new(s))".to_string())),
+                            Line("}".to_string())))),
+                        Line("}".to_string()),
+                        BlankLine,
+                        Line(format!("impl <'a{}> Reader<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line(format!("pub fn equals(&self, other : &Reader<'a{}>) -> Result<bool> \\{", ta)),
+                            Indent(box Line("self.reader.equals(&other.reader)".to_string())),
+                            Line("}".to_string()),
+                            Line("pub fn stable_hash(&self) -> Result<u64> {".to_string()),
+                            Indent(box Line("self.reader.stable_hash()".to_string())),
+                            Line("}".to_string())))),
+                        Line("}".to_string()),
+                        BlankLine,
+                        // The trait impls cannot report errors. A reader that
+                        // fails to read is equal only to itself, and hashes to
+                        // zero, so that Eq stays an equivalence relation.
+                        Line(format!("impl <'a{}> std::cmp::Eq for Reader<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line(format!("fn eq(&self, other : &Reader<'a{}>) -> bool \\{", ta)),
+                            Indent(box Branch(vec!(
+                                Line("match self.equals(other) {".to_string()),
+                                Indent(box Branch(vec!(
+                                    Line("Ok(result) => result,".to_string()),
+                                    Line("Err(_) => self.reader.is_same(&other.reader),".to_string())))),
+                                Line("}".to_string())))),
+                            Line("}".to_string())))),
+                        Line("}".to_string()),
+                        BlankLine,
+                        Line(format!("impl <'a{}> std::cmp::TotalEq for Reader<'a{}> \\{\\}", ta, ta)),
+                        BlankLine,
+                        Line(format!("impl <'a{}, S : std::io::Writer> std::hash::Hash<S> for Reader<'a{}> \\{", ta, ta)),
+                        Indent(box Branch(vec!(
+                            Line("fn hash(&self, state : &mut S) {".to_string()),
+                            Indent(box Branch(vec!(
+                                Line("use std::hash::Hash;".to_string()),
+                                Line("self.stable_hash().unwrap_or(0).hash(state)".to_string())))),
+                            Line("}".to_string())))),
+                        Line("}".to_string()),
+                        BlankLine))
+            };
+
+            //# A builder of a generic struct can be viewed as a reader with
+            //# the type arguments in reader form.
+            let as_reader_signature = if params.is_empty() {
+                "pub fn as_reader(&self) -> Reader<'a> {".to_string()
+            } else {
+                let reader_params : Vec<String> = params.iter().map(|p| format!("{}R", p)).collect();
+                format!("pub fn as_reader<{}>(&self) -> Reader<'a{}> \\{",
+                        reader_params.connect(", "), type_args_suffix(reader_params.as_slice()))
             };
 
+            let pipeline_params = pipeline_args_text(params.as_slice());
+            let pipeline_impl = if params.is_empty() { "impl".to_string() }
+                                else { format!("impl {}", pipeline_params) };
+
             let accessors = vec!(
                 Branch(preamble),
-                Line("pub struct Reader<'a> { reader : layout::StructReader<'a> }".to_string()),
+                Line(format!("pub struct Reader<'a{}> \\{ reader : layout::StructReader<'a> \\}", ta)),
                 BlankLine,
-                Line("impl <'a> layout::FromStructReader<'a> for Reader<'a> {".to_string()),
+                Line(format!("impl <'a{}> layout::FromStructReader<'a> for Reader<'a{}> \\{", ta, ta)),
                 Indent(
                     box Branch(vec!(
-                        Line("fn new(reader: layout::StructReader<'a>) -> Reader<'a> {".to_string()),
+                        Line(format!("fn new(reader: layout::StructReader<'a>) -> Reader<'a{}> \\{", ta)),
                         Indent(box Line("Reader { reader : reader }".to_string())),
                         Line("}".to_string())))),
                 Line("}".to_string()),
                 BlankLine,
-                Line("impl <'a> layout::ToStructReader<'a> for Reader<'a> {".to_string()),
+                Line(format!("impl <'a{}> layout::ToStructReader<'a> for Reader<'a{}> \\{", ta, ta)),
                 Indent(box Line("fn struct_reader(&self) -> layout::StructReader<'a> { self.reader }".to_string())),
                 Line("}".to_string()),
                 BlankLine,
-                Line("impl <'a> Reader<'a> {".to_string()),
+                Line(format!("impl <'a{}> Reader<'a{}> \\{", reader_bounds, ta)),
                 Indent(box Branch(reader_members)),
                 Line("}".to_string()),
                 BlankLine,
-                Line("pub struct Builder<'a> { builder : layout::StructBuilder<'a> }".to_string()),
-                builderStructSize,
-                Line("impl <'a> layout::FromStructBuilder<'a> for Builder<'a> {".to_string()),
+                readerTraits,
+                Line(format!("pub struct Builder<'a{}> \\{ builder : layout::StructBuilder<'a> \\}", ta)),
+                builderTraits,
+                Line(format!("impl <'a{}> layout::FromStructBuilder<'a> for Builder<'a{}> \\{", ta, ta)),
                 Indent(
                     box Branch(vec!(
-                        Line("fn new(builder : layout::StructBuilder<'a>) -> Builder<'a> {".to_string()),
+                        Line(format!("fn new(builder : layout::StructBuilder<'a>) -> Builder<'a{}> \\{", ta)),
                         Indent(box Line("Builder { builder : builder }".to_string())),
                         Line("}".to_string())))),
                 Line("}".to_string()),
 
-                Line("impl <'a> Builder<'a> {".to_string()),
+                Line(format!("impl <'a{}> Builder<'a{}> \\{", builder_bounds, ta)),
                 Indent(
                     box Branch(vec!(
-                        Line("pub fn as_reader(&self) -> Reader<'a> {".to_string()),
+                        Line(as_reader_signature),
                         Indent(box Line("FromStructReader::new(self.builder.as_reader())".to_string())),
                         Line("}".to_string())))),
                 Indent(box Branch(builder_members)),
                 Line("}".to_string()),
                 BlankLine,
-                Line("pub struct Pipeline { _typeless : AnyPointer::Pipeline }".to_string()),
-                Line("impl FromTypelessPipeline for Pipeline {".to_string()),
+                Branch(param_init_impls),
+                Line(format!("pub struct Pipeline{} \\{ _typeless : AnyPointer::Pipeline \\}", pipeline_params)),
+                Line(format!("{} FromTypelessPipeline for Pipeline{} \\{", pipeline_impl, pipeline_params)),
                 Indent(
                     box Branch(vec!(
-                        Line("fn new(typeless : AnyPointer::Pipeline) -> Pipeline {".to_string()),
+                        Line(format!("fn new(typeless : AnyPointer::Pipeline) -> Pipeline{} \\{", pipeline_params)),
                         Indent(box Line("Pipeline { _typeless : typeless }".to_string())),
                         Line("}".to_string())))),
                 Line("}".to_string()),
-                Line("impl Pipeline {".to_string()),
+                Line(format!("{} Pipeline{} \\{", pipeline_impl, pipeline_params)),
                 Indent(box Branch(pipeline_impl_interior)),
                 Line("}".to_string()),
                 );
@@ -1170,15 +1843,23 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
             output.push(BlankLine);
             output.push(Line(format!("pub mod {} \\{", *names.last().unwrap())));
 
-            output.push(Indent(box Line("use capnp::list::{ToU16};".to_string())));
+            output.push(Indent(box Branch(vec!(
+                Line("use std;".to_string()),
+                Line("use capnp::layout;".to_string()),
+                Line("use capnp::layout::FromStructReader;".to_string()),
+                Line("use capnp::list::{ToU16};".to_string()),
+                Line("use capnp::Result;".to_string()),
+                Line("use capnp::schema_capnp;".to_string()),
+                BlankLine,
+                try!(schema_text(node_reader))))));
             output.push(BlankLine);
 
             let mut members = Vec::new();
-            let enumerants = enumReader.get_enumerants();
+            let enumerants = try!(enumReader.get_enumerants());
             for ii in range(0, enumerants.size()) {
                 let enumerant = enumerants[ii];
                 members.push(
-                    Line(format!("{} = {},", capitalize_first_letter(enumerant.get_name()),
+                    Line(format!("{} = {},", capitalize_first_letter(try!(enumerant.get_name())),
                               ii)));
             }
 
@@ -1210,16 +1891,30 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
             let mut dispatch_arms = Vec::new();
 
             mod_interior.push(Line ("#![allow(unused_variable)]".to_string()));
+            mod_interior.push(Line("use std;".to_string()));
             mod_interior.push(Line("use capnp::AnyPointer;".to_string()));
             mod_interior.push(
                 Line("use capnp::capability::{ClientHook, FromClientHook, FromServer, Request, ServerHook};".to_string()));
             mod_interior.push(Line("use capnp::capability;".to_string()));
+            mod_interior.push(Line("use capnp::common::Word;".to_string()));
+            mod_interior.push(Line("use capnp::layout;".to_string()));
+            mod_interior.push(Line("use capnp::layout::FromStructReader;".to_string()));
+            mod_interior.push(Line("use capnp::list::{FromPointerReader, FromPointerBuilder};".to_string()));
+            mod_interior.push(Line("use capnp::Result;".to_string()));
+            mod_interior.push(Line("use capnp::schema_capnp;".to_string()));
             mod_interior.push(BlankLine);
+            mod_interior.push(try!(schema_text(node_reader)));
+            mod_interior.push(BlankLine);
+
+            let params = try!(type_parameters(node_map, node_id));
+            let client_params = pipeline_args_text(params.as_slice());
+            let client_impl = if params.is_empty() { "impl".to_string() }
+                              else { format!("impl {}", client_params) };
 
-            let methods = interface.get_methods();
+            let methods = try!(interface.get_methods());
             for ordinal in range(0, methods.size()) {
                 let method = methods[ordinal];
-                let name = method.get_name();
+                let name = try!(method.get_name());
 
                 method.get_code_order();
                 let params_id = method.get_param_struct_type();
@@ -1227,8 +1922,8 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                 let params_name = if params_node.get_scope_id() == 0 {
                     let params_name = format!("{}Params", capitalize_first_letter(name));
 
-                    nested_output.push(generate_node(node_map, scope_map,
-                                                     params_id, params_name.as_slice()));
+                    nested_output.push(try!(generate_node(node_map, scope_map,
+                                                          params_id, params_name.as_slice())));
                     params_name
                 } else {
                     scope_map.get(&params_node.get_id()).connect("::")
@@ -1238,8 +1933,8 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                 let results_node = node_map.get(&results_id);
                 let results_name = if results_node.get_scope_id() == 0 {
                     let results_name = format!("{}Results", capitalize_first_letter(name));
-                    nested_output.push(generate_node(node_map, scope_map,
-                                                     results_id, results_name.as_slice() ));
+                    nested_output.push(try!(generate_node(node_map, scope_map,
+                                                          results_id, results_name.as_slice())));
                     results_name
                 } else {
                     scope_map.get(&results_node.get_id()).connect("::")
@@ -1250,33 +1945,58 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                             "{} => server.{}(capability::internal_get_typed_context(context)),",
                             ordinal, camel_to_snake_case(name))));
 
+                let param_brand = try!(method.get_param_brand());
+                let result_brand = try!(method.get_result_brand());
+
+                //# The server is not generic, so parameters of the interface
+                //# are AnyPointer from its point of view.
+                let context_params = try!(brand_arguments(node_map, scope_map, params_id, param_brand,
+                                                          true, "'a", true));
+                let context_results = try!(brand_arguments(node_map, scope_map, results_id, result_brand,
+                                                           false, "'a", true));
                 mod_interior.push(
                     Line(format!(
-                            "pub type {}Context<'a> = capability::CallContext<{}::Reader<'a>, {}::Builder<'a>>;",
-                            capitalize_first_letter(name), params_name, results_name)));
+                            "pub type {}Context<'a> = capability::CallContext<{}::Reader<'a{}>, {}::Builder<'a{}>>;",
+                            capitalize_first_letter(name), params_name, type_args_suffix(context_params.as_slice()),
+                            results_name, type_args_suffix(context_results.as_slice()))));
                 server_interior.push(
                     Line(format!(
                             "fn {}(&mut self, {}Context);",
                             camel_to_snake_case(name), capitalize_first_letter(name)
                             )));
 
-                client_impl_interior.push(
-                    Line(format!("pub fn {}_request(&self) -> Request<{}::Builder,{}::Reader,{}::Pipeline> \\{",
-                                 camel_to_snake_case(name), params_name, results_name, results_name)));
+                let request_params = try!(brand_arguments(node_map, scope_map, params_id, param_brand,
+                                                          false, "'a", false));
+                let request_results = try!(brand_arguments(node_map, scope_map, results_id, result_brand,
+                                                           true, "'a", false));
+                let pipeline_results = try!(brand_arguments(node_map, scope_map, results_id, result_brand,
+                                                            true, "'static", false));
+                if request_params.is_empty() && request_results.is_empty() {
+                    client_impl_interior.push(
+                        Line(format!("pub fn {}_request(&self) -> Request<{}::Builder,{}::Reader,{}::Pipeline> \\{",
+                                     camel_to_snake_case(name), params_name, results_name, results_name)));
+                } else {
+                    client_impl_interior.push(
+                        Line(format!("pub fn {}_request<'a>(&self) -> Request<{}::Builder<'a{}>,{}::Reader<'a{}>,{}::Pipeline{}> \\{",
+                                     camel_to_snake_case(name),
+                                     params_name, type_args_suffix(request_params.as_slice()),
+                                     results_name, type_args_suffix(request_results.as_slice()),
+                                     results_name, pipeline_args_text(pipeline_results.as_slice()))));
+                }
 
                 client_impl_interior.push(Indent(
                         box Line(format!("self.client.new_call(0x{:x}, {}, None)", node_id, ordinal))));
                 client_impl_interior.push(Line("}".to_string()));
 
-                method.get_annotations();
+                try!(method.get_annotations());
             }
 
             let mut base_dispatch_arms = Vec::new();
             let server_base = {
                 let mut base_traits = Vec::new();
-                let extends = interface.get_extends();
-                for ii in range(0, extends.size()) {
-                    let base_id = extends[ii];
+                let superclasses = try!(interface.get_superclasses());
+                for ii in range(0, superclasses.size()) {
+                    let base_id = superclasses[ii].get_id();
                     let the_mod = scope_map.get(&base_id).connect("::");
                     base_dispatch_arms.push(
                         Line(format!(
@@ -1284,27 +2004,48 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                                 base_id, the_mod)));
                     base_traits.push(format!("{}::Server", the_mod));
                 }
-                if extends.size() > 0 { format!(": {}", base_traits.as_slice().connect(" + ")) }
+                if superclasses.size() > 0 { format!(": {}", base_traits.as_slice().connect(" + ")) }
                 else { "".to_string() }
             };
 
 
             mod_interior.push(BlankLine);
-            mod_interior.push(Line("pub struct Client{ pub client : capability::Client }".to_string()));
+            mod_interior.push(Line(format!("pub struct Client{}\\{ pub client : capability::Client \\}", client_params)));
             mod_interior.push(
                 Branch(vec!(
-                    Line("impl FromClientHook for Client {".to_string()),
-                    Indent(box Line("fn new(hook : Box<ClientHook:Send>) -> Client {".to_string())),
+                    Line(format!("{} FromClientHook for Client{} \\{", client_impl, client_params)),
+                    Indent(box Line(format!("fn new(hook : Box<ClientHook:Send>) -> Client{} \\{", client_params))),
                     Indent(box Indent(box Line("Client { client : capability::Client::new(hook) }".to_string()))),
                     Indent(box Line("}".to_string())),
                     Line("}".to_string()))));
 
+            mod_interior.push(
+                Branch(vec!(
+                    Line(format!("impl <'a{}> FromPointerReader<'a> for Client{} \\{",
+                                 type_args_suffix(params.as_slice()), client_params)),
+                    Indent(box Line(format!("fn get_from_pointer(reader : &layout::PointerReader<'a>, _default_value : *Word) -> Result<Client{}> \\{", client_params))),
+                    Indent(box Indent(box Line("reader.get_capability().map(|c| FromClientHook::new(c))".to_string()))),
+                    Indent(box Line("}".to_string())),
+                    Line("}".to_string()))));
+
+            mod_interior.push(
+                Branch(vec!(
+                    // Capabilities cannot be initialized in place, so there
+                    // is no InitPointerBuilder impl.
+                    Line(format!("impl <'a{}> FromPointerBuilder<'a> for Client{} \\{",
+                                 type_args_suffix(params.as_slice()), client_params)),
+                    Indent(box Line(format!("fn get_from_pointer(builder : layout::PointerBuilder<'a>, _default_value : *Word) -> Result<Client{}> \\{", client_params))),
+                    Indent(box Indent(box Line("builder.get_capability().map(|c| FromClientHook::new(c))".to_string()))),
+                    Indent(box Line("}".to_string())),
+                    Line("}".to_string()))));
 
             mod_interior.push(
                 Branch(vec!(
-                    Line("impl <T:ServerHook, U : Server + Send> FromServer<T,U> for Client {".to_string()),
+                    Line(format!("impl <{}T:ServerHook, U : Server + Send> FromServer<T,U> for Client{} \\{",
+                                 params.iter().map(|p| format!("{}, ", p)).collect::<Vec<String>>().as_slice().concat(),
+                                 client_params)),
                     Indent(box Branch( vec!(
-                        Line("fn new(_hook : Option<T>, server : Box<U>) -> Client {".to_string()),
+                        Line(format!("fn new(_hook : Option<T>, server : Box<U>) -> Client{} \\{", client_params)),
                         Indent(
                             box Line("Client { client : ServerHook::new_client(None::<T>, box ServerDispatch { server : server})}".to_string())),
                         Line("}".to_string())))),
@@ -1313,15 +2054,15 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
 
             mod_interior.push(
                     Branch(vec!(
-                        Line("impl Clone for Client {".to_string()),
-                        Indent(box Line("fn clone(&self) -> Client {".to_string())),
+                        Line(format!("{} Clone for Client{} \\{", client_impl, client_params)),
+                        Indent(box Line(format!("fn clone(&self) -> Client{} \\{", client_params))),
                         Indent(box Indent(box Line("Client { client : capability::Client::new(self.client.hook.copy()) }".to_string()))),
                         Indent(box Line("}".to_string())),
                         Line("}".to_string()))));
 
 
             mod_interior.push(
-                Branch(vec!(Line("impl Client {".to_string()),
+                Branch(vec!(Line(format!("{} Client{} \\{", client_impl, client_params)),
                             Indent(box Branch(client_impl_interior)),
                             Line("}".to_string()))));
 
@@ -1372,7 +2113,7 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
             let names = scope_map.get(&node_id);
             let styled_name = camel_to_upper_case(names.last().unwrap().as_slice());
 
-            let (typ, txt) = match tuple_option(c.get_type().which(), c.get_value().which()) {
+            let (typ, txt) = match tuple_option(try!(c.get_type()).which(), try!(c.get_value()).which()) {
                 Some((Type::Void(()), Value::Void(()))) => ("()".to_string(), "()".to_string()),
                 Some((Type::Bool(()), Value::Bool(b))) => ("bool".to_string(), b.to_str()),
                 Some((Type::Int8(()), Value::Int8(i))) => ("i8".to_string(), i.to_str()),
@@ -1388,12 +2129,36 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
                 Some((Type::Float32(()), Value::Float32(f))) => ("f32".to_string(), format!("{}f32", f.to_str())),
                 Some((Type::Float64(()), Value::Float64(f))) => ("f64".to_string(), format!("{}f64", f.to_str())),
 
-                Some((Type::Text(()), Value::Text(_t))) => { fail!() }
-                Some((Type::Data(()), Value::Data(_d))) => { fail!() }
-                Some((Type::List(_t), Value::List(_p))) => { fail!() }
-                Some((Type::Struct(_t), Value::Struct(_p))) => { fail!() }
-                Some((Type::Interface(_t), Value::Interface(()))) => { fail!() }
-                Some((Type::AnyPointer(()), Value::AnyPointer(_pr))) => { fail!() }
+                Some((Type::Text(()), Value::Text(t))) => {
+                    let t = try!(t);
+                    let bytes = try!(encode_words((t.len() + 1 + 7) / 8, |root| { root.set_as_text(t); Ok(()) }));
+                    output.push(try!(pointer_constant_text(node_map, scope_map, names.last().unwrap().as_slice(),
+                                                           try!(c.get_type()), bytes.as_slice())));
+                    return Ok(Branch(output));
+                }
+                Some((Type::Data(()), Value::Data(d))) => {
+                    let d = try!(d);
+                    let bytes = try!(encode_words((d.len() + 7) / 8, |root| { root.set_as_data(d); Ok(()) }));
+                    output.push(try!(pointer_constant_text(node_map, scope_map, names.last().unwrap().as_slice(),
+                                                           try!(c.get_type()), bytes.as_slice())));
+                    return Ok(Branch(output));
+                }
+                Some((Type::List(_), Value::List(p))) |
+                Some((Type::Struct(_), Value::Struct(p))) |
+                Some((Type::AnyPointer(_), Value::AnyPointer(p))) => {
+                    let bytes = try!(encode_default_words(p));
+                    output.push(try!(pointer_constant_text(node_map, scope_map, names.last().unwrap().as_slice(),
+                                                           try!(c.get_type()), bytes.as_slice())));
+                    return Ok(Branch(output));
+                }
+                Some((Type::Interface(_), Value::Interface(()))) => {
+                    //# The only possible value is null, so the accessor
+                    //# always fails with NullCapabilityPointer.
+                    let bytes = try!(encode_words(0, |_root| Ok(())));
+                    output.push(try!(pointer_constant_text(node_map, scope_map, names.last().unwrap().as_slice(),
+                                                           try!(c.get_type()), bytes.as_slice())));
+                    return Ok(Branch(output));
+                }
                 None => { fail!("unrecognized type") }
                 _ => { fail!("type does not match value") }
             };
@@ -1419,11 +2184,15 @@ fn generate_node(node_map : &collections::hashmap::HashMap<u64, schema_capnp::No
         None => ()
     }
 
-    Branch(output)
+    Ok(Branch(output))
 }
 
 
 
+fn to_io_error(error : capnp::Error) -> std::io::IoError {
+    std::io::IoError { kind : std::io::InvalidInput, desc : error.description(), detail : None }
+}
+
 pub fn main() -> std::io::IoResult<()> {
     use std::io::{Writer, File, Truncate, Write};
     use capnp::serialize;
@@ -1433,31 +2202,33 @@ pub fn main() -> std::io::IoResult<()> {
 
     let message = try!(serialize::new_reader(&mut inp, capnp::ReaderOptions::new()));
 
-    let request : schema_capnp::CodeGeneratorRequest::Reader = message.get_root();
+    let request : schema_capnp::CodeGeneratorRequest::Reader =
+        try!(message.get_root().map_err(to_io_error));
 
     let mut node_map = collections::hashmap::HashMap::<u64, schema_capnp::Node::Reader>::new();
     let mut scope_map = collections::hashmap::HashMap::<u64, Vec<String>>::new();
 
-    let nodes = request.get_nodes();
+    let nodes = try!(request.get_nodes().map_err(to_io_error));
     for ii in range(0, nodes.size()) {
         node_map.insert(nodes[ii].get_id(), nodes[ii]);
     }
 
-    let files = request.get_requested_files();
+    let files = try!(request.get_requested_files().map_err(to_io_error));
 
     for ii in range(0, files.size()) {
         let requested_file = files[ii];
         let id = requested_file.get_id();
-        let mut filepath = std::path::Path::new(requested_file.get_filename());
+        let mut filepath = std::path::Path::new(try!(requested_file.get_filename().map_err(to_io_error)));
 
 
-        let imports = requested_file.get_imports();
+        let imports = try!(requested_file.get_imports().map_err(to_io_error));
         for jj in range(0, imports.size()) {
             let import = imports[jj];
-            let importpath = std::path::Path::new(import.get_name());
+            let importpath = std::path::Path::new(try!(import.get_name().map_err(to_io_error)));
             let root_name : String = format!("::{}_capnp",
                                                importpath.filestem_str().unwrap().replace("-", "_"));
-            populate_scope_map(&node_map, &mut scope_map, vec!(root_name), import.get_id());
+            try!(populate_scope_map(&node_map, &mut scope_map, vec!(root_name), import.get_id())
+                 .map_err(to_io_error));
         }
 
         let root_name : String = format!("{}_capnp",
@@ -1467,12 +2238,12 @@ pub fn main() -> std::io::IoResult<()> {
 
         let root_mod = format!("::{}", root_name);
 
-        populate_scope_map(&node_map, &mut scope_map, vec!(root_mod), id);
+        try!(populate_scope_map(&node_map, &mut scope_map, vec!(root_mod), id).map_err(to_io_error));
 
         let lines = Branch(vec!(Line("#![allow(unused_imports)]".to_string()),
                                 Line("#![allow(dead_code)]".to_string()),
-                                generate_node(&node_map, &scope_map,
-                                              id, root_name.as_slice())));
+                                try!(generate_node(&node_map, &scope_map,
+                                                   id, root_name.as_slice()).map_err(to_io_error))));
 
         let text = stringify(&lines);
 
@@ -12,7 +12,6 @@
 extern crate collections;
 extern crate capnp;
 
-pub mod schema_capnp;
 pub mod codegen;
 
 pub fn main() {
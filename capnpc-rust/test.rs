@@ -7,6 +7,7 @@
 #![crate_type = "bin"]
 
 extern crate capnp;
+extern crate collections;
 
 pub mod test_capnp;
 
@@ -61,17 +62,17 @@ mod tests {
 
 
         let testPrimListReader = testPrimList.as_reader();
-        let uint8List = testPrimListReader.get_uint8_list();
+        let uint8List = testPrimListReader.get_uint8_list().unwrap();
         for i in range(0, uint8List.size()) {
             assert_eq!(uint8List[i], i as u8);
         }
-        let uint64List = testPrimListReader.get_uint64_list();
+        let uint64List = testPrimListReader.get_uint64_list().unwrap();
         for i in range(0, uint64List.size()) {
             assert_eq!(uint64List[i], i as u64);
         }
 
         assert_eq!(testPrimListReader.has_bool_list(), true);
-        let boolList = testPrimListReader.get_bool_list();
+        let boolList = testPrimListReader.get_bool_list().unwrap();
         assert!(boolList[0]);
         assert!(boolList[1]);
         assert!(boolList[2]);
@@ -89,7 +90,7 @@ mod tests {
         assert!(!boolList[63]);
         assert!(boolList[64]);
 
-        assert_eq!(testPrimListReader.get_void_list().size(), 1025);
+        assert_eq!(testPrimListReader.get_void_list().unwrap().size(), 1025);
     }
 
     #[test]
@@ -102,14 +103,14 @@ mod tests {
         let test_struct_list = message.init_root::<TestStructList::Builder>();
 
         test_struct_list.init_struct_list(4);
-        let struct_list = test_struct_list.get_struct_list();
+        let struct_list = test_struct_list.get_struct_list().unwrap();
         struct_list[0].init_uint8_list(1).set(0, 5u8);
 
         // why does the next line pass the typechecker?
 //        struct_list[0].get_uint8_list()[0] = 6u8;
         {
             let reader = test_struct_list.as_reader();
-            assert_eq!(reader.get_struct_list()[0].get_uint8_list()[0], 5u8);
+            assert_eq!(reader.get_struct_list().unwrap()[0].get_uint8_list().unwrap()[0], 5u8);
         }
     }
 
@@ -133,33 +134,33 @@ mod tests {
         assert_eq!(test_blob_reader.has_text_field(), true);
         assert_eq!(test_blob_reader.has_data_field(), true);
 
-        assert_eq!(test_blob_reader.get_text_field(), "abcdefghi");
-        assert!(test_blob_reader.get_data_field() == [0u8, 1u8, 2u8, 3u8, 4u8]);
+        assert_eq!(test_blob_reader.get_text_field().unwrap(), "abcdefghi");
+        assert!(test_blob_reader.get_data_field().unwrap() == [0u8, 1u8, 2u8, 3u8, 4u8]);
 
         let text_builder = test_blob.init_text_field(10);
-        assert_eq!(test_blob.as_reader().get_text_field(),
+        assert_eq!(test_blob.as_reader().get_text_field().unwrap(),
                    "\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00");
         let mut writer = std::io::BufWriter::new(text_builder.as_mut_bytes());
         writer.write("aabbccddee".as_bytes()).unwrap();
 
         let data_builder = test_blob.init_data_field(7);
-        assert!(test_blob.as_reader().get_data_field() ==
+        assert!(test_blob.as_reader().get_data_field().unwrap() ==
                 [0u8,0u8,0u8,0u8,0u8,0u8,0u8]);
         for c in data_builder.mut_iter() {
             *c = 5;
         }
         data_builder[0] = 4u8;
 
-        assert_eq!(test_blob.as_reader().get_text_field(), "aabbccddee");
-        assert!(test_blob.as_reader().get_data_field() == [4u8,5u8,5u8,5u8,5u8,5u8,5u8]);
+        assert_eq!(test_blob.as_reader().get_text_field().unwrap(), "aabbccddee");
+        assert!(test_blob.as_reader().get_data_field().unwrap() == [4u8,5u8,5u8,5u8,5u8,5u8,5u8]);
 
-        let bytes = test_blob.get_text_field().as_mut_bytes();
+        let bytes = test_blob.get_text_field().unwrap().as_mut_bytes();
         bytes[4] = 'z' as u8;
         bytes[5] = 'z' as u8;
-        assert_eq!(test_blob.as_reader().get_text_field(), "aabbzzddee");
+        assert_eq!(test_blob.as_reader().get_text_field().unwrap(), "aabbzzddee");
 
-        test_blob.get_data_field()[2] = 10;
-        assert!(test_blob.as_reader().get_data_field() == [4u8,5u8,10u8,5u8,5u8,5u8,5u8]);
+        test_blob.get_data_field().unwrap()[2] = 10;
+        assert!(test_blob.as_reader().get_data_field().unwrap() == [4u8,5u8,10u8,5u8,5u8,5u8,5u8]);
     }
 
 
@@ -192,7 +193,7 @@ mod tests {
         assert_eq!(bigStructReader.get_int8_field(), -128);
         assert_eq!(bigStructReader.get_int32_field(), 1009);
 
-        let innerReader = bigStructReader.get_struct_field();
+        let innerReader = bigStructReader.get_struct_field().unwrap();
         assert!(!innerReader.get_bool_field_a());
         assert!(innerReader.get_bool_field_b());
         assert_eq!(innerReader.get_float64_field(), 0.1234567);
@@ -263,7 +264,7 @@ mod tests {
 
 
         let complex_list_reader = test_complex_list.as_reader();
-        let enumListReader = complex_list_reader.get_enum_list();
+        let enumListReader = complex_list_reader.get_enum_list().unwrap();
         for i in range::<uint>(0,10) {
             assert!(enumListReader[i] == Some(AnEnum::Qux));
         }
@@ -271,41 +272,41 @@ mod tests {
             assert!(enumListReader[i] == Some(AnEnum::Bar));
         }
 
-        let text_list = complex_list_reader.get_text_list();
+        let text_list = complex_list_reader.get_text_list().unwrap();
         assert_eq!(text_list.size(), 2);
-        assert_eq!(text_list[0], "garply");
-        assert_eq!(text_list[1], "foo");
+        assert_eq!(text_list.get(0).unwrap(), "garply");
+        assert_eq!(text_list.get(1).unwrap(), "foo");
 
-        let data_list = complex_list_reader.get_data_list();
+        let data_list = complex_list_reader.get_data_list().unwrap();
         assert_eq!(data_list.size(), 2);
-        assert!(data_list[0] == [0u8, 1u8, 2u8]);
-        assert!(data_list[1] == [255u8, 254u8, 253u8]);
+        assert!(data_list.get(0).unwrap() == [0u8, 1u8, 2u8]);
+        assert!(data_list.get(1).unwrap() == [255u8, 254u8, 253u8]);
 
-        let prim_list_list = complex_list_reader.get_prim_list_list();
+        let prim_list_list = complex_list_reader.get_prim_list_list().unwrap();
         assert_eq!(prim_list_list.size(), 2);
-        assert_eq!(prim_list_list[0].size(), 3);
-        assert!(prim_list_list[0][0] == 5);
-        assert!(prim_list_list[0][1] == 6);
-        assert!(prim_list_list[0][2] == 7);
-        assert!(prim_list_list[1][0] == -1);
-
-        let prim_list_list_list = complex_list_reader.get_prim_list_list_list();
-        assert!(prim_list_list_list[0][0][0] == 0);
-        assert!(prim_list_list_list[0][0][1] == 1);
-        assert!(prim_list_list_list[0][1][0] == 255);
-        assert!(prim_list_list_list[1][0][0] == 10);
-        assert!(prim_list_list_list[1][0][1] == 9);
-        assert!(prim_list_list_list[1][0][2] == 8);
-
-        let enum_list_list = complex_list_reader.get_enum_list_list();
-        assert!(enum_list_list[0][0] == Some(AnEnum::Bar));
-        assert!(enum_list_list[1][0] == Some(AnEnum::Foo));
-        assert!(enum_list_list[1][1] == Some(AnEnum::Qux));
-
-        assert!(complex_list_reader.get_text_list_list()[0][0] == "abc");
-        assert!(complex_list_reader.get_data_list_list()[0][0] == [255, 254, 253]);
-
-        assert!(complex_list_reader.get_struct_list_list()[0][0].get_int8_field() == -1);
+        assert_eq!(prim_list_list.get(0).unwrap().size(), 3);
+        assert!(prim_list_list.get(0).unwrap()[0] == 5);
+        assert!(prim_list_list.get(0).unwrap()[1] == 6);
+        assert!(prim_list_list.get(0).unwrap()[2] == 7);
+        assert!(prim_list_list.get(1).unwrap()[0] == -1);
+
+        let prim_list_list_list = complex_list_reader.get_prim_list_list_list().unwrap();
+        assert!(prim_list_list_list.get(0).unwrap().get(0).unwrap()[0] == 0);
+        assert!(prim_list_list_list.get(0).unwrap().get(0).unwrap()[1] == 1);
+        assert!(prim_list_list_list.get(0).unwrap().get(1).unwrap()[0] == 255);
+        assert!(prim_list_list_list.get(1).unwrap().get(0).unwrap()[0] == 10);
+        assert!(prim_list_list_list.get(1).unwrap().get(0).unwrap()[1] == 9);
+        assert!(prim_list_list_list.get(1).unwrap().get(0).unwrap()[2] == 8);
+
+        let enum_list_list = complex_list_reader.get_enum_list_list().unwrap();
+        assert!(enum_list_list.get(0).unwrap()[0] == Some(AnEnum::Bar));
+        assert!(enum_list_list.get(1).unwrap()[0] == Some(AnEnum::Foo));
+        assert!(enum_list_list.get(1).unwrap()[1] == Some(AnEnum::Qux));
+
+        assert!(complex_list_reader.get_text_list_list().unwrap().get(0).unwrap().get(0).unwrap() == "abc");
+        assert!(complex_list_reader.get_data_list_list().unwrap().get(0).unwrap().get(0).unwrap() == [255, 254, 253]);
+
+        assert!(complex_list_reader.get_struct_list_list().unwrap().get(0).unwrap()[0].get_int8_field() == -1);
     }
 
     #[test]
@@ -334,6 +335,52 @@ mod tests {
         assert_eq!(test_defaults.get_int8_field(), 63);
     }
 
+    #[test]
+    fn test_pointer_defaults() {
+        use test_capnp::TestDefaults;
+
+        let mut message = MallocMessageBuilder::new_default();
+        let test_defaults = message.init_root::<TestDefaults::Builder>();
+
+        {
+            let reader = test_defaults.as_reader();
+            let struct_field = reader.get_struct_field().unwrap();
+            assert_eq!(struct_field.get_uint32_field(), 7);
+            assert_eq!(struct_field.get_float64_field(), 2.5);
+            assert_eq!(struct_field.get_bool_field_b(), true);
+
+            let int32_list = reader.get_int32_list().unwrap();
+            assert_eq!(int32_list.size(), 3);
+            assert_eq!(int32_list[0], 1);
+            assert_eq!(int32_list[1], -2);
+            assert_eq!(int32_list[2], 300000);
+
+            let text_list = reader.get_text_list().unwrap();
+            assert_eq!(text_list.size(), 2);
+            assert!(text_list.get(0).unwrap() == "plugh");
+            assert!(text_list.get(1).unwrap() == "xyzzy");
+
+            assert_eq!(reader.get_struct_list().unwrap()[1].get_uint32_field(), 2);
+            assert!(!reader.has_struct_field());
+        }
+
+        // Getting a builder copies the default into the message.
+        let struct_field = test_defaults.get_struct_field().unwrap();
+        assert_eq!(struct_field.get_uint32_field(), 7);
+        struct_field.set_uint32_field(8);
+        assert!(test_defaults.has_struct_field());
+        assert_eq!(test_defaults.get_struct_field().unwrap().get_uint32_field(), 8);
+
+        let int32_list = test_defaults.get_int32_list().unwrap();
+        assert_eq!(int32_list[1], -2);
+        int32_list.set(1, 42);
+        assert_eq!(test_defaults.as_reader().get_int32_list().unwrap()[1], 42);
+
+        let struct_list = test_defaults.get_struct_list().unwrap();
+        assert_eq!(struct_list.size(), 2);
+        assert_eq!(struct_list[0].get_uint32_field(), 1);
+    }
+
     #[test]
     fn test_any_pointer() {
         use test_capnp::{TestAnyPointer, TestEmptyStruct};
@@ -346,19 +393,347 @@ mod tests {
 
         {
             let reader = test_any_pointer.as_reader();
-            assert_eq!(reader.get_any_pointer_field().get_as_text(), "xyzzy");
+            assert_eq!(reader.get_any_pointer_field().get_as_text().unwrap(), "xyzzy");
         }
 
         any_pointer.init_as_struct::<TestEmptyStruct::Builder>();
-        any_pointer.get_as_struct::<TestEmptyStruct::Builder>();
+        any_pointer.get_as_struct::<TestEmptyStruct::Builder>().unwrap();
 
         {
             let reader = test_any_pointer.as_reader();
-            reader.get_any_pointer_field().get_as_struct::<TestEmptyStruct::Reader>();
+            reader.get_any_pointer_field().get_as_struct::<TestEmptyStruct::Reader>().unwrap();
         }
 
     }
 
+    #[test]
+    fn test_any_pointer_list() {
+        use test_capnp::{TestAnyPointer, TestEmptyStruct};
+
+        let mut message = MallocMessageBuilder::new_default();
+        let test_any_pointer = message.init_root::<TestAnyPointer::Builder>();
+
+        let list = test_any_pointer.init_any_pointer_list(3);
+        list.get(0).set_as_text("foo");
+        list.get(1).init_as_struct::<TestEmptyStruct::Builder>();
+
+        let capabilities = test_any_pointer.init_capability_list(2);
+        assert_eq!(capabilities.size(), 2);
+
+        let reader = test_any_pointer.as_reader();
+        let list = reader.get_any_pointer_list().unwrap();
+        assert_eq!(list.size(), 3);
+        assert_eq!(list.get(0).get_as_text().unwrap(), "foo");
+        list.get(1).get_as_struct::<TestEmptyStruct::Reader>().unwrap();
+        assert!(list.get(2).is_null());
+        assert_eq!(reader.get_capability_list().unwrap().size(), 2);
+    }
+
+    #[test]
+    fn test_orphans() {
+        use capnp::{Text, PrimitiveList};
+        use test_capnp::{TestBigStruct, TestBlob, TestPrimList};
+
+        let mut message = MallocMessageBuilder::new_default();
+        let root = message.get_root_internal();
+        let orphanage = root.get_orphanage();
+        let big_struct = root.init_as_struct::<TestBigStruct::Builder>();
+
+        // Move a struct from one field to another without copying it.
+        big_struct.init_struct_field().set_uint32_field(17);
+        let mut orphan = big_struct.disown_struct_field();
+        assert!(!big_struct.has_struct_field());
+        assert_eq!(orphan.get().unwrap().get_uint32_field(), 17);
+        big_struct.adopt_another_struct_field(orphan);
+        assert_eq!(big_struct.get_another_struct_field().unwrap().get_uint32_field(), 17);
+
+        // Create detached objects and adopt them later.
+        let mut inner = orphanage.new_struct_orphan::<TestBigStruct::Inner::Builder>();
+        inner.get().unwrap().set_uint64_field(123);
+        big_struct.adopt_struct_field(inner);
+        assert_eq!(big_struct.as_reader().get_struct_field().unwrap().get_uint64_field(), 123);
+
+        // An orphan that is dropped without being adopted is zeroed, and
+        // releases its borrow of the message.
+        let mut message2 = MallocMessageBuilder::new_default();
+        {
+            let orphanage = message2.get_orphanage();
+            let mut text = orphanage.new_orphan::<Text::Builder>(5);
+            text.get().unwrap().as_mut_bytes().copy_memory("bytes".as_bytes());
+        }
+        let root2 = message2.get_root_internal();
+        let orphanage2 = root2.get_orphanage();
+        let blob = root2.init_as_struct::<TestBlob::Builder>();
+        let mut text = orphanage2.new_orphan::<Text::Builder>(5);
+        text.get().unwrap().as_mut_bytes().copy_memory("hello".as_bytes());
+        blob.adopt_text_field(text);
+        assert_eq!(blob.as_reader().get_text_field().unwrap(), "hello");
+
+        let mut message3 = MallocMessageBuilder::new_default();
+        let root3 = message3.get_root_internal();
+        let orphanage3 = root3.get_orphanage();
+        let prim_list = root3.init_as_struct::<TestPrimList::Builder>();
+        let mut list = orphanage3.new_orphan::<PrimitiveList::Builder<u32>>(3);
+        for i in range(0u, 3) {
+            list.get().unwrap().set(i, i as u32 * 10);
+        }
+        prim_list.adopt_uint32_list(list);
+        let mut list = prim_list.disown_uint32_list();
+        assert!(!prim_list.has_uint32_list());
+        assert_eq!(list.get().unwrap()[2], 20);
+    }
+
+    #[test]
+    fn test_equality_and_hashing() {
+        use collections::hashmap::HashSet;
+        use test_capnp::TestBigStruct;
+
+        let mut message1 = MallocMessageBuilder::new_default();
+        let big_struct1 = message1.init_root::<TestBigStruct::Builder>();
+        big_struct1.init_struct_field().set_uint32_field(17);
+        big_struct1.init_another_struct_field().set_uint32_field(17);
+
+        let mut message2 = MallocMessageBuilder::new_default();
+        let big_struct2 = message2.init_root::<TestBigStruct::Builder>();
+        big_struct2.init_another_struct_field().set_uint32_field(17);
+        big_struct2.init_struct_field().set_uint32_field(17);
+
+        // Same contents, different layout.
+        assert!(big_struct1.as_reader() == big_struct2.as_reader());
+        assert!(big_struct1.get_struct_field().unwrap().as_reader() ==
+                big_struct1.get_another_struct_field().unwrap().as_reader());
+
+        let mut set = HashSet::new();
+        set.insert(big_struct1.as_reader());
+        assert!(set.contains(&big_struct2.as_reader()));
+
+        big_struct2.get_struct_field().unwrap().set_bool_field_c(true);
+        assert!(big_struct1.as_reader() != big_struct2.as_reader());
+        assert!(!set.contains(&big_struct2.as_reader()));
+    }
+
+    #[test]
+    fn test_hashing_truncated_message() {
+        use std::hash::hash;
+        use collections::hashmap::HashSet;
+        use capnp::message::{MessageReader, ReaderOptions, SegmentArrayMessageReader};
+        use capnp::serialize;
+        use test_capnp::TestBigStruct;
+
+        let mut message = MallocMessageBuilder::new_default();
+        {
+            let big_struct = message.init_root::<TestBigStruct::Builder>();
+            big_struct.init_struct_field().set_uint32_field(17);
+        }
+        let words = serialize::message_to_flat_words(&message);
+
+        // Drop the segment table, and the last word of the struct field.
+        let reader = SegmentArrayMessageReader::from_segments(vec!(words.slice(1, words.len() - 1)),
+                                                              ReaderOptions::new());
+        let root = reader.get_root::<TestBigStruct::Reader>().unwrap();
+        assert!(root.stable_hash().is_err());
+        assert!(root.equals(&root).is_err());
+        assert_eq!(hash(&root), hash(&0u64));
+
+        // A reader that cannot be read is still equal to itself, so it can
+        // be found again in a set, but it is not equal to a readable one.
+        assert!(root == root);
+        let mut set = HashSet::new();
+        set.insert(root);
+        assert!(set.contains(&reader.get_root::<TestBigStruct::Reader>().unwrap()));
+
+        let mut other_message = MallocMessageBuilder::new_default();
+        let other = other_message.init_root::<TestBigStruct::Builder>();
+        other.init_struct_field().set_uint32_field(17);
+        assert!(root != other.as_reader());
+        assert!(!set.contains(&other.as_reader()));
+    }
+
+    #[test]
+    fn test_builder_pool() {
+        use capnp::message::MallocMessageBuilderPool;
+        use capnp::serialize;
+        use test_capnp::TestBigStruct;
+
+        let options = *BuilderOptions::new().first_segment_words(4);
+        let mut pool = MallocMessageBuilderPool::new(options, 1);
+
+        let mut message = pool.get();
+        {
+            let big_struct = message.init_root::<TestBigStruct::Builder>();
+            big_struct.set_uint64_field(7);
+            big_struct.init_struct_field().set_uint32_field(17);
+        }
+        let segments = message.get_segments_for_output(|segments| {
+            segments.iter().map(|s| s.as_ptr()).collect::<Vec<*capnp::common::Word>>()
+        });
+        assert!(segments.len() > 1);
+
+        pool.recycle(message);
+        assert_eq!(pool.len(), 1);
+
+        // The recycled builder comes back empty, and reuses its segments.
+        let mut message = pool.get();
+        assert_eq!(pool.len(), 0);
+        message.get_segments_for_output(|segments| {
+            assert_eq!(segments[0].len(), 0);
+        });
+        {
+            let big_struct = message.init_root::<TestBigStruct::Builder>();
+            assert_eq!(big_struct.get_uint64_field(), 0);
+            assert!(!big_struct.has_struct_field());
+            big_struct.init_struct_field().set_uint32_field(18);
+        }
+        message.get_segments_for_output(|new_segments| {
+            assert_eq!(new_segments.len(), segments.len());
+            for i in range(0, segments.len()) {
+                assert!(new_segments[i].as_ptr() == segments[i]);
+            }
+        });
+
+        // A smaller message in a recycled builder serializes the same as
+        // in a new one, without the segments that it does not need.
+        pool.recycle(message);
+        let mut recycled = pool.get();
+        recycled.init_root::<TestBigStruct::Builder>().set_uint64_field(7);
+        let mut fresh = MallocMessageBuilder::new(options);
+        fresh.init_root::<TestBigStruct::Builder>().set_uint64_field(7);
+
+        let recycled_words = serialize::message_to_flat_words(&recycled);
+        let fresh_words = serialize::message_to_flat_words(&fresh);
+        assert_eq!(recycled_words.len(), fresh_words.len());
+        for i in range(0, fresh_words.len()) {
+            let a : u64 = unsafe { std::mem::transmute(recycled_words.as_slice()[i]) };
+            let b : u64 = unsafe { std::mem::transmute(fresh_words.as_slice()[i]) };
+            assert_eq!(a, b);
+        }
+        recycled.get_segments_for_output(|new_segments| {
+            assert!(new_segments.len() < segments.len());
+        });
+    }
+
+    #[test]
+    fn test_shared_memory() {
+        use capnp::common::allocate_zeroed_words;
+        use capnp::message::{MessageReader, ReaderOptions};
+        use capnp::shared_memory::{SharedMemoryMessageBuilder, read_message};
+        use test_capnp::TestBigStruct;
+
+        let mut region = allocate_zeroed_words(64);
+        {
+            let mut message = SharedMemoryMessageBuilder::new(
+                region.as_mut_slice(), 4, *BuilderOptions::new().first_segment_words(2));
+            {
+                let big_struct = message.init_root::<TestBigStruct::Builder>();
+                big_struct.set_uint64_field(7);
+                big_struct.init_struct_field().set_uint32_field(17);
+            }
+            message.get_segments_for_output(|segments| assert!(segments.len() > 1));
+            assert!(!message.overflowed());
+            message.write_header().unwrap();
+        }
+
+        let reader = read_message(region.as_slice(), ReaderOptions::new()).unwrap();
+        let big_struct = reader.get_root::<TestBigStruct::Reader>().unwrap();
+        assert_eq!(big_struct.get_uint64_field(), 7);
+        assert_eq!(big_struct.get_struct_field().unwrap().get_uint32_field(), 17);
+    }
+
+    #[test]
+    fn test_shared_memory_overflow() {
+        use capnp::common::allocate_zeroed_words;
+        use capnp::shared_memory::SharedMemoryMessageBuilder;
+        use test_capnp::TestBigStruct;
+
+        // The header only has room for one segment, and the struct does
+        // not fit in it.
+        let mut region = allocate_zeroed_words(64);
+        let mut message = SharedMemoryMessageBuilder::new(
+            region.as_mut_slice(), 1, *BuilderOptions::new().first_segment_words(2));
+
+        // The first segment is allocated up front, after the two header words.
+        assert_eq!(message.segments_remaining(), 0);
+        assert_eq!(message.words_remaining(), 64 - 2 - 2);
+        assert!(!message.overflowed());
+        {
+            let big_struct = message.init_root::<TestBigStruct::Builder>();
+            big_struct.set_uint64_field(7);
+            assert_eq!(big_struct.get_uint64_field(), 7);
+        }
+        assert_eq!(message.words_remaining(), 64 - 2 - 2);
+        assert!(message.overflowed());
+        assert!(message.write_header().is_err());
+    }
+
+    #[test]
+    fn test_compact() {
+        use test_capnp::TestBigStruct;
+
+        let mut message = MallocMessageBuilder::new(*BuilderOptions::new().first_segment_words(8));
+        {
+            let big_struct = message.init_root::<TestBigStruct::Builder>();
+            for i in range(0u32, 20) {
+                big_struct.init_struct_field().set_uint32_field(i);
+            }
+            big_struct.set_uint8_field(3);
+        }
+        let old_words = message.get_segments_for_output(|segments| {
+            segments.iter().fold(0, |total, s| total + s.len())
+        });
+
+        let mut compacted = message.compact().unwrap();
+        compacted.get_segments_for_output(|segments| {
+            assert_eq!(segments.len(), 1);
+            assert!(segments[0].len() < old_words);
+        });
+        let big_struct = compacted.get_root::<TestBigStruct::Builder>().unwrap();
+        assert_eq!(big_struct.get_uint8_field(), 3);
+        assert_eq!(big_struct.get_struct_field().unwrap().get_uint32_field(), 19);
+    }
+
+    #[test]
+    fn test_visitor() {
+        use capnp::common::{WordCount, WirePointerCount};
+        use capnp::visitor::Visitor;
+        use test_capnp::TestBlob;
+
+        struct Recorder { events : Vec<String> }
+
+        impl Visitor for Recorder {
+            fn visit_struct(&mut self, data_words : WordCount, pointer_count : WirePointerCount) {
+                self.events.push(format!("struct {} {}", data_words, pointer_count));
+            }
+            fn leave_struct(&mut self) {
+                self.events.push("end struct".to_string());
+            }
+            fn visit_text(&mut self, text : &str) {
+                self.events.push(format!("text {}", text));
+            }
+            fn visit_data(&mut self, data : &[u8]) {
+                self.events.push(format!("data {}", data.len()));
+            }
+            fn visit_far_pointer(&mut self, segment_id : u32, _double_far : bool) {
+                self.events.push(format!("far {}", segment_id));
+            }
+        }
+
+        // A one-word first segment forces every object behind a far pointer.
+        let mut message = MallocMessageBuilder::new(*BuilderOptions::new().first_segment_words(1));
+        {
+            let blob = message.init_root::<TestBlob::Builder>();
+            blob.set_text_field("abc");
+            blob.set_data_field([1u8, 2]);
+        }
+
+        let mut recorder = Recorder { events : Vec::new() };
+        message.get_root_internal().as_reader().visit(&mut recorder).unwrap();
+        let expected = ["far 1", "struct 0 2", "far 2", "text abc", "far 2", "data 2", "end struct"];
+        assert_eq!(recorder.events.len(), expected.len());
+        for i in range(0, expected.len()) {
+            assert_eq!(recorder.events.get(i).as_slice(), expected[i]);
+        }
+    }
+
     #[test]
     fn test_writable_struct_pointer() {
         use test_capnp::TestBigStruct;
@@ -371,24 +746,24 @@ mod tests {
 
         struct_field.set_uint64_field(-7);
         assert_eq!(struct_field.get_uint64_field(), -7);
-        assert_eq!(big_struct.get_struct_field().get_uint64_field(), -7);
+        assert_eq!(big_struct.get_struct_field().unwrap().get_uint64_field(), -7);
         let struct_field = big_struct.init_struct_field();
         assert_eq!(struct_field.get_uint64_field(), 0);
         assert_eq!(struct_field.get_uint32_field(), 0);
 
         // getting before init is the same as init
-        let other_struct_field = big_struct.get_another_struct_field();
+        let other_struct_field = big_struct.get_another_struct_field().unwrap();
         assert_eq!(other_struct_field.get_uint64_field(), 0);
         other_struct_field.set_uint32_field(-31);
 
         let reader = other_struct_field.as_reader();
-        big_struct.set_struct_field(reader);
-        assert_eq!(big_struct.get_struct_field().get_uint32_field(), -31);
+        big_struct.set_struct_field(reader).unwrap();
+        assert_eq!(big_struct.get_struct_field().unwrap().get_uint32_field(), -31);
         assert_eq!(other_struct_field.get_uint32_field(), -31);
         other_struct_field.set_uint32_field(42);
-        assert_eq!(big_struct.get_struct_field().get_uint32_field(), -31);
+        assert_eq!(big_struct.get_struct_field().unwrap().get_uint32_field(), -31);
         assert_eq!(other_struct_field.get_uint32_field(), 42);
-        assert_eq!(big_struct.get_another_struct_field().get_uint32_field(), 42);
+        assert_eq!(big_struct.get_another_struct_field().unwrap().get_uint32_field(), 42);
     }
 
     #[test]
@@ -419,6 +794,110 @@ mod tests {
         assert_eq!(union_struct.get_union0().has_u0f0sp(), true);
     }
 
+    #[test]
+    fn test_generics() {
+        use test_capnp::TestUseGenerics;
+
+        let mut message = MallocMessageBuilder::new_default();
+        let root = message.init_root::<TestUseGenerics::Builder>();
+        {
+            let basic = root.init_basic();
+            basic.init_foo(0).set_text_field("foo");
+            basic.init_rev().init_foo(0).set_uint8_field(3);
+            let list = basic.init_list(2);
+            list.get(1).init_bar(0).set_uint8_field(5);
+        }
+        root.init_inner().init_bar(0).set_text_field("bar");
+
+        let reader = root.as_reader();
+        let basic = reader.get_basic().unwrap();
+        assert_eq!(basic.get_foo().unwrap().get_text_field().unwrap(), "foo");
+        assert_eq!(basic.get_rev().unwrap().get_foo().unwrap().get_uint8_field(), 3);
+        let list = basic.get_list().unwrap();
+        assert_eq!(list.size(), 2);
+        assert_eq!(list.get(1).get_bar().unwrap().get_uint8_field(), 5);
+        assert!(!list.get(0).has_bar());
+        assert_eq!(reader.get_inner().unwrap().get_bar().unwrap().get_text_field().unwrap(), "bar");
+    }
+
+    #[test]
+    fn test_generic_interface() {
+        use capnp::Text;
+        use test_capnp::{TestBigStruct, TestBlob, TestGenerics, TestUseGenerics};
+
+        // The params and results of echo() on TestUseGenerics.echo, which is
+        // a TestGenerics(TestBlob, Text).Echo(TestBigStruct).
+        let mut message = MallocMessageBuilder::new_default();
+        {
+            let params = message.init_root::<TestGenerics::Echo::EchoParams::Builder<
+                    TestBlob::Builder, Text::Builder, TestBigStruct::Builder>>();
+            params.init_qux(0).set_uint32_field(17);
+            let reader = params.as_reader::<TestBlob::Reader, Text::Reader, TestBigStruct::Reader>();
+            assert_eq!(reader.get_qux().unwrap().get_uint32_field(), 17);
+        }
+
+        let mut message = MallocMessageBuilder::new_default();
+        {
+            let results = message.init_root::<TestGenerics::Echo::EchoResults::Builder<
+                    TestBlob::Builder, Text::Builder, TestBigStruct::Builder>>();
+            results.init_qux(0).set_uint32_field(18);
+            results.init_inner().init_foo(0).set_text_field("inner");
+            let reader = results.as_reader::<TestBlob::Reader, Text::Reader, TestBigStruct::Reader>();
+            assert_eq!(reader.get_qux().unwrap().get_uint32_field(), 18);
+            assert_eq!(reader.get_inner().unwrap().get_foo().unwrap().get_text_field().unwrap(), "inner");
+        }
+
+        // The field holds a typed generic client.
+        let mut message = MallocMessageBuilder::new_default();
+        let root = message.init_root::<TestUseGenerics::Builder>();
+        let echo : capnp::Result<TestGenerics::Echo::Client<TestBlob::Reader, Text::Reader,
+                                                            TestBigStruct::Reader>> =
+            root.as_reader().get_echo();
+        assert!(echo.is_err());
+    }
+
+    #[test]
+    fn test_schema() {
+        use capnp::schema_capnp::{Field, Node, Type, Value};
+        use test_capnp::{TestBlob, TestInterface};
+
+        let node = TestBlob::schema().unwrap();
+        assert!(node.get_display_name().unwrap().ends_with("TestBlob"));
+        let fields = match node.which() {
+            Some(Node::Struct(st)) => st.get_fields().unwrap(),
+            _ => fail!("expected a struct node"),
+        };
+        assert_eq!(fields.size(), 2);
+        assert_eq!(fields[1].get_name().unwrap(), "dataField");
+        match fields[1].get_ordinal().which() {
+            Some(Field::Ordinal::Explicit(1)) => {}
+            _ => fail!(),
+        }
+        match fields[0].which() {
+            Some(Field::Slot(slot)) => {
+                match slot.get_type().unwrap().which() {
+                    Some(Type::Text(())) => {}
+                    _ => fail!(),
+                }
+            }
+            _ => fail!(),
+        }
+
+        let annotations = fields[0].get_annotations().unwrap();
+        assert_eq!(annotations.size(), 1);
+        match annotations[0].get_value().unwrap().which() {
+            Some(Value::Text(t)) => assert_eq!(t.unwrap(), "a note"),
+            _ => fail!(),
+        }
+
+        match TestInterface::schema().unwrap().which() {
+            Some(Node::Interface(interface)) => {
+                assert_eq!(interface.get_methods().unwrap().size(), 4);
+            }
+            _ => fail!("expected an interface node"),
+        }
+    }
+
     #[test]
     fn test_constants() {
         use test_capnp::TestConstants;
@@ -434,6 +913,23 @@ mod tests {
         assert_eq!(TestConstants::UINT64_CONST, 12345678901234567890);
         assert_eq!(TestConstants::FLOAT32_CONST, 1234.5);
         assert_eq!(TestConstants::FLOAT64_CONST, -123e45);
+        assert_eq!(TestConstants::get_text_const().unwrap(), "foo");
+        assert_eq!(TestConstants::get_data_const().unwrap(), "bar".as_bytes());
+
+        let struct_const = TestConstants::get_struct_const().unwrap();
+        assert_eq!(struct_const.get_text_field().unwrap(), "baz");
+        assert_eq!(struct_const.get_data_field().unwrap(), "qux".as_bytes());
+
+        let uint32_list = TestConstants::get_uint32_list_const().unwrap();
+        assert_eq!(uint32_list.size(), 3);
+        assert_eq!(uint32_list[2], 3);
+
+        let text_list = TestConstants::get_text_list_const().unwrap();
+        assert_eq!(text_list.size(), 2);
+        assert_eq!(text_list.get(1).unwrap(), "xyzzy");
+
+        assert_eq!(TestConstants::get_interface_const().err(),
+                   Some(capnp::error::NullCapabilityPointer));
     }
 
     #[test]
@@ -444,8 +940,8 @@ mod tests {
         let mut message2 = MallocMessageBuilder::new_default();
         let struct1 = message1.init_root::<TestBigStruct::Builder>();
         struct1.set_uint8_field(3);
-        message2.set_root(&struct1.as_reader());
-        let struct2 = message2.get_root::<TestBigStruct::Builder>();
+        message2.set_root(&struct1.as_reader()).unwrap();
+        let struct2 = message2.get_root::<TestBigStruct::Builder>().unwrap();
 
         assert_eq!(struct2.get_uint8_field(), 3u8);
     }
@@ -48,13 +48,13 @@ pub mod addressbook {
     pub fn print_address_book() -> IoResult<()> {
 
         let message_reader = try!(serialize_packed::new_reader_unbuffered(&mut stdin(), ReaderOptions::new()));
-        let address_book = message_reader.get_root::<AddressBook::Reader>();
-        let people = address_book.get_people();
+        let address_book = message_reader.get_root::<AddressBook::Reader>().unwrap();
+        let people = address_book.get_people().unwrap();
 
         for i in range(0, people.size()) {
             let person = people[i];
-            println!("{}: {}", person.get_name(), person.get_email());
-            let phones = person.get_phones();
+            println!("{}: {}", person.get_name().unwrap(), person.get_email().unwrap());
+            let phones = person.get_phones().unwrap();
             for j in range(0, phones.size()) {
                 let phone = phones[j];
                 let type_name = match phone.get_type() {
@@ -63,17 +63,17 @@ pub mod addressbook {
                     Some(Person::PhoneNumber::Type::Work) => {"work"}
                     None => {"UNKNOWN"}
                 };
-                println!("  {} phone: {}", type_name, phone.get_number());
+                println!("  {} phone: {}", type_name, phone.get_number().unwrap());
             }
             match person.get_employment().which() {
                 Some(Person::Employment::Unemployed(())) => {
                     println!("  unemployed");
                 }
                 Some(Person::Employment::Employer(employer)) => {
-                    println!("  employer: {}", employer);
+                    println!("  employer: {}", employer.unwrap());
                 }
                 Some(Person::Employment::School(school)) => {
-                    println!("  student at: {}", school);
+                    println!("  student at: {}", school.unwrap());
                 }
                 Some(Person::Employment::SelfEmployed(())) => {
                     println!("  self-employed");
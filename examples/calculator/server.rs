@@ -29,7 +29,10 @@ impl ValueImpl {
 
 impl Calculator::Value::Server for ValueImpl {
     fn read(&mut self, mut context : Calculator::Value::ReadContext) {
-        let (_, results) = context.get();
+        let (_, results) = match context.get() {
+            Ok(v) => v,
+            Err(_) => return context.fail(),
+        };
         results.set_value(self.value);
         context.done();
     }
@@ -44,6 +47,7 @@ fn evaluate_impl(
             Ok(v)
         },
         Some(Calculator::Expression::PreviousResult(p)) => {
+            let p = try!(p.map_err(|e| e.to_string()));
             Ok(try!(p.read_request().send().wait()).get_value())
         }
         Some(Calculator::Expression::Parameter(p)) => {
@@ -55,8 +59,8 @@ fn evaluate_impl(
             }
         }
         Some(Calculator::Expression::Call(call)) => {
-            let func = call.get_function();
-            let call_params = call.get_params();
+            let func = try!(call.get_function().map_err(|e| e.to_string()));
+            let call_params = try!(call.get_params().map_err(|e| e.to_string()));
             let mut param_values = Vec::new();
             for ii in range(0, call_params.size()) {
                 let x = try!(evaluate_impl(call_params[ii], params));
@@ -81,22 +85,29 @@ struct FunctionImpl {
 impl FunctionImpl {
     fn new(param_count : uint, body : Calculator::Expression::Reader) -> FunctionImpl {
         let mut result = FunctionImpl { param_count : param_count, body : MallocMessageBuilder::new_default() };
-        result.body.set_root(&body);
+        result.body.set_root(&body).unwrap();
         result
     }
 }
 
 impl Calculator::Function::Server for FunctionImpl {
     fn call(&mut self, mut context : Calculator::Function::CallContext) {
-        let (params, results) = context.get();
-        if params.get_params().size() != self.param_count{
+        let (params, results) = match context.get() {
+            Ok(v) => v,
+            Err(_) => return context.fail(),
+        };
+        let params = match params.get_params() {
+            Ok(p) => p,
+            Err(_) => return context.fail(),
+        };
+        if params.size() != self.param_count{
             //"Wrong number of parameters."
             return context.fail();
         };
 
         {
-            let expression = self.body.get_root::<Calculator::Expression::Builder>().as_reader();
-            match evaluate_impl(expression, Some(params.get_params())) {
+            let expression = self.body.get_root::<Calculator::Expression::Builder>().unwrap().as_reader();
+            match evaluate_impl(expression, Some(params)) {
                 Ok(r) => results.set_value(r),
                 Err(_) => return context.fail(),
             }
@@ -112,8 +123,14 @@ pub struct OperatorImpl {
 
 impl Calculator::Function::Server for OperatorImpl {
     fn call(&mut self, mut context : Calculator::Function::CallContext) {
-        let (params, results) = context.get();
-        let params = params.get_params();
+        let (params, results) = match context.get() {
+            Ok(v) => v,
+            Err(_) => return context.fail(),
+        };
+        let params = match params.get_params() {
+            Ok(p) => p,
+            Err(_) => return context.fail(),
+        };
         if params.size() != 2 {
             //"Wrong number of parameters: {}", params.size()
             return context.fail();
@@ -136,8 +153,11 @@ struct CalculatorImpl;
 
 impl Calculator::Server for CalculatorImpl {
     fn evaluate(&mut self, mut context : Calculator::EvaluateContext) {
-        let (params, results) = context.get();
-        match evaluate_impl(params.get_expression(), None) {
+        let (params, results) = match context.get() {
+            Ok(v) => v,
+            Err(_) => return context.fail(),
+        };
+        match params.get_expression().map_err(|e| e.to_string()).and_then(|e| evaluate_impl(e, None)) {
             Ok(r) => {
                 results.set_value(
                     FromServer::new(
@@ -149,15 +169,25 @@ impl Calculator::Server for CalculatorImpl {
         context.done();
     }
     fn def_function(&mut self, mut context : Calculator::DefFunctionContext) {
-        let (params, results) = context.get();
+        let (params, results) = match context.get() {
+            Ok(v) => v,
+            Err(_) => return context.fail(),
+        };
+        let body = match params.get_body() {
+            Ok(b) => b,
+            Err(_) => return context.fail(),
+        };
         results.set_func(
             FromServer::new(
                 None::<LocalClient>,
-                box FunctionImpl::new(params.get_param_count() as uint, params.get_body())));
+                box FunctionImpl::new(params.get_param_count() as uint, body)));
         context.done();
     }
     fn get_operator(&mut self, mut context : Calculator::GetOperatorContext) {
-        let (params, results) = context.get();
+        let (params, results) = match context.get() {
+            Ok(v) => v,
+            Err(_) => return context.fail(),
+        };
         results.set_func(
             match params.get_op() {
                 Some(op) => {
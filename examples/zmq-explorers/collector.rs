@@ -56,7 +56,7 @@ pub fn main() -> Result<(), zmq::Error> {
             let reader = capnp::message::SegmentArrayMessageReader::new(
                 segments,
                 capnp::message::DefaultReaderOptions);
-            let obs = reader.get_root::<Observation::Reader>();
+            let obs = reader.get_root::<Observation::Reader>().unwrap();
 
             if obs.get_x() >= 1.0 || obs.get_x() < 0.0 ||
                 obs.get_y() >= 1.0 || obs.get_y() < 0.0 {
@@ -67,7 +67,7 @@ pub fn main() -> Result<(), zmq::Error> {
             match obs.get_diagnostic().which() {
                 Some(Observation::Diagnostic::Ok(())) => {}
                 Some(Observation::Diagnostic::Warning(s)) => {
-                    println!("received diagnostic: {}", s);
+                    println!("received diagnostic: {}", s.unwrap());
                 }
                 None => {}
             }
@@ -78,7 +78,7 @@ pub fn main() -> Result<(), zmq::Error> {
             grid.set_latest_timestamp(obs.get_timestamp());
             grid.set_number_of_updates(grid.get_number_of_updates() + 1);
 
-            let cell = cells[x][y];
+            let cell = cells.get(x).unwrap()[y];
             cell.set_latest_timestamp(obs.get_timestamp());
 
             let n = cell.get_number_of_updates();
@@ -15,21 +15,21 @@ fn write_ppm(path : &std::path::Path, grid : Grid::Reader, mode : OutputMode) ->
     let mut buffered = std::io::BufferedWriter::new(writer);
     try!(buffered.write(bytes!("P6\n")));
 
-    let cells = grid.get_cells();
+    let cells = grid.get_cells().unwrap();
     let width = cells.size();
     assert!(width > 0);
-    let height = cells[0].size();
+    let height = cells.get(0).unwrap().size();
 
     try!(buffered.write(format!("{} {}\n", width, height).as_bytes()));
     try!(buffered.write(bytes!("255\n")));
 
     for x in range(0, width) {
-        assert!(cells[x].size() == height);
+        assert!(cells.get(x).unwrap().size() == height);
     }
 
     for y in range(0, height) {
         for x in range(0, width) {
-            let cell = cells[x][y];
+            let cell = cells.get(x).unwrap()[y];
 
             match mode {
                 Colors => {
@@ -78,7 +78,7 @@ pub fn main() -> Result<(), zmq::Error> {
         let segments = capnp_zmq::frames_to_segments(frames);
         let reader = capnp::message::SegmentArrayMessageReader::new(segments,
                                                                     capnp::message::DefaultReaderOptions);
-        let grid = reader.get_root::<Grid::Reader>();
+        let grid = reader.get_root::<Grid::Reader>().unwrap();
 
         println!("{}", grid.get_latest_timestamp());
 